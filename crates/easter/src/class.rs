@@ -0,0 +1,65 @@
+use joker::track::*;
+
+use id::Id;
+use expr::Expr;
+use obj::PropKey;
+use fun::Fun;
+use stmt::Script;
+use ty::{Annot, Type, TypeParam};
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Class {
+    pub location: Option<Span>,
+    pub decorators: Vec<Decorator>,
+    pub id: Option<Id>,
+    // The type parameters and `implements` clause are only parsed with the
+    // type annotation syntax extension and are otherwise left empty.
+    pub type_params: Vec<TypeParam>,
+    pub extends: Option<Box<Expr>>,
+    pub implements: Vec<Type>,
+    pub body: Vec<ClassMember>
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum ClassMember {
+    Method(Method),
+    Field(Field),
+    StaticBlock(Option<Span>, Script),
+    // The tokens skipped over by a parser recovering from a syntax error.
+    Error(Option<Span>)
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Method {
+    pub location: Option<Span>,
+    pub decorators: Vec<Decorator>,
+    pub is_static: bool,
+    pub kind: MethodKind,
+    pub key: PropKey,
+    pub fun: Fun
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Field {
+    pub location: Option<Span>,
+    pub decorators: Vec<Decorator>,
+    pub is_static: bool,
+    pub key: PropKey,
+    pub annot: Option<Annot>,
+    pub value: Option<Expr>
+}
+
+// `@expr`, which is only parsed with the decorators syntax extension.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Decorator {
+    pub location: Option<Span>,
+    pub expr: Expr
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Untrack)]
+pub enum MethodKind {
+    Constructor,
+    Method,
+    Get,
+    Set
+}
@@ -6,7 +6,8 @@ use joker::track::{Span};
 #[derive(Debug, PartialEq, Clone)]
 pub enum Error {
     InvalidAssignTarget(Option<Span>),
-    InvalidPropPatt(Option<Span>)
+    InvalidPropPatt(Option<Span>),
+    TrailingCommaAfterRest(Option<Span>)
 }
 
 impl Display for Error {
@@ -20,6 +21,7 @@ impl StdError for Error {
         match *self {
             Error::InvalidAssignTarget(_) => "invalid assignment pattern",
             Error::InvalidPropPatt(_) => "invalid object property in assignment pattern",
+            Error::TrailingCommaAfterRest(_) => "trailing comma after rest element",
         }
     }
 
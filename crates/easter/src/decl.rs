@@ -3,73 +3,159 @@ use joker::track::*;
 
 use expr::Expr;
 use fun::Fun;
+use class::Class;
 use id::Id;
+use obj::DotKey;
 use patt::{CompoundPatt, Patt};
 use punc::Semi;
+use ty::{Interface, Type, TypeAlias};
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Import {
-    // ES6: more import forms
-    ForEffect(Option<Span>, StringLiteral),
+    ForEffect(Option<Span>, StringLiteral<'static>, Semi),
+    Named(Option<Span>, Option<Id>, Vec<ImportSpec>, StringLiteral<'static>, Semi),
+    Namespace(Option<Span>, Option<Id>, Id, StringLiteral<'static>, Semi),
+}
+
+impl Import {
+    pub fn bound_ids(&self) -> Vec<&Id> {
+        match *self {
+            Import::ForEffect(_, _, _) => vec![],
+            Import::Named(_, ref default, ref specs, _, _) => {
+                default.iter().chain(specs.iter().map(|spec| &spec.local)).collect()
+            }
+            Import::Namespace(_, ref default, ref id, _, _) => default.iter().chain(Some(id)).collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct ImportSpec {
+    pub location: Option<Span>,
+    pub imported: DotKey,
+    pub local: Id,
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Export {
-    // ES6: more export forms
     Var(Option<Span>, Vec<Dtor>, Semi),
     Decl(Decl),
+    DefaultExpr(Option<Span>, Expr, Semi),
+    DefaultFun(Option<Span>, Fun),
+    DefaultClass(Option<Span>, Class),
+    List(Option<Span>, Vec<ExportSpec>, Semi),
+    ListFrom(Option<Span>, Vec<ExportSpec>, StringLiteral<'static>, Semi),
+    AllFrom(Option<Span>, StringLiteral<'static>, Semi),
+    NamespaceFrom(Option<Span>, DotKey, StringLiteral<'static>, Semi),
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct ExportSpec {
+    pub location: Option<Span>,
+    pub local: DotKey,
+    pub exported: DotKey,
+}
+
+impl Export {
+    // The names this declaration adds to the module's exports, paired with
+    // the location to blame for each one.
+    pub fn exported_names(&self) -> Vec<(&str, Option<Span>)> {
+        match *self {
+            Export::Var(_, ref dtors, _) => {
+                dtors.iter().flat_map(|dtor| dtor.bound_ids()).map(id_name).collect()
+            }
+            Export::Decl(ref decl) => decl.bound_ids().into_iter().map(id_name).collect(),
+            Export::DefaultExpr(location, _, _)
+            | Export::DefaultFun(location, _)
+            | Export::DefaultClass(location, _) => vec![("default", location)],
+            Export::List(_, ref specs, _) | Export::ListFrom(_, ref specs, _, _) => {
+                specs.iter().map(|spec| (spec.exported.value.as_ref(), spec.exported.location)).collect()
+            }
+            Export::AllFrom(_, _, _) => vec![],
+            Export::NamespaceFrom(_, ref name, _, _) => vec![(name.value.as_ref(), name.location)],
+        }
+    }
+}
+
+fn id_name(id: &Id) -> (&str, Option<Span>) {
+    (id.name.as_ref(), id.location)
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Decl {
     Fun(Fun),
+    Class(Class),
     Let(Option<Span>, Vec<Dtor>, Semi),
     Const(Option<Span>, Vec<ConstDtor>, Semi),
+    Interface(Interface),
+    TypeAlias(TypeAlias),
+}
+
+impl Decl {
+    pub fn bound_ids(&self) -> Vec<&Id> {
+        match *self {
+            Decl::Fun(ref fun) => fun.kind.id().into_iter().collect(),
+            Decl::Class(ref class) => class.id.iter().collect(),
+            Decl::Let(_, ref dtors, _) => dtors.iter().flat_map(|dtor| dtor.bound_ids()).collect(),
+            Decl::Const(_, ref dtors, _) => dtors.iter().flat_map(|dtor| dtor.patt.bound_ids()).collect(),
+            // Types have a namespace of their own.
+            Decl::Interface(_) | Decl::TypeAlias(_) => vec![],
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Dtor {
-    Simple(Option<Span>, Id, Option<Expr>),
-    Compound(Option<Span>, CompoundPatt<Id>, Expr),
+    Simple(Option<Span>, Id, Option<Type>, Option<Expr>),
+    Compound(Option<Span>, CompoundPatt<Id>, Option<Type>, Expr),
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct ConstDtor {
     pub location: Option<Span>,
     pub patt: Patt<Id>,
+    pub ty: Option<Type>,
     pub value: Expr,
 }
 
+impl Dtor {
+    pub fn bound_ids(&self) -> Vec<&Id> {
+        match *self {
+            Dtor::Simple(_, ref id, _, _) => vec![id],
+            Dtor::Compound(_, ref patt, _, _) => patt.bound_ids(),
+        }
+    }
+}
+
 pub trait DtorExt: Sized {
     fn from_simple_init(Id, Expr) -> Self;
     fn from_compound_init(CompoundPatt<Id>, Expr) -> Self;
-    fn from_init(Patt<Id>, Expr) -> Self;
+    fn from_init(Patt<Id>, Expr) -> Result<Self, Patt<Id>>;
     fn from_init_opt(Patt<Id>, Option<Expr>) -> Result<Self, Patt<Id>>;
 }
 
 impl DtorExt for Dtor {
     fn from_compound_init(lhs: CompoundPatt<Id>, rhs: Expr) -> Dtor {
-        Dtor::Compound(span(&lhs, &rhs), lhs, rhs)
+        Dtor::Compound(span(&lhs, &rhs), lhs, None, rhs)
     }
 
     fn from_simple_init(lhs: Id, rhs: Expr) -> Dtor {
-        Dtor::Simple(span(&lhs, &rhs), lhs, Some(rhs))
+        Dtor::Simple(span(&lhs, &rhs), lhs, None, Some(rhs))
     }
 
-    fn from_init(lhs: Patt<Id>, rhs: Expr) -> Dtor {
+    fn from_init(lhs: Patt<Id>, rhs: Expr) -> Result<Dtor, Patt<Id>> {
         match lhs {
-            Patt::Simple(id) => Dtor::from_simple_init(id, rhs),
-            Patt::Compound(patt) => Dtor::from_compound_init(patt, rhs),
-            // TODO Likely should result in panic
-            Patt::Assign(_, _, _) => {
-                unimplemented!("export declaration with assign pattern not implemented")
-            }
+            Patt::Simple(id) => Ok(Dtor::from_simple_init(id, rhs)),
+            Patt::Compound(patt) => Ok(Dtor::from_compound_init(patt, rhs)),
+            // A pattern with a default value only makes sense nested inside
+            // of another pattern.
+            lhs @ Patt::Assign(_, _, _) => Err(lhs)
         }
     }
 
     fn from_init_opt(lhs: Patt<Id>, rhs: Option<Expr>) -> Result<Dtor, Patt<Id>> {
         match (lhs, rhs) {
-            (Patt::Simple(id), rhs) => Ok(Dtor::Simple(*id.tracking_ref(), id, rhs)),
+            (Patt::Simple(id), rhs) => Ok(Dtor::Simple(*id.tracking_ref(), id, None, rhs)),
             (Patt::Compound(patt), Some(rhs)) => Ok(Dtor::from_compound_init(patt, rhs)),
             (lhs @ Patt::Compound(_), None) | (lhs @ Patt::Assign(_, _, _), _) => Err(lhs)
         }
@@ -78,24 +164,36 @@ impl DtorExt for Dtor {
 
 impl DtorExt for ConstDtor {
     fn from_compound_init(lhs: CompoundPatt<Id>, rhs: Expr) -> ConstDtor {
-        ConstDtor::from_init(Patt::Compound(lhs), rhs)
+        let lhs = Patt::Compound(lhs);
+        ConstDtor {
+            location: span(&lhs, &rhs),
+            patt: lhs,
+            ty: None,
+            value: rhs,
+        }
     }
 
     fn from_simple_init(lhs: Id, rhs: Expr) -> ConstDtor {
-        ConstDtor::from_init(Patt::Simple(lhs), rhs)
-    }
-
-    fn from_init(lhs: Patt<Id>, rhs: Expr) -> ConstDtor {
+        let lhs = Patt::Simple(lhs);
         ConstDtor {
             location: span(&lhs, &rhs),
             patt: lhs,
+            ty: None,
             value: rhs,
         }
     }
 
+    fn from_init(lhs: Patt<Id>, rhs: Expr) -> Result<ConstDtor, Patt<Id>> {
+        match lhs {
+            Patt::Simple(id) => Ok(ConstDtor::from_simple_init(id, rhs)),
+            Patt::Compound(patt) => Ok(ConstDtor::from_compound_init(patt, rhs)),
+            lhs @ Patt::Assign(_, _, _) => Err(lhs)
+        }
+    }
+
     fn from_init_opt(lhs: Patt<Id>, rhs: Option<Expr>) -> Result<ConstDtor, Patt<Id>> {
         match rhs {
-            Some(rhs) => Ok(ConstDtor::from_init(lhs, rhs)),
+            Some(rhs) => ConstDtor::from_init(lhs, rhs),
             None => Err(lhs),
         }
     }
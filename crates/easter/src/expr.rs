@@ -1,10 +1,13 @@
 use std::fmt;
 use std::fmt::{Debug, Formatter};
 use joker::track::{TrackingRef, TrackingMut, Span, Untrack};
-use joker::token::{NumberLiteral, StringLiteral, RegExpLiteral};
+use joker::token::{NumberLiteral, BigIntLiteral, StringLiteral, RegExpLiteral};
 
-use obj::{DotKey, Prop, PropVal};
+use obj::{DotKey, PrivateName, Prop, PropVal};
 use fun::Fun;
+use class::Class;
+use jsx::{JSXElement, JSXFragment};
+use ty::Type;
 use punc::{Unop, Binop, Assop, Logop};
 use id::Id;
 use patt::{Patt, RestPatt, CompoundPatt, PropPatt};
@@ -19,7 +22,25 @@ pub enum Assign {
 #[derive(PartialEq, Debug, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum ExprListItem {
     Expr(Expr),
-    Spread(Option<Span>, Expr)
+    // The flag records a trailing comma after the final spread of an array
+    // literal, which a literal may have but a rest element may not.
+    Spread(Option<Span>, Expr, bool)
+}
+
+// The quasis and expressions of a template interleave, so there is always
+// exactly one more quasi than there are expressions.
+#[derive(PartialEq, Debug, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Template {
+    pub location: Option<Span>,
+    pub quasis: Vec<TemplateElement>,
+    pub exprs: Vec<Expr>
+}
+
+#[derive(PartialEq, Debug, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct TemplateElement {
+    pub location: Option<Span>,
+    pub cooked: Option<String>,
+    pub raw: String
 }
 
 #[derive(Clone, TrackingRef, TrackingMut, Untrack)]
@@ -29,6 +50,7 @@ pub enum Expr {
     Arr(Option<Span>, Vec<Option<ExprListItem>>),
     Obj(Option<Span>, Vec<Prop>),
     Fun(Fun),
+    Class(Class),
     Seq(Option<Span>, Vec<Expr>),
     Unop(Option<Span>, Unop, Box<Expr>),
     Binop(Option<Span>, Binop, Box<Expr>, Box<Expr>),
@@ -40,17 +62,47 @@ pub enum Expr {
     Assign(Option<Span>, Box<Assign>, Box<Expr>),
     BinAssign(Option<Span>, Assop, Box<Expr>, Box<Expr>),
     Cond(Option<Span>, Box<Expr>, Box<Expr>, Box<Expr>),
+    Yield(Option<Span>, Option<Box<Expr>>),
+    YieldDelegate(Option<Span>, Box<Expr>),
+    Await(Option<Span>, Box<Expr>),
     Call(Option<Span>, Box<Expr>, Vec<ExprListItem>),
     New(Option<Span>, Box<Expr>, Option<Vec<ExprListItem>>),
     Dot(Option<Span>, Box<Expr>, DotKey),
     Brack(Option<Span>, Box<Expr>, Box<Expr>),
+    PrivateDot(Option<Span>, Box<Expr>, PrivateName),
+    // `#x in obj`
+    PrivateIn(Option<Span>, PrivateName, Box<Expr>),
+    SuperDot(Option<Span>, DotKey),
+    SuperBrack(Option<Span>, Box<Expr>),
+    SuperCall(Option<Span>, Vec<ExprListItem>),
+    // `import(specifier)`
+    Import(Option<Span>, Box<Expr>),
+    // An optional chain wraps the whole chain so that `(a?.b).c` and
+    // `a?.b.c` stay distinct; only the `?.` links are Optional* nodes.
+    Chain(Option<Span>, Box<Expr>),
+    OptionalDot(Option<Span>, Box<Expr>, DotKey),
+    OptionalBrack(Option<Span>, Box<Expr>, Box<Expr>),
+    OptionalPrivateDot(Option<Span>, Box<Expr>, PrivateName),
+    OptionalCall(Option<Span>, Box<Expr>, Vec<ExprListItem>),
     NewTarget(Option<Span>),
+    ImportMeta(Option<Span>),
     True(Option<Span>),
     False(Option<Span>),
     Null(Option<Span>),
-    Number(Option<Span>, NumberLiteral),
-    RegExp(Option<Span>, RegExpLiteral),
-    String(Option<Span>, StringLiteral)
+    Number(Option<Span>, NumberLiteral<'static>),
+    BigInt(Option<Span>, BigIntLiteral<'static>),
+    RegExp(Option<Span>, RegExpLiteral<'static>),
+    String(Option<Span>, StringLiteral<'static>),
+    Template(Template),
+    TaggedTemplate(Option<Span>, Box<Expr>, Template),
+    JSXElement(JSXElement),
+    JSXFragment(JSXFragment),
+    // `expr as T`, `expr!` and `expr<T>` come from the type annotation
+    // syntax extension. Type arguments are only parsed before arguments or
+    // a template.
+    As(Option<Span>, Box<Expr>, Type),
+    NonNull(Option<Span>, Box<Expr>),
+    TypeArgs(Option<Span>, Box<Expr>, Vec<Type>)
 }
 
 // TODO May be move this back into cover. Or remove it altogether and bring Error here.
@@ -59,6 +111,9 @@ impl Expr {
         match self {
             Expr::Dot(_, _, _)
           | Expr::Brack(_, _, _)
+          | Expr::PrivateDot(_, _, _)
+          | Expr::SuperDot(_, _)
+          | Expr::SuperBrack(_, _)
           | Expr::Id(_) => true,
           _ => false
         }
@@ -68,27 +123,60 @@ impl Expr {
         match self {
             Expr::Dot(_, _, _)
           | Expr::Brack(_, _, _)
+          | Expr::PrivateDot(_, _, _)
+          | Expr::SuperDot(_, _)
+          | Expr::SuperBrack(_, _)
           | Expr::Id(_) => Ok(self),
           _ => Err(cover::Error::InvalidAssignTarget(*self.tracking_ref()))
         }
     }
 
+    // Whether this is a member expression, possibly the end of an optional
+    // chain, whose property is a private name.
+    pub fn is_private_reference(&self) -> bool {
+        match *self {
+            Expr::PrivateDot(_, _, _)
+          | Expr::OptionalPrivateDot(_, _, _) => true,
+            Expr::Chain(_, ref link) => link.is_private_reference(),
+            _ => false
+        }
+    }
+
+    // The target of an assignment or of a for-in/of head, which may be a
+    // destructuring pattern but has no default value of its own.
     pub fn into_simple_or_compound_pattern(self) -> Result<Patt<Expr>, cover::Error> {
         match self {
-            Expr::Obj(location, props) => {
+            Expr::Obj(location, mut props) => {
                 let mut prop_patts = Vec::with_capacity(props.len());
+                let mut rest = None;
+                if let Some(last) = props.pop() {
+                    if let Prop::Spread(loc, expr, comma) = last {
+                        if comma {
+                            return Err(cover::Error::TrailingCommaAfterRest(loc));
+                        }
+                        rest = Some(Box::new(RestPatt {
+                            location: loc,
+                            patt: expr.into_assignable().map(Patt::Simple)?
+                        }));
+                    } else {
+                        props.push(last);
+                    }
+                }
                 for prop in props {
                     prop_patts.push(prop.into_assign_prop()?);
                 }
-                Ok(Patt::Compound(CompoundPatt::Obj(location, prop_patts)))
+                Ok(Patt::Compound(CompoundPatt::Obj(location, prop_patts, rest)))
             }
             Expr::Arr(location, mut exprs) => {
                 let mut patts = Vec::with_capacity(exprs.len());
                 let mut rest = None;
                 if let Some(last) = exprs.pop() {
-                    if let Some(ExprListItem::Spread(None, expr)) = last {
+                    if let Some(ExprListItem::Spread(loc, expr, comma)) = last {
+                        if comma {
+                            return Err(cover::Error::TrailingCommaAfterRest(loc));
+                        }
                         rest = Some(Box::new(RestPatt {
-                            location: None,
+                            location: loc,
                             patt: expr.into_simple_or_compound_pattern()?
                         }));
                     } else {
@@ -97,8 +185,8 @@ impl Expr {
                 }
                 for expr in exprs {
                     patts.push(match expr {
-                        Some(ExprListItem::Expr(expr)) => Some(expr.into_simple_or_compound_pattern()?),
-                        Some(ExprListItem::Spread(loc, _)) => { return Err(cover::Error::InvalidAssignTarget(loc)); }
+                        Some(ExprListItem::Expr(expr)) => Some(expr.into_element_pattern()?),
+                        Some(ExprListItem::Spread(loc, _, _)) => { return Err(cover::Error::InvalidAssignTarget(loc)); }
                         None => None
                     });
                 }
@@ -107,6 +195,75 @@ impl Expr {
             _ => { return self.into_assignable().map(Patt::Simple); }
         }
     }
+
+    // An array pattern element or object pattern property value, which may
+    // have a default value, like the `a = 1` in `[a = 1] = b`.
+    pub fn into_element_pattern(self) -> Result<Patt<Expr>, cover::Error> {
+        match self {
+            Expr::Assign(location, left, right) => match *left {
+                Assign::Expr(left) => {
+                    Ok(Patt::Assign(location, Box::new(left.into_simple_or_compound_pattern()?), right))
+                }
+                Assign::Pattern(_) => Err(cover::Error::InvalidAssignTarget(location))
+            },
+            _ => self.into_simple_or_compound_pattern()
+        }
+    }
+
+    // Checks what `into_simple_or_compound_pattern` would, for a target that
+    // stays an expression in the tree.
+    pub fn check_simple_or_compound_pattern(&self) -> Result<(), cover::Error> {
+        match *self {
+            Expr::Obj(_, ref props) => {
+                for (i, prop) in props.iter().enumerate() {
+                    match *prop {
+                        Prop::Spread(location, ref expr, comma) if i + 1 == props.len() => {
+                            if comma {
+                                return Err(cover::Error::TrailingCommaAfterRest(location));
+                            }
+                            if !expr.is_assignable() {
+                                return Err(cover::Error::InvalidAssignTarget(*expr.tracking_ref()));
+                            }
+                        }
+                        Prop::Regular(_, _, PropVal::Init(ref expr)) => { expr.check_element_pattern()?; }
+                        Prop::Shorthand(_) => {}
+                        _ => { return Err(cover::Error::InvalidPropPatt(*prop.tracking_ref())); }
+                    }
+                }
+                Ok(())
+            }
+            Expr::Arr(_, ref exprs) => {
+                for (i, expr) in exprs.iter().enumerate() {
+                    match *expr {
+                        Some(ExprListItem::Spread(location, ref expr, comma)) => {
+                            if i + 1 < exprs.len() {
+                                return Err(cover::Error::InvalidAssignTarget(location));
+                            }
+                            if comma {
+                                return Err(cover::Error::TrailingCommaAfterRest(location));
+                            }
+                            expr.check_simple_or_compound_pattern()?;
+                        }
+                        Some(ExprListItem::Expr(ref expr)) => { expr.check_element_pattern()?; }
+                        None => {}
+                    }
+                }
+                Ok(())
+            }
+            _ if self.is_assignable() => Ok(()),
+            _ => Err(cover::Error::InvalidAssignTarget(*self.tracking_ref()))
+        }
+    }
+
+    fn check_element_pattern(&self) -> Result<(), cover::Error> {
+        match *self {
+            Expr::Assign(location, ref left, _) => match **left {
+                Assign::Expr(ref left) => left.check_simple_or_compound_pattern(),
+                Assign::Pattern(_) => Err(cover::Error::InvalidAssignTarget(location))
+            },
+            _ => self.check_simple_or_compound_pattern()
+        }
+    }
 }
 
 // TODO May be move this back into cover. Or remove it altogether and bring Error here.
@@ -119,7 +276,7 @@ impl IntoAssignProp for Prop {
         let location = *self.tracking_ref();
         Ok(match self {
             Prop::Regular(location, key, PropVal::Init(expr)) => {
-                PropPatt::Regular(location, key, expr.into_simple_or_compound_pattern()?)
+                PropPatt::Regular(location, key, expr.into_element_pattern()?)
             }
             Prop::Shorthand(id) => {
                 PropPatt::Shorthand(None, id, None)
@@ -140,6 +297,7 @@ impl PartialEq for Expr {
             (&Expr::Arr(_, ref elts_l),           &Expr::Arr(_, ref elts_r))           => elts_l == elts_r,
             (&Expr::Obj(_, ref props_l),          &Expr::Obj(_, ref props_r))          => props_l == props_r,
             (&Expr::Fun(ref fun_l),               &Expr::Fun(ref fun_r))               => fun_l == fun_r,
+            (&Expr::Class(ref class_l),           &Expr::Class(ref class_r))           => class_l == class_r,
             (&Expr::Seq(_, ref exprs_l),          &Expr::Seq(_, ref exprs_r))          => exprs_l == exprs_r,
             (&Expr::Unop(_, ref op_l, ref arg_l), &Expr::Unop(_, ref op_r, ref arg_r)) => (op_l, arg_l) == (op_r, arg_r),
             (&Expr::Binop(_, ref op_l, ref arg1_l, ref arg2_l),
@@ -156,6 +314,9 @@ impl PartialEq for Expr {
              &Expr::BinAssign(_, ref op_r, ref patt_r, ref arg_r))                     => (op_l, patt_l, arg_l) == (op_r, patt_r, arg_r),
             (&Expr::Cond(_, ref test_l, ref cons_l, ref alt_l),
              &Expr::Cond(_, ref test_r, ref cons_r, ref alt_r))                        => (test_l, cons_l, alt_l) == (test_r, cons_r, alt_r),
+            (&Expr::Yield(_, ref arg_l),          &Expr::Yield(_, ref arg_r))          => arg_l == arg_r,
+            (&Expr::YieldDelegate(_, ref arg_l),  &Expr::YieldDelegate(_, ref arg_r))  => arg_l == arg_r,
+            (&Expr::Await(_, ref arg_l),          &Expr::Await(_, ref arg_r))          => arg_l == arg_r,
             (&Expr::Call(_, ref callee_l, ref args_l),
              &Expr::Call(_, ref callee_r, ref args_r))                                 => (callee_l, args_l) == (callee_r, args_r),
             (&Expr::New(_, ref callee_l, None),   &Expr::New(_, ref callee_r, None))   => callee_l == callee_r,
@@ -167,13 +328,41 @@ impl PartialEq for Expr {
             (&Expr::Dot(_, ref obj_l, ref key_l), &Expr::Dot(_, ref obj_r, ref key_r)) => (obj_l, key_l) == (obj_r, key_r),
             (&Expr::Brack(_, ref obj_l, ref prop_l),
              &Expr::Brack(_, ref obj_r, ref prop_r))                                   => (obj_l, prop_l) == (obj_r, prop_r),
+            (&Expr::PrivateDot(_, ref obj_l, ref key_l),
+             &Expr::PrivateDot(_, ref obj_r, ref key_r))                               => (obj_l, key_l) == (obj_r, key_r),
+            (&Expr::PrivateIn(_, ref key_l, ref obj_l),
+             &Expr::PrivateIn(_, ref key_r, ref obj_r))                                => (key_l, obj_l) == (key_r, obj_r),
+            (&Expr::SuperDot(_, ref key_l),       &Expr::SuperDot(_, ref key_r))       => key_l == key_r,
+            (&Expr::SuperBrack(_, ref prop_l),    &Expr::SuperBrack(_, ref prop_r))    => prop_l == prop_r,
+            (&Expr::SuperCall(_, ref args_l),     &Expr::SuperCall(_, ref args_r))     => args_l == args_r,
+            (&Expr::Import(_, ref spec_l),        &Expr::Import(_, ref spec_r))        => spec_l == spec_r,
+            (&Expr::Chain(_, ref expr_l),         &Expr::Chain(_, ref expr_r))         => expr_l == expr_r,
+            (&Expr::OptionalDot(_, ref obj_l, ref key_l),
+             &Expr::OptionalDot(_, ref obj_r, ref key_r))                              => (obj_l, key_l) == (obj_r, key_r),
+            (&Expr::OptionalBrack(_, ref obj_l, ref prop_l),
+             &Expr::OptionalBrack(_, ref obj_r, ref prop_r))                           => (obj_l, prop_l) == (obj_r, prop_r),
+            (&Expr::OptionalPrivateDot(_, ref obj_l, ref key_l),
+             &Expr::OptionalPrivateDot(_, ref obj_r, ref key_r))                       => (obj_l, key_l) == (obj_r, key_r),
+            (&Expr::OptionalCall(_, ref callee_l, ref args_l),
+             &Expr::OptionalCall(_, ref callee_r, ref args_r))                         => (callee_l, args_l) == (callee_r, args_r),
             (&Expr::NewTarget(_),          &Expr::NewTarget(_))                        => true,
+            (&Expr::ImportMeta(_),         &Expr::ImportMeta(_))                       => true,
             (&Expr::True(_),               &Expr::True(_))                             => true,
             (&Expr::False(_),              &Expr::False(_))                            => true,
             (&Expr::Null(_),               &Expr::Null(_))                             => true,
             (&Expr::Number(_, ref lit_l),  &Expr::Number(_, ref lit_r))                => lit_l == lit_r,
+            (&Expr::BigInt(_, ref lit_l),  &Expr::BigInt(_, ref lit_r))                => lit_l == lit_r,
             (&Expr::RegExp(_, ref lit_l),  &Expr::RegExp(_, ref lit_r))                => lit_l == lit_r,
             (&Expr::String(_, ref lit_l),  &Expr::String(_, ref lit_r))                => lit_l == lit_r,
+            (&Expr::Template(ref tmpl_l),  &Expr::Template(ref tmpl_r))                => tmpl_l == tmpl_r,
+            (&Expr::TaggedTemplate(_, ref tag_l, ref tmpl_l),
+             &Expr::TaggedTemplate(_, ref tag_r, ref tmpl_r))                          => (tag_l, tmpl_l) == (tag_r, tmpl_r),
+            (&Expr::JSXElement(ref elt_l), &Expr::JSXElement(ref elt_r))               => elt_l == elt_r,
+            (&Expr::JSXFragment(ref frag_l), &Expr::JSXFragment(ref frag_r))           => frag_l == frag_r,
+            (&Expr::As(_, ref expr_l, ref ty_l), &Expr::As(_, ref expr_r, ref ty_r))   => (expr_l, ty_l) == (expr_r, ty_r),
+            (&Expr::NonNull(_, ref expr_l),       &Expr::NonNull(_, ref expr_r))       => expr_l == expr_r,
+            (&Expr::TypeArgs(_, ref expr_l, ref args_l),
+             &Expr::TypeArgs(_, ref expr_r, ref args_r))                               => (expr_l, args_l) == (expr_r, args_r),
             _ => false
         }
     }
@@ -187,6 +376,7 @@ impl Debug for Expr {
             &Expr::Arr(_, ref elts)                          => fmt.debug_tuple("Arr").field(elts).finish(),
             &Expr::Obj(_, ref props)                         => fmt.debug_tuple("Obj").field(props).finish(),
             &Expr::Fun(ref fun)                              => fmt.debug_tuple("Fun").field(fun).finish(),
+            &Expr::Class(ref class)                          => fmt.debug_tuple("Class").field(class).finish(),
             &Expr::Seq(_, ref exprs)                         => fmt.debug_tuple("Seq").field(exprs).finish(),
             &Expr::Unop(_, ref op, ref arg)                  => fmt.debug_tuple("Unop").field(op).field(arg).finish(),
             &Expr::Binop(_, ref op, ref left, ref right)     => fmt.debug_tuple("Binop").field(op).field(left).field(right).finish(),
@@ -198,6 +388,9 @@ impl Debug for Expr {
             &Expr::Assign(_, ref left, ref right)            => fmt.debug_tuple("Assign").field(left).field(right).finish(),
             &Expr::BinAssign(_, ref op, ref left, ref right) => fmt.debug_tuple("BinAssign").field(op).field(left).field(right).finish(),
             &Expr::Cond(_, ref test, ref cons, ref alt)      => fmt.debug_tuple("Cond").field(test).field(cons).field(alt).finish(),
+            &Expr::Yield(_, ref arg)                         => fmt.debug_tuple("Yield").field(arg).finish(),
+            &Expr::YieldDelegate(_, ref arg)                 => fmt.debug_tuple("YieldDelegate").field(arg).finish(),
+            &Expr::Await(_, ref arg)                         => fmt.debug_tuple("Await").field(arg).finish(),
             &Expr::Call(_, ref callee, ref args)             => fmt.debug_tuple("Call").field(callee).field(args).finish(),
             &Expr::New(_, ref ctor, None) => {
                 let args: Vec<Expr> = vec![];
@@ -206,13 +399,33 @@ impl Debug for Expr {
             &Expr::New(_, ref ctor, Some(ref args))          => fmt.debug_tuple("New").field(ctor).field(args).finish(),
             &Expr::Dot(_, ref expr, ref key)                 => fmt.debug_tuple("Dot").field(expr).field(key).finish(),
             &Expr::Brack(_, ref expr, ref prop)              => fmt.debug_tuple("Brack").field(expr).field(prop).finish(),
+            &Expr::PrivateDot(_, ref expr, ref key)          => fmt.debug_tuple("PrivateDot").field(expr).field(key).finish(),
+            &Expr::PrivateIn(_, ref key, ref expr)           => fmt.debug_tuple("PrivateIn").field(key).field(expr).finish(),
+            &Expr::SuperDot(_, ref key)                      => fmt.debug_tuple("SuperDot").field(key).finish(),
+            &Expr::SuperBrack(_, ref prop)                   => fmt.debug_tuple("SuperBrack").field(prop).finish(),
+            &Expr::SuperCall(_, ref args)                    => fmt.debug_tuple("SuperCall").field(args).finish(),
+            &Expr::Import(_, ref spec)                       => fmt.debug_tuple("Import").field(spec).finish(),
+            &Expr::Chain(_, ref expr)                        => fmt.debug_tuple("Chain").field(expr).finish(),
+            &Expr::OptionalDot(_, ref expr, ref key)         => fmt.debug_tuple("OptionalDot").field(expr).field(key).finish(),
+            &Expr::OptionalBrack(_, ref expr, ref prop)      => fmt.debug_tuple("OptionalBrack").field(expr).field(prop).finish(),
+            &Expr::OptionalPrivateDot(_, ref expr, ref key)  => fmt.debug_tuple("OptionalPrivateDot").field(expr).field(key).finish(),
+            &Expr::OptionalCall(_, ref callee, ref args)     => fmt.debug_tuple("OptionalCall").field(callee).field(args).finish(),
             &Expr::NewTarget(_)                              => fmt.write_str("NewTarget"),
+            &Expr::ImportMeta(_)                             => fmt.write_str("ImportMeta"),
             &Expr::True(_)                                   => fmt.write_str("True"),
             &Expr::False(_)                                  => fmt.write_str("False"),
             &Expr::Null(_)                                   => fmt.write_str("Null"),
             &Expr::Number(_, ref lit)                        => fmt.debug_tuple("Number").field(lit).finish(),
+            &Expr::BigInt(_, ref lit)                        => fmt.debug_tuple("BigInt").field(lit).finish(),
             &Expr::RegExp(_, ref lit)                        => fmt.debug_tuple("RegExp").field(lit).finish(),
-            &Expr::String(_, ref lit)                        => fmt.debug_tuple("String").field(lit).finish()
+            &Expr::String(_, ref lit)                        => fmt.debug_tuple("String").field(lit).finish(),
+            &Expr::Template(ref tmpl)                        => fmt.debug_tuple("Template").field(tmpl).finish(),
+            &Expr::TaggedTemplate(_, ref tag, ref tmpl)      => fmt.debug_tuple("TaggedTemplate").field(tag).field(tmpl).finish(),
+            &Expr::JSXElement(ref elt)                       => fmt.debug_tuple("JSXElement").field(elt).finish(),
+            &Expr::JSXFragment(ref frag)                     => fmt.debug_tuple("JSXFragment").field(frag).finish(),
+            &Expr::As(_, ref expr, ref ty)                   => fmt.debug_tuple("As").field(expr).field(ty).finish(),
+            &Expr::NonNull(_, ref expr)                      => fmt.debug_tuple("NonNull").field(expr).finish(),
+            &Expr::TypeArgs(_, ref expr, ref args)           => fmt.debug_tuple("TypeArgs").field(expr).field(args).finish()
         }
     }
 }
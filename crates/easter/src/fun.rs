@@ -4,21 +4,44 @@ use id::Id;
 use patt::{Patt, RestPatt};
 use stmt::Script;
 use expr::Expr;
+use ty::{Annot, Type, TypeParam};
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Params {
     pub location: Option<Span>,
     pub list: Vec<Patt<Id>>,
-    pub rest: Option<RestPatt<Id>>
+    pub rest: Option<RestPatt<Id>>,
+    // The annotations of `list` and then `rest`, which are only parsed with
+    // the type annotation syntax extension and are otherwise left empty.
+    pub types: Vec<Option<Annot>>
 }
 
-// This allows regular function, arrow function & generator
-// TODO: missing async and async generator
+impl Params {
+    pub fn bound_ids(&self) -> Vec<&Id> {
+        let mut ids: Vec<&Id> = self.list.iter().flat_map(|patt| patt.bound_ids()).collect();
+        if let Some(ref rest) = self.rest {
+            ids.extend(rest.patt.bound_ids());
+        }
+        ids
+    }
+
+    // Whether the list is just identifiers, without defaults, destructuring
+    // or a rest parameter.
+    pub fn is_simple(&self) -> bool {
+        self.rest.is_none() && self.list.iter().all(|patt| patt.is_simple())
+    }
+}
+
+// This allows regular function, arrow function & generator, any of which
+// may also be async
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Fun {
     pub location: Option<Span>,
     pub kind: FunctionKind,
+    pub is_async: bool,
+    pub type_params: Vec<TypeParam>,
     pub params: Params,
+    pub ret: Option<Type>,
     // FIXME What is the more cleaner way to solve this? Arrow function need to store an expression
     // conditionally enum totally does not provide the clean way to do it.
     pub body: Script,
@@ -34,6 +57,15 @@ pub enum FunctionKind {
     AnonymousGenerator
 }
 
+impl FunctionKind {
+    pub fn id(&self) -> Option<&Id> {
+        match *self {
+            FunctionKind::Named(ref id) | FunctionKind::Generator(ref id) => Some(id),
+            _ => None
+        }
+    }
+}
+
 // impl TrackingRef for FunctionKind {
 //     fn tracking_ref(&self) -> &Option<Span> {
 //         &None
@@ -8,7 +8,7 @@ use patt::Patt;
 #[derive(Debug, Eq, PartialEq, Clone, TrackingRef, TrackingMut)]
 pub struct Id {
     pub location: Option<Span>,
-    pub name: Name
+    pub name: Name<'static>
 }
 
 impl Untrack for Id {
@@ -23,10 +23,11 @@ pub trait IdExt {
 }
 
 impl IdExt for Id {
+    // The name is detached from the source it was lexed from.
     fn new(name: Name, location: Option<Span>) -> Id {
         Id {
             location: location,
-            name: name
+            name: name.into_owned()
         }
     }
 
@@ -39,6 +40,6 @@ impl IdExt for Id {
     }
 
     fn into_dtor(self) -> Dtor {
-        Dtor::Simple(*self.tracking_ref(), self, None)
+        Dtor::Simple(*self.tracking_ref(), self, None, None)
     }
 }
@@ -0,0 +1,76 @@
+use joker::track::*;
+use joker::token::{StringLiteral, JSXText};
+
+use expr::Expr;
+
+// A JSX identifier, which unlike an ECMAScript identifier may contain `-`.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut)]
+pub struct JSXId {
+    pub location: Option<Span>,
+    pub name: String
+}
+
+impl Untrack for JSXId {
+    fn untrack(&mut self) { self.location = None; }
+}
+
+// Attribute names are never members.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum JSXName {
+    Id(JSXId),
+    Namespaced(Option<Span>, JSXId, JSXId),
+    Member(Option<Span>, Box<JSXName>, JSXId)
+}
+
+// The children of a self-closing element are `None`; the closing tag of
+// any other element repeats its name, so it is not stored.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct JSXElement {
+    pub location: Option<Span>,
+    pub name: JSXName,
+    pub attrs: Vec<JSXAttr>,
+    pub children: Option<Vec<JSXChild>>
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct JSXFragment {
+    pub location: Option<Span>,
+    pub children: Vec<JSXChild>
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum JSXAttr {
+    Regular(Option<Span>, JSXName, Option<JSXAttrValue>),
+    Spread(Option<Span>, Expr)
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum JSXAttrValue {
+    String(Option<Span>, StringLiteral<'static>),
+    Expr(Option<Span>, Expr),
+    Element(JSXElement),
+    Fragment(JSXFragment)
+}
+
+// An expression container holds `None` when it is empty or only contains
+// comments, as in `{/* ... */}`.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum JSXChild {
+    Text(Option<Span>, JSXText),
+    Expr(Option<Span>, Option<Expr>),
+    Spread(Option<Span>, Expr),
+    Element(JSXElement),
+    Fragment(JSXFragment)
+}
+
+impl JSXName {
+    // The name as written, ignoring locations, so that a closing tag can be
+    // checked against its opening tag.
+    pub fn qualified(&self) -> String {
+        match *self {
+            JSXName::Id(ref id) => id.name.clone(),
+            JSXName::Namespaced(_, ref namespace, ref name) => format!("{}:{}", namespace.name, name.name),
+            JSXName::Member(_, ref object, ref property) => format!("{}.{}", object.qualified(), property.name)
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use joker::track::*;
 use joker::token::{StringLiteral, NumberLiteral};
+use joker::word::Name;
 
 use id::Id;
 use expr::Expr;
@@ -10,26 +11,52 @@ use fun::Fun;
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut)]
 pub struct DotKey {
     pub location: Option<Span>,
-    pub value: String
+    pub value: Name<'static>
 }
 
 impl Untrack for DotKey {
     fn untrack(&mut self) { self.location = None; }
 }
 
+// A `#name` class element name, stored without the leading `#`.
+#[derive(Debug, PartialEq, Eq, Clone, TrackingRef, TrackingMut)]
+pub struct PrivateName {
+    pub location: Option<Span>,
+    pub name: String
+}
+
+impl Untrack for PrivateName {
+    fn untrack(&mut self) { self.location = None; }
+}
+
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum Prop {
     Regular(Option<Span>, PropKey, PropVal),
     Method(PropKey, Fun),
-    Shorthand(Id)
+    Shorthand(Id),
+    // The flag records a trailing comma after a final spread, which a
+    // literal may have but a rest element may not.
+    Spread(Option<Span>, Expr, bool)
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum PropKey {
-    Id(Option<Span>, String),
-    String(Option<Span>, StringLiteral),
-    Number(Option<Span>, NumberLiteral),
-    Computed(Option<Span>, Expr)
+    Id(Option<Span>, Name<'static>),
+    String(Option<Span>, StringLiteral<'static>),
+    Number(Option<Span>, NumberLiteral<'static>),
+    Computed(Option<Span>, Expr),
+    Private(PrivateName)
+}
+
+impl PropKey {
+    // The statically known name of a non-computed key.
+    pub fn name(&self) -> Option<&str> {
+        match *self {
+            PropKey::Id(_, ref name) => Some(name.as_ref()),
+            PropKey::String(_, ref lit) => Some(&lit.value),
+            _ => None
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
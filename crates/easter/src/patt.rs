@@ -13,8 +13,7 @@ pub struct RestPatt<T> {
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum CompoundPatt<T> {
     Arr(Option<Span>, Vec<Option<Patt<T>>>, Option<Box<RestPatt<T>>>),
-    // TODO add Rest param to support object spread/rest
-    Obj(Option<Span>, Vec<PropPatt<T>>)
+    Obj(Option<Span>, Vec<PropPatt<T>>, Option<Box<RestPatt<T>>>)
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
@@ -31,7 +30,7 @@ pub enum PropPatt<T> {
 pub enum Patt<T> {
     Simple(T),
     Compound(CompoundPatt<T>),
-    Assign(Option<Span>, T, Box<Expr>)
+    Assign(Option<Span>, Box<Patt<T>>, Box<Expr>)
 }
 
 impl<T> Patt<T> {
@@ -44,6 +43,55 @@ impl<T> Patt<T> {
     }
 }
 
+impl Patt<Id> {
+    // The identifiers bound by the pattern, in source order.
+    pub fn bound_ids(&self) -> Vec<&Id> {
+        let mut ids = Vec::new();
+        self.collect_ids(&mut ids);
+        ids
+    }
+
+    fn collect_ids<'a>(&'a self, ids: &mut Vec<&'a Id>) {
+        match *self {
+            Patt::Simple(ref id) => { ids.push(id); }
+            Patt::Compound(ref patt) => { patt.collect_ids(ids); }
+            Patt::Assign(_, ref patt, _) => { patt.collect_ids(ids); }
+        }
+    }
+}
+
+impl CompoundPatt<Id> {
+    pub fn bound_ids(&self) -> Vec<&Id> {
+        let mut ids = Vec::new();
+        self.collect_ids(&mut ids);
+        ids
+    }
+
+    fn collect_ids<'a>(&'a self, ids: &mut Vec<&'a Id>) {
+        match *self {
+            CompoundPatt::Arr(_, ref elts, ref rest) => {
+                for elt in elts.iter().filter_map(|elt| elt.as_ref()) {
+                    elt.collect_ids(ids);
+                }
+                if let Some(ref rest) = *rest {
+                    rest.patt.collect_ids(ids);
+                }
+            }
+            CompoundPatt::Obj(_, ref props, ref rest) => {
+                for prop in props {
+                    match *prop {
+                        PropPatt::Regular(_, _, ref patt) => { patt.collect_ids(ids); }
+                        PropPatt::Shorthand(_, ref id, _) => { ids.push(id); }
+                    }
+                }
+                if let Some(ref rest) = *rest {
+                    rest.patt.collect_ids(ids);
+                }
+            }
+        }
+    }
+}
+
 
 impl<T: TrackingRef> TrackingRef for Patt<T> {
     fn tracking_ref(&self) -> &Option<Span> {
@@ -3,6 +3,7 @@ use joker::token::StringLiteral;
 
 use id::Id;
 use expr::Expr;
+use fun::Fun;
 use decl::{Decl, Dtor, ConstDtor, Import, Export};
 use patt::{Patt};
 use punc::Semi;
@@ -27,16 +28,31 @@ pub enum Stmt {
     For(Option<Span>, Option<Box<ForHead>>, Option<Expr>, Option<Expr>, Box<Stmt>),
     ForIn(Option<Span>, Box<ForInHead>, Expr, Box<Stmt>),
     ForOf(Option<Span>, Box<ForOfHead>, Expr, Box<Stmt>),
-    Debugger(Option<Span>, Semi)
+    ForAwait(Option<Span>, Box<ForOfHead>, Expr, Box<Stmt>),
+    Debugger(Option<Span>, Semi),
+    // A function declaration as the body of an if statement or label, which
+    // Annex B allows in sloppy code.
+    Fun(Fun),
+    // The tokens skipped over by a parser recovering from a syntax error.
+    Error(Option<Span>)
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Body<Item> {
     pub location: Option<Span>,
+    pub hashbang: Option<Hashbang>,
     pub dirs: Vec<Dir>,
     pub items: Vec<Item>
 }
 
+// The `#!` interpreter line at the start of a script or module, with `text`
+// holding everything after the `#!`.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Hashbang {
+    pub location: Option<Span>,
+    pub text: String
+}
+
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Block {
     pub location: Option<Span>,
@@ -44,7 +60,7 @@ pub struct Block {
 }
 
 pub fn empty_script() -> Body<StmtListItem> {
-    Body { location: None, dirs: vec![], items: vec![]  }
+    Body { location: None, hashbang: None, dirs: vec![], items: vec![]  }
 }
 
 pub type Script = Body<StmtListItem>;
@@ -82,6 +98,89 @@ impl Stmt {
             _ => Err(self)
         }
     }
+
+    // The identifiers declared with `var` anywhere in this statement, not
+    // counting nested functions, in source order.
+    pub fn var_declared_ids(&self) -> Vec<&Id> {
+        let mut ids = Vec::new();
+        self.collect_var_ids(&mut ids);
+        ids
+    }
+
+    fn collect_var_ids<'a>(&'a self, ids: &mut Vec<&'a Id>) {
+        match *self {
+            Stmt::Var(_, ref dtors, _) => {
+                ids.extend(dtors.iter().flat_map(|dtor| dtor.bound_ids()));
+            }
+            Stmt::Block(ref block) => { collect_items_var_ids(&block.items, ids); }
+            Stmt::If(_, _, ref cons, ref alt) => {
+                cons.collect_var_ids(ids);
+                if let Some(ref alt) = *alt {
+                    alt.collect_var_ids(ids);
+                }
+            }
+            Stmt::Label(_, _, ref body)
+          | Stmt::With(_, _, ref body)
+          | Stmt::While(_, _, ref body)
+          | Stmt::DoWhile(_, ref body, _, _) => { body.collect_var_ids(ids); }
+            Stmt::Switch(_, _, ref cases) => {
+                for case in cases {
+                    collect_items_var_ids(&case.body, ids);
+                }
+            }
+            Stmt::Try(_, ref body, ref catch, ref finally) => {
+                collect_items_var_ids(&body.items, ids);
+                if let Some(ref catch) = *catch {
+                    collect_items_var_ids(&catch.body.items, ids);
+                }
+                if let Some(ref finally) = *finally {
+                    collect_items_var_ids(&finally.items, ids);
+                }
+            }
+            Stmt::For(_, ref head, _, _, ref body) => {
+                if let Some(ref head) = *head {
+                    if let ForHead::Var(_, ref dtors) = **head {
+                        ids.extend(dtors.iter().flat_map(|dtor| dtor.bound_ids()));
+                    }
+                }
+                body.collect_var_ids(ids);
+            }
+            Stmt::ForIn(_, ref head, _, ref body) => {
+                match **head {
+                    ForInHead::VarInit(_, ref id, _) => { ids.push(id); }
+                    ForInHead::Var(_, ref patt) => { ids.extend(patt.bound_ids()); }
+                    _ => {}
+                }
+                body.collect_var_ids(ids);
+            }
+            Stmt::ForOf(_, ref head, _, ref body)
+          | Stmt::ForAwait(_, ref head, _, ref body) => {
+                if let ForOfHead::Var(_, ref patt) = **head {
+                    ids.extend(patt.bound_ids());
+                }
+                body.collect_var_ids(ids);
+            }
+            _ => {}
+        }
+    }
+
+    // The function declared by this statement, which Annex B allows as the
+    // body of a label.
+    fn declared_fun(&self) -> Option<&Fun> {
+        match *self {
+            Stmt::Fun(ref fun) => Some(fun),
+            Stmt::Label(_, _, ref body) => body.declared_fun(),
+            _ => None
+        }
+    }
+}
+
+fn collect_items_var_ids<'a>(items: &'a [StmtListItem], ids: &mut Vec<&'a Id>) {
+    for item in items {
+        if let StmtListItem::Stmt(ref stmt) = *item {
+            stmt.collect_var_ids(ids);
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
@@ -126,14 +225,16 @@ pub struct Case {
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub struct Dir {
     pub location: Option<Span>,
-    pub string: StringLiteral,
+    pub string: StringLiteral<'static>,
     pub semi: Semi
 }
 
 impl Dir {
+    // The directive's source text without its quotes, so that escaped
+    // forms like 'use\x20strict' are not mistaken for a pragma.
     pub fn pragma(&self) -> &str {
         if let Some(ref source) = self.string.source {
-            source
+            &source[1..source.len() - 1]
         } else {
             &self.string.value
         }
@@ -147,6 +248,45 @@ pub enum ModItem {
     StmtListItem(StmtListItem)
 }
 
+impl ModItem {
+    pub fn is_directive(&self) -> bool {
+        match *self {
+            ModItem::StmtListItem(ref item) => item.is_directive(),
+            _ => false
+        }
+    }
+
+    pub fn to_directive(&self) -> Option<Dir> {
+        match *self {
+            ModItem::StmtListItem(ref item) => item.to_directive(),
+            _ => None
+        }
+    }
+
+    // Imports and all declarations, functions included, are lexical at the
+    // top level of a module.
+    pub fn lexically_declared_ids(&self) -> Vec<&Id> {
+        match *self {
+            ModItem::Import(ref import) => import.bound_ids(),
+            ModItem::Export(Export::Decl(ref decl)) => decl.bound_ids(),
+            ModItem::Export(Export::DefaultFun(_, ref fun)) => fun.kind.id().into_iter().collect(),
+            ModItem::Export(Export::DefaultClass(_, ref class)) => class.id.iter().collect(),
+            ModItem::Export(_) => vec![],
+            ModItem::StmtListItem(ref item) => item.lexically_declared_ids()
+        }
+    }
+
+    pub fn var_declared_ids(&self) -> Vec<&Id> {
+        match *self {
+            ModItem::Export(Export::Var(_, ref dtors, _)) => {
+                dtors.iter().flat_map(|dtor| dtor.bound_ids()).collect()
+            }
+            ModItem::StmtListItem(ref item) => item.var_declared_ids(),
+            _ => vec![]
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
 pub enum StmtListItem {
     Decl(Decl),
@@ -178,4 +318,31 @@ impl StmtListItem {
     pub fn into_mod_item(self) -> ModItem {
         ModItem::StmtListItem(self)
     }
+
+    // The identifiers this item declares in the enclosing block, including
+    // those of function declarations, which are only var-like at the top
+    // level of a function or script.
+    pub fn lexically_declared_ids(&self) -> Vec<&Id> {
+        match *self {
+            StmtListItem::Decl(ref decl) => decl.bound_ids(),
+            StmtListItem::Stmt(ref stmt) => {
+                stmt.declared_fun().and_then(|fun| fun.kind.id()).into_iter().collect()
+            }
+        }
+    }
+
+    pub fn var_declared_ids(&self) -> Vec<&Id> {
+        match *self {
+            StmtListItem::Decl(_) => vec![],
+            StmtListItem::Stmt(ref stmt) => stmt.var_declared_ids()
+        }
+    }
+
+    pub fn fun_declaration(&self) -> Option<&Fun> {
+        match *self {
+            StmtListItem::Decl(Decl::Fun(ref fun)) => Some(fun),
+            StmtListItem::Decl(_) => None,
+            StmtListItem::Stmt(ref stmt) => stmt.declared_fun()
+        }
+    }
 }
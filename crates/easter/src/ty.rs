@@ -0,0 +1,116 @@
+use joker::track::*;
+use joker::token::{NumberLiteral, StringLiteral};
+
+use id::Id;
+use obj::PropKey;
+use punc::Semi;
+
+// Types only appear with the type annotation syntax extension, which covers
+// the common ground of TypeScript and Flow.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum Type {
+    // A possibly qualified name with type arguments, which also covers the
+    // predefined types like `number` and `any`.
+    Ref(Option<Span>, TypeName, Vec<Type>),
+    Void(Option<Span>),
+    Null(Option<Span>),
+    This(Option<Span>),
+    True(Option<Span>),
+    False(Option<Span>),
+    Number(Option<Span>, NumberLiteral<'static>),
+    String(Option<Span>, StringLiteral<'static>),
+    Typeof(Option<Span>, TypeName),
+    Keyof(Option<Span>, Box<Type>),
+    Array(Option<Span>, Box<Type>),
+    Tuple(Option<Span>, Vec<Type>),
+    Object(Option<Span>, Vec<TypeMember>),
+    Fun(Option<Span>, Box<FunType>),
+    Union(Option<Span>, Vec<Type>),
+    Intersection(Option<Span>, Vec<Type>),
+    Paren(Option<Span>, Box<Type>),
+    // `A extends B ? C : D`
+    Conditional(Option<Span>, Box<Type>, Box<Type>, Box<Type>, Box<Type>),
+    // Only the target of an `as const` assertion.
+    Const(Option<Span>)
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum TypeName {
+    Id(Id),
+    Qualified(Option<Span>, Box<TypeName>, Id)
+}
+
+// `T extends U = V`
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct TypeParam {
+    pub location: Option<Span>,
+    pub id: Id,
+    pub constraint: Option<Type>,
+    pub default: Option<Type>
+}
+
+// The signature of a function type, or of a method or call signature in an
+// object type. Only function types require the return type.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct FunType {
+    pub location: Option<Span>,
+    pub type_params: Vec<TypeParam>,
+    pub params: Vec<ParamType>,
+    pub ret: Option<Type>
+}
+
+// `...id?: T`
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct ParamType {
+    pub location: Option<Span>,
+    pub rest: bool,
+    pub id: Id,
+    pub optional: bool,
+    pub ty: Option<Type>
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub enum TypeMember {
+    Prop(PropType),
+    Method(Option<Span>, PropKey, bool, FunType),
+    // `[id: K]: T`
+    Index(Option<Span>, Id, Type, Type),
+    Call(FunType)
+}
+
+// `readonly key?: T`
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct PropType {
+    pub location: Option<Span>,
+    pub readonly: bool,
+    pub key: PropKey,
+    pub optional: bool,
+    pub ty: Option<Type>
+}
+
+// The `?` of an optional parameter and its `: T`.
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Annot {
+    pub location: Option<Span>,
+    pub optional: bool,
+    pub ty: Option<Type>
+}
+
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct Interface {
+    pub location: Option<Span>,
+    pub id: Id,
+    pub type_params: Vec<TypeParam>,
+    pub extends: Vec<Type>,
+    pub body: Vec<TypeMember>
+}
+
+// `type Id<T> = U;`
+#[derive(Debug, PartialEq, Clone, TrackingRef, TrackingMut, Untrack)]
+pub struct TypeAlias {
+    pub location: Option<Span>,
+    pub id: Id,
+    pub type_params: Vec<TypeParam>,
+    pub ty: Type,
+    pub semi: Semi
+}
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::default::Default;
 use std::rc::Rc;
@@ -6,6 +7,8 @@ use joker::word::Name;
 use joker::track::span;
 use easter::stmt::Stmt;
 use easter::id::Id;
+use easter::obj::PrivateName;
+use error::Error;
 use result::Result;
 use parser::{Parser, Strict};
 
@@ -14,19 +17,37 @@ pub trait WithContext {
       where F: FnOnce(&mut Self) -> Result<Stmt>;
     fn allow_in<F, T>(&mut self, allow_in: bool, parse: F) -> Result<T>
       where F: FnOnce(&mut Self) -> Result<T>;
+    fn allow_arguments<F, T>(&mut self, allow_arguments: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_function<F, T>(&mut self, generator: bool, is_async: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_parameters<F, T>(&mut self, generator: bool, is_async: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn with_super<F, T>(&mut self, property: bool, call: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
+    fn in_class_body<F, T>(&mut self, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>;
 }
 
-impl WithContext for Parser {
+impl<'a> WithContext for Parser<'a> {
     fn with_labels<F>(&mut self, mut labels: Vec<Id>, label_type: LabelType, op: F) -> Result<Stmt>
       where F: FnOnce(&mut Self) -> Result<Stmt>
     {
         let mut label_strings = Vec::new();
+        let mut duplicate = None;
         for id in labels.iter() {
             let label = Rc::new(id.name.clone());
+            if self.context.labels.contains_key(&label) {
+                duplicate = Some(id.clone());
+                break;
+            }
             self.context.labels.insert(label.clone(), label_type);
             label_strings.push(label);
         }
-        let result = op(self);
+        let result = match duplicate {
+            Some(id) => Err(Error::DuplicateLabel(id)),
+            None => op(self)
+        };
         for label in label_strings {
             self.context.labels.remove(&label);
         }
@@ -47,6 +68,83 @@ impl WithContext for Parser {
         replace(&mut self.context.allow_in, allow_in);
         result
     }
+
+    // Field initializers and static blocks may not refer to `arguments`,
+    // and neither may the arrow functions inside them.
+    fn allow_arguments<F, T>(&mut self, allow_arguments: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let allow_arguments = replace(&mut self.context.allow_arguments, allow_arguments);
+        let result = parse(self);
+        replace(&mut self.context.allow_arguments, allow_arguments);
+        result
+    }
+
+    // Parses with `yield` and `await` treated as they are in a function of
+    // the given kind.
+    fn in_function<F, T>(&mut self, generator: bool, is_async: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let generator = replace(&mut self.context.generator, generator);
+        let is_async = replace(&mut self.context.is_async, is_async);
+        let parameters = replace(&mut self.context.parameters, false);
+        let static_block = replace(&mut self.context.static_block, false);
+        let result = parse(self);
+        replace(&mut self.context.generator, generator);
+        replace(&mut self.context.is_async, is_async);
+        replace(&mut self.context.parameters, parameters);
+        replace(&mut self.context.static_block, static_block);
+        result
+    }
+
+    // Like `in_function`, but for the formal parameters, where `yield` and
+    // `await` are keywords but yield and await expressions are errors.
+    fn in_parameters<F, T>(&mut self, generator: bool, is_async: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        self.in_function(generator, is_async, |this| {
+            this.context.parameters = true;
+            parse(this)
+        })
+    }
+
+    // Parses with `super.x` and `super()` allowed or not. Function contexts
+    // inherit these from the enclosing code, which is what arrow functions
+    // need; everything else that starts a function sets them explicitly.
+    fn with_super<F, T>(&mut self, property: bool, call: bool, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let property = replace(&mut self.context.allow_super_property, property);
+        let call = replace(&mut self.context.allow_super_call, call);
+        let result = parse(self);
+        replace(&mut self.context.allow_super_property, property);
+        replace(&mut self.context.allow_super_call, call);
+        result
+    }
+
+    // Parses a class body with a fresh scope for private names. References
+    // to names the body does not declare are left for the enclosing class
+    // body to resolve, and are errors if there is none.
+    fn in_class_body<F, T>(&mut self, parse: F) -> Result<T>
+      where F: FnOnce(&mut Self) -> Result<T>
+    {
+        let scope = Rc::new(RefCell::new(PrivateScope::new()));
+        let outer = replace(&mut self.context.private_scope, Some(scope.clone()));
+        let result = parse(self);
+        replace(&mut self.context.private_scope, outer.clone());
+        let result = result?;
+        let scope = scope.borrow();
+        for name in scope.references.iter() {
+            if scope.declared.contains_key(&name.name) {
+                continue;
+            }
+            match outer {
+                Some(ref outer) => { outer.borrow_mut().references.push(name.clone()); }
+                None => { return Err(Error::UndeclaredPrivateName(name.location, name.name.clone())); }
+            }
+        }
+        Ok(result)
+    }
 }
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
@@ -55,6 +153,50 @@ pub enum LabelType {
     Iteration
 }
 
+// A getter and a setter of the same private name and placement combine into
+// an accessor pair; any other redeclaration is an error.
+#[derive(Debug, Eq, PartialEq, Copy, Clone)]
+pub enum PrivateKind {
+    Field,
+    Method,
+    Getter,
+    Setter,
+    Accessor
+}
+
+// The private names declared by a class body, and the references to private
+// names made within it. The scope is shared by the contexts of the functions
+// nested in the class body, so that their references are collected too.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PrivateScope {
+    pub declared: HashMap<String, (PrivateKind, bool)>,
+    pub references: Vec<PrivateName>
+}
+
+impl PrivateScope {
+    pub fn new() -> PrivateScope {
+        PrivateScope {
+            declared: HashMap::new(),
+            references: Vec::new()
+        }
+    }
+
+    pub fn declare(&mut self, name: &PrivateName, kind: PrivateKind, is_static: bool) -> Result<()> {
+        let kind = match self.declared.get(&name.name) {
+            None => kind,
+            Some(&(PrivateKind::Getter, was_static))
+                if kind == PrivateKind::Setter && was_static == is_static => PrivateKind::Accessor,
+            Some(&(PrivateKind::Setter, was_static))
+                if kind == PrivateKind::Getter && was_static == is_static => PrivateKind::Accessor,
+            Some(_) => {
+                return Err(Error::DuplicatePrivateName(name.location, name.name.clone()));
+            }
+        };
+        self.declared.insert(name.name.clone(), (kind, is_static));
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 pub enum Goal {
     Script,
@@ -70,10 +212,19 @@ impl Default for Goal {
 pub struct Context {
     pub strict: Strict,
     pub function: bool,
+    pub generator: bool,
+    pub is_async: bool,
+    pub parameters: bool,
+    pub static_block: bool,
     pub iteration: bool,
     pub switch: bool,
     pub allow_in: bool,
-    pub labels: HashMap<Rc<Name>, LabelType>
+    pub allow_arguments: bool,
+    pub allow_arrow_return_type: bool,
+    pub allow_super_property: bool,
+    pub allow_super_call: bool,
+    pub private_scope: Option<Rc<RefCell<PrivateScope>>>,
+    pub labels: HashMap<Rc<Name<'static>>, LabelType>
 }
 
 impl Context {
@@ -81,20 +232,38 @@ impl Context {
         Context {
             strict: Strict::Unknown,
             function: false,
+            generator: false,
+            is_async: false,
+            parameters: false,
+            static_block: false,
             iteration: false,
             switch: false,
             allow_in: true,
+            allow_arguments: true,
+            allow_arrow_return_type: true,
+            allow_super_property: false,
+            allow_super_call: false,
+            private_scope: None,
             labels: HashMap::new()
         }
     }
 
-    pub fn new_function(&self) -> Context {
+    pub fn new_function(&self, generator: bool, is_async: bool) -> Context {
         Context {
             strict: self.strict,
             function: true,
+            generator: generator,
+            is_async: is_async,
+            parameters: false,
+            static_block: false,
             iteration: false,
             switch: false,
             allow_in: true,
+            allow_arguments: true,
+            allow_arrow_return_type: true,
+            allow_super_property: self.allow_super_property,
+            allow_super_call: self.allow_super_call,
+            private_scope: self.private_scope.clone(),
             labels: HashMap::new()
         }
     }
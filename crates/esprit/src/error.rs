@@ -13,28 +13,60 @@ use result::Result;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum Error {
-    UnexpectedToken(Token, &'static str),
-    FailedASI(Token),
+    UnexpectedToken(Token<'static>, &'static str),
+    FailedASI(Token<'static>),
     LexError(joker::error::Error),
     TopLevelReturn(Span),
-    IllegalBreak(Token),
-    IllegalContinue(Token),
+    IllegalBreak(Token<'static>),
+    IllegalContinue(Token<'static>),
     InvalidLabel(Id),
     InvalidLabelType(Id),
     ContextualKeyword(Span, Atom),
     IllegalStrictBinding(Span, Atom),
-    UnexpectedDirective(Option<Span>, StringLiteral),
+    YieldInParameters(Span),
+    AwaitInParameters(Span),
+    ArgumentsInClassInitializer(Span),
+    UnexpectedDirective(Option<Span>, StringLiteral<'static>),
     UnexpectedModule(Option<Span>),
     ImportInScript(Import),
     ExportInScript(Export),
     ForOfLetExpr(Span),
-    DuplicateDefault(Token),
-    StrictWith(Token),
-    ThrowArgument(Token),
-    OrphanTry(Token),
+    ForOfAsync(Option<Span>),
+    DuplicateDefault(Token<'static>),
+    StrictWith(Token<'static>),
+    ThrowArgument(Token<'static>),
+    OrphanTry(Token<'static>),
     InvalidLHS(Option<Span>, cover::Error),
     UnsupportedFeature(&'static str),
-    CompoundParamWithUseStrict(CompoundPatt<Id>)
+    CompoundParamWithUseStrict(CompoundPatt<Id>),
+    DuplicateConstructor(Option<Span>),
+    IllegalConstructor(Option<Span>),
+    StaticPrototype(Option<Span>),
+    DuplicateExport(Option<Span>, String),
+    UndeclaredExport(Option<Span>, String),
+    IllegalTemplateEscape(Span),
+    IllegalSuperProperty(Span),
+    IllegalSuperCall(Span),
+    ImportMetaOutsideModule(Span),
+    UnaryBeforeExponent(Span),
+    MixedCoalesce(Option<Span>),
+    IllegalOptionalChain(Span),
+    IllegalPrivateName(Option<Span>),
+    DuplicatePrivateName(Option<Span>, String),
+    UndeclaredPrivateName(Option<Span>, String),
+    MismatchedJSXTag(Option<Span>),
+    LegacyOctalLiteral(Option<Span>),
+    LegacyOctalEscape(Option<Span>),
+    LegacyFunctionDeclaration(Span),
+    ForInInitializer(Option<Span>),
+    DuplicateProto(Option<Span>),
+    HTMLCommentInModule(Span),
+    InvalidDeclarator(Option<Span>),
+    DuplicateDeclaration(Option<Span>, String),
+    LexicalVarConflict(Option<Span>, String),
+    DuplicateParameter(Option<Span>, String),
+    DuplicateLabel(Id),
+    IllegalDecorator(Option<Span>)
 }
 
 impl StdError for Error {
@@ -50,11 +82,15 @@ impl StdError for Error {
             Error::InvalidLabelType(_) => "invalid label type",
             Error::ContextualKeyword(_, _) => "contextual keyword",
             Error::IllegalStrictBinding(_, _) => "illegal strict binding",
+            Error::YieldInParameters(_) => "yield expression in formal parameters",
+            Error::AwaitInParameters(_) => "await expression in formal parameters",
+            Error::ArgumentsInClassInitializer(_) => "arguments in a field initializer or static block",
             Error::UnexpectedDirective(_, _) => "unexpected directive",
             Error::UnexpectedModule(_) => "unexpected module",
             Error::ImportInScript(_) => "import in script",
             Error::ExportInScript(_) => "export in script",
             Error::ForOfLetExpr(_) => "for-of-let expr",
+            Error::ForOfAsync(_) => "for-of loop variable named async",
             Error::DuplicateDefault(_) => "duplicate default",
             Error::StrictWith(_) => "strict with",
             Error::ThrowArgument(_) => "throw argument",
@@ -62,6 +98,34 @@ impl StdError for Error {
             Error::InvalidLHS(_, ref err) => err.description(),
             Error::UnsupportedFeature(_) => "unsupported feature",
             Error::CompoundParamWithUseStrict(_) => "compound param with use strict",
+            Error::DuplicateConstructor(_) => "duplicate constructor",
+            Error::IllegalConstructor(_) => "class constructor may not be a special method",
+            Error::StaticPrototype(_) => "static class member named prototype",
+            Error::DuplicateExport(_, _) => "duplicate export name",
+            Error::UndeclaredExport(_, _) => "export of a name that is not declared in the module",
+            Error::IllegalTemplateEscape(_) => "illegal escape sequence in untagged template",
+            Error::IllegalSuperProperty(_) => "super property outside of a method",
+            Error::IllegalSuperCall(_) => "super call outside of a derived class constructor",
+            Error::ImportMetaOutsideModule(_) => "import.meta outside of a module",
+            Error::UnaryBeforeExponent(_) => "unparenthesized unary expression before '**'",
+            Error::MixedCoalesce(_) => "'??' mixed with '||' or '&&' without parentheses",
+            Error::IllegalOptionalChain(_) => "invalid optional chain",
+            Error::IllegalPrivateName(_) => "private name not allowed here",
+            Error::DuplicatePrivateName(_, _) => "duplicate private name",
+            Error::UndeclaredPrivateName(_, _) => "reference to undeclared private name",
+            Error::MismatchedJSXTag(_) => "JSX closing tag does not match its opening tag",
+            Error::LegacyOctalLiteral(_) => "legacy octal literal",
+            Error::LegacyOctalEscape(_) => "legacy octal escape sequence",
+            Error::LegacyFunctionDeclaration(_) => "function declaration as the body of an if statement or label",
+            Error::ForInInitializer(_) => "initializer in for-in loop head",
+            Error::DuplicateProto(_) => "duplicate __proto__ property in object literal",
+            Error::HTMLCommentInModule(_) => "HTML-like comment in module",
+            Error::InvalidDeclarator(_) => "pattern with a default value used as a declarator",
+            Error::DuplicateDeclaration(_, _) => "duplicate lexical declaration",
+            Error::LexicalVarConflict(_, _) => "lexical declaration conflicts with a var declaration",
+            Error::DuplicateParameter(_, _) => "duplicate parameter name",
+            Error::DuplicateLabel(_) => "duplicate label",
+            Error::IllegalDecorator(_) => "decorator on a constructor, or on something other than a class or class element",
         }
     }
 
@@ -84,7 +148,8 @@ impl fmt::Display for Error {
 #[derive(Debug, PartialEq, Clone)]
 pub enum Check {
     Strict(Error),
-    Module(Error)
+    Module(Error),
+    Script(Error)
 }
 
 impl Check {
@@ -98,6 +163,13 @@ impl Check {
                     Ok(())
                 }
             }
+            Check::Script(error) => {
+                if module {
+                    Ok(())
+                } else {
+                    Err(error)
+                }
+            }
         }
     }
 }
@@ -1,13 +1,15 @@
 use joker::track::{Span, span};
 use joker::token::Token;
 use easter::punc::Unop;
-use easter::expr::{Expr, ExprListItem};
-use easter::obj::DotKey;
+use easter::expr::{Expr, ExprListItem, Template};
+use easter::obj::{DotKey, PrivateName};
+use easter::ty::Type;
 
 pub enum Prefix {
     Unop(Unop),
     Inc(Span),
-    Dec(Span)
+    Dec(Span),
+    Await(Span)
 }
 
 pub enum Postfix {
@@ -15,12 +17,14 @@ pub enum Postfix {
     Dec(Span)
 }
 
-pub enum Deref {
-    Brack(Expr, Token),
-    Dot(DotKey)
+pub enum Deref<'a> {
+    Brack(Expr, Token<'a>),
+    Dot(DotKey),
+    PrivateDot(PrivateName),
+    Template(Template)
 }
 
-impl Deref {
+impl<'a> Deref<'a> {
     pub fn append_to(self, expr: Expr) -> Expr {
         match self {
             Deref::Brack(deref, end) => {
@@ -29,21 +33,29 @@ impl Deref {
             Deref::Dot(key) => {
                 Expr::Dot(span(&expr, &key), Box::new(expr), key)
             }
+            Deref::PrivateDot(key) => {
+                Expr::PrivateDot(span(&expr, &key), Box::new(expr), key)
+            }
+            Deref::Template(template) => {
+                Expr::TaggedTemplate(span(&expr, &template), Box::new(expr), template)
+            }
         }
     }
 }
 
-pub enum Suffix {
-    Deref(Deref),
-    Arguments(Arguments)
+pub enum Suffix<'a> {
+    Deref(Deref<'a>),
+    Arguments(Arguments<'a>),
+    NonNull(Token<'a>),
+    TypeArgs(Vec<Type>, Token<'a>)
 }
 
-pub struct Arguments {
+pub struct Arguments<'a> {
     pub args: Vec<ExprListItem>,
-    pub end: Token
+    pub end: Token<'a>
 }
 
-impl Arguments {
+impl<'a> Arguments<'a> {
     pub fn append_to(self, expr: Expr) -> Expr {
         Expr::Call(span(&expr, &Some(self.end.location)), Box::new(expr), self.args)
     }
@@ -53,11 +65,17 @@ impl Arguments {
     }
 }
 
-impl Suffix {
+impl<'a> Suffix<'a> {
     pub fn append_to(self, expr: Expr) -> Expr {
         match self {
             Suffix::Deref(deref) => deref.append_to(expr),
-            Suffix::Arguments(args) => args.append_to(expr)
+            Suffix::Arguments(args) => args.append_to(expr),
+            Suffix::NonNull(end) => {
+                Expr::NonNull(span(&expr, &Some(end.location)), Box::new(expr))
+            }
+            Suffix::TypeArgs(types, end) => {
+                Expr::TypeArgs(span(&expr, &Some(end.location)), Box::new(expr), types)
+            }
         }
     }
 }
@@ -29,23 +29,40 @@ mod stack;
 // type Parser<I: Iterator<Item=char>> = parser::Parser<I>;
 
 use easter::stmt::{Script, Module};
-use parser::Parser;
+use error::Error;
 use result::Result;
 
-pub use parser::Program;
+pub use parser::{Parser, Program};
 
 pub fn script(s: &str) -> Result<Script> {
-    Parser::from_chars(s.chars()).script(false)
+    Parser::from_source(s).script(false)
 }
 
 pub fn strict(s: &str) -> Result<Script> {
-    Parser::from_chars(s.chars()).script(true)
+    Parser::from_source(s).script(true)
 }
 
 pub fn module(s: &str) -> Result<Module> {
-    Parser::from_chars(s.chars()).module()
+    Parser::from_source(s).module()
 }
 
 pub fn program(s: &str) -> Result<Program> {
-    Parser::from_chars(s.chars()).program()
+    Parser::from_source(s).program()
+}
+
+// The tolerant parsers replace each statement that fails to parse with a
+// `Stmt::Error` node, and return the errors alongside the tree.
+
+pub fn tolerant_script(s: &str) -> Result<(Script, Vec<Error>)> {
+    let mut parser = Parser::from_source(s);
+    parser.tolerant = true;
+    let script = parser.script(false)?;
+    Ok((script, parser.take_errors()))
+}
+
+pub fn tolerant_module(s: &str) -> Result<(Module, Vec<Error>)> {
+    let mut parser = Parser::from_source(s);
+    parser.tolerant = true;
+    let module = parser.module()?;
+    Ok((module, parser.take_errors()))
 }
@@ -1,29 +1,34 @@
-use easter::decl::{ConstDtor, Decl, Dtor, DtorExt};
-use easter::expr::{Expr, ExprListItem, Assign};
+use easter::class::{Class, ClassMember, Decorator, Field, Method, MethodKind};
+use easter::cover;
+use easter::decl::{ConstDtor, Decl, Dtor, DtorExt, Export, ExportSpec, Import, ImportSpec};
+use easter::expr::{Expr, ExprListItem, Assign, Template, TemplateElement};
 use easter::fun::FunctionKind;
 use easter::fun::{Fun, Params};
 use easter::id::{Id, IdExt};
-use easter::obj::{DotKey, Prop, PropKey, PropVal};
+use easter::jsx::{JSXAttr, JSXAttrValue, JSXChild, JSXElement, JSXFragment, JSXId, JSXName};
+use easter::obj::{DotKey, PrivateName, Prop, PropKey, PropVal};
 use easter::patt::{CompoundPatt, Patt, PropPatt, RestPatt};
-use easter::punc::{Op, ToOp, Unop, UnopTag};
+use easter::punc::{BinopTag, Op, Precedence, ToOp, Unop, UnopTag};
 use easter::stmt::empty_script;
 use easter::stmt::{
-    Block, Case, Catch, Dir, ForHead, ForInHead, ForOfHead, ModItem, Module, Script, Stmt,
-    StmtListItem,
+    Block, Case, Catch, Dir, ForHead, ForInHead, ForOfHead, Hashbang, ModItem, Module, Script,
+    Stmt, StmtListItem,
 };
-use joker::lexer::Lexer;
-use joker::token::{Token, TokenData};
+use easter::ty::{Annot, FunType, Interface, ParamType, PropType, Type, TypeAlias, TypeMember, TypeName, TypeParam};
+use joker::lexer::{Lexer, Mode};
+use joker::token::{StringLiteral, Token, TokenData};
 use joker::track::*;
 use joker::word::{Atom, Name, Reserved};
 // use easter::cover::{IntoAssignTarget, IntoAssignPatt};
 
 use atom::AtomExt;
-use context::{Context, Goal, LabelType, WithContext};
+use context::{Context, Goal, LabelType, PrivateKind, WithContext};
 use error::{Check, Error};
 use expr::{Arguments, Deref, Postfix, Prefix, Suffix};
 use result::Result;
 use stack::{Infix, Stack};
 use state::State;
+use std::collections::{HashMap, HashSet};
 use std::mem::replace;
 use std::rc::Rc;
 use tokens::{First, Follows, HasLabelType};
@@ -33,12 +38,19 @@ use track::{SpanTracker, Tracking};
 use tristate::TriState;
 pub use tristate::TriState as Strict;
 
-pub struct Parser {
+pub struct Parser<'a> {
     pub goal: Goal,
     pub validate: bool, // should we do strict mode validation as eagerly as possible?
     pub deferred: Vec<Check>, // strict mode checks that haven't been performed yet
-    pub lexer: Lexer,
+    pub lexer: Lexer<'a>,
     pub context: Context,
+    pub jsx: bool, // should "<" in expressions begin JSX elements?
+    pub annex_b: bool, // allow the syntactic web browser extensions of Annex B in sloppy code?
+    pub types: bool, // parse the type annotation syntax extension?
+    pub decorators: bool, // parse "@" decorators on classes and class elements?
+    pub tolerant: bool, // should errors in statements be skipped over and collected?
+    pub errors: Vec<Error>, // errors recovered from so far in tolerant mode
+    cover_errors: Vec<Error>, // errors in literals that may yet turn out to be patterns
 }
 
 enum ProgramItems {
@@ -79,11 +91,40 @@ impl Untrack for Program {
     }
 }
 
+fn dtor_from_init<T: DtorExt>(lhs: Patt<Id>, rhs: Expr) -> Result<T> {
+    T::from_init(lhs, rhs).map_err(|lhs| Error::InvalidDeclarator(*lhs.tracking_ref()))
+}
+
+fn annotate(dtor: Dtor, ty: Option<Type>) -> Dtor {
+    match dtor {
+        Dtor::Simple(location, id, _, init) => Dtor::Simple(location, id, ty, init),
+        Dtor::Compound(location, patt, _, init) => Dtor::Compound(location, patt, ty, init),
+    }
+}
+
+fn error_stmt(location: Option<Span>) -> StmtListItem {
+    StmtListItem::Stmt(Stmt::Error(location))
+}
+
+fn error_item(location: Option<Span>) -> ModItem {
+    error_stmt(location).into_mod_item()
+}
+
+// When parentheses hold neither an expression nor arrow parameters, an error
+// in a literal that couldn't be a pattern says more than a missing `=>`.
+fn cover_or_arrow_error(error: Error, arrow_error: Error) -> Error {
+    match error {
+        Error::DuplicateProto(_) => error,
+        _ => arrow_error,
+    }
+}
+
 fn unexpected_module(module: Module) -> Error {
     let Module {
         location,
         dirs,
         items,
+        ..
     } = module;
 
     // If there's a "use module" pragma, blame that.
@@ -117,7 +158,15 @@ fn unexpected_module(module: Module) -> Error {
 impl Program {
     pub fn script(self) -> Result<Script> {
         match self {
-            Program::Ambiguous(_, script) => Ok(script),
+            Program::Ambiguous(checks, script) => {
+                for check in checks {
+                    if let Check::Script(_) = check {
+                        check.perform(false)?;
+                    }
+                }
+
+                Ok(script)
+            }
             Program::Module(module) => {
                 return Err(unexpected_module(module));
             }
@@ -148,12 +197,14 @@ impl Program {
 
                 let Script {
                     location,
+                    hashbang,
                     dirs,
                     items,
                 } = script;
 
                 Ok(Module {
                     location: location,
+                    hashbang: hashbang,
                     dirs: dirs,
                     items: items.into_iter().map(|item| item.into_mod_item()).collect(),
                 })
@@ -163,31 +214,39 @@ impl Program {
     }
 }
 
-impl Parser {
-    pub fn from_chars<I>(i: I) -> Parser
-    where
-        I: Iterator<Item = char>,
-    {
-        let lexer = Lexer::new(i);
+impl<'a> Parser<'a> {
+    pub fn from_source(source: &'a str) -> Parser<'a> {
+        let lexer = Lexer::new(source);
         Parser::new(true, lexer)
     }
 
-    pub fn new(validate: bool, lexer: Lexer) -> Parser {
+    pub fn new(validate: bool, lexer: Lexer<'a>) -> Parser<'a> {
         Parser {
             goal: Goal::Unknown,
             validate: validate,
             deferred: Vec::new(),
             lexer: lexer,
             context: Context::new(),
+            jsx: false,
+            annex_b: false,
+            types: false,
+            decorators: false,
+            tolerant: false,
+            errors: Vec::new(),
+            cover_errors: Vec::new(),
         }
     }
 
+    pub fn take_errors(&mut self) -> Vec<Error> {
+        replace(&mut self.errors, Vec::new())
+    }
+
     fn take_deferred(&mut self) -> Vec<Check> {
         replace(&mut self.deferred, Vec::new())
     }
 
     fn unexpected<T>(&mut self, msg: &'static str) -> Result<T> {
-        Err(Error::UnexpectedToken(self.peek()?.clone(), msg))
+        Err(Error::UnexpectedToken(self.peek()?.clone().into_owned(), msg))
     }
 
     fn match_directive_opt(&mut self) -> Result<Option<Dir>> {
@@ -201,7 +260,7 @@ impl Parser {
                     Newline::Required,
                     |semi| Dir {
                         location: None,
-                        string: literal.clone(),
+                        string: literal.clone().into_owned(),
                         semi: semi,
                     },
                 )?));
@@ -212,17 +271,37 @@ impl Parser {
         Ok(None)
     }
 
-    fn set_module(&mut self) {
+    // Any HTML-like comments skipped while the goal was unknown would have
+    // been tokens in a module.
+    fn set_module(&mut self) -> Result<()> {
         self.goal = Goal::Module;
         self.context.strict = Strict::Yes;
+        self.lexer.set_html_comments(false);
+        match self.lexer.html_comment() {
+            Some(location) if self.validate => Err(Error::HTMLCommentInModule(location)),
+            _ => Ok(()),
+        }
     }
 
+    // A hashbang is skipped along with the whitespace and comments before the
+    // first token.
+    fn hashbang(&mut self) -> Result<Option<Hashbang>> {
+        self.peek_statement()?;
+        Ok(self.lexer.hashbang().map(|(location, text)| Hashbang {
+            location: Some(location),
+            text: text.to_string(),
+        }))
+    }
+
+    // Modules may use `await` at the top level.
     pub fn module(&mut self) -> Result<Module> {
         debug_assert_eq!(self.goal, Goal::Unknown);
-        self.set_module();
+        self.set_module()?;
+        self.context.is_async = true;
         self.span(&mut |this| {
             Ok(Module {
                 location: None,
+                hashbang: this.hashbang()?,
                 dirs: this.body_directives()?,
                 items: this.module_items()?,
             })
@@ -231,16 +310,20 @@ impl Parser {
 
     pub fn program(&mut self) -> Result<Program> {
         debug_assert_eq!(self.goal, Goal::Unknown);
+        self.lexer.set_html_comments(true);
         self.span(&mut |this| {
+            let hashbang = this.hashbang()?;
             let dirs = this.body_directives()?;
 
             match this.program_items()? {
                 ProgramItems::Script(items) => {
+                    this.check_body(&items)?;
                     let checks = this.take_deferred();
                     Ok(Program::Ambiguous(
                         checks,
                         Script {
                             location: None,
+                            hashbang: hashbang,
                             dirs: dirs,
                             items: items,
                         },
@@ -248,6 +331,7 @@ impl Parser {
                 }
                 ProgramItems::Module(items) => Ok(Program::Module(Module {
                     location: None,
+                    hashbang: hashbang,
                     dirs: dirs,
                     items: items,
                 })),
@@ -259,35 +343,140 @@ impl Parser {
         debug_assert_eq!(self.goal, Goal::Unknown);
         self.goal = Goal::Script;
         self.context.strict = TriState::from(strict);
-        self.script_body()
+        self.lexer.set_html_comments(true);
+        self.span(&mut |this| {
+            let hashbang = this.hashbang()?;
+            let mut body = this.script_body()?;
+            // A tolerant parse carries on past a stray `}`, `case` or `default`.
+            while this.tolerant && this.peek_statement()?.value != TokenData::EOF {
+                let item = this.recovering(|this| this.unexpected("unexpected token after script"), error_stmt, false)?;
+                body.items.push(item);
+                body.items.extend(this.statement_list()?);
+            }
+            Ok(Script {
+                hashbang: hashbang,
+                ..body
+            })
+        })
     }
 
     fn script_body(&mut self) -> Result<Script> {
         self.span(&mut |this| {
+            let dirs = this.body_directives()?;
+            let items = this.statement_list()?;
+            this.check_body(&items)?;
             Ok(Script {
                 location: None,
-                dirs: this.body_directives()?,
-                items: this.statement_list()?,
+                hashbang: None,
+                dirs: dirs,
+                items: items,
             })
         })
     }
 
+    // At the top level of a function or script, function declarations are
+    // var-scoped.
+    fn check_body(&mut self, items: &[StmtListItem]) -> Result<()> {
+        let mut lexical = Vec::new();
+        let mut var = Vec::new();
+        for item in items {
+            if item.fun_declaration().is_some() {
+                var.extend(item.lexically_declared_ids());
+            } else {
+                lexical.extend(item.lexically_declared_ids().into_iter().map(|id| (id, false)));
+            }
+            var.extend(item.var_declared_ids());
+        }
+        self.check_declarations(lexical, var)
+    }
+
+    // In a block, function declarations are lexical, but Annex B lets sloppy
+    // code declare the same plain function more than once.
+    fn check_block<'b, I>(&mut self, items: I) -> Result<()>
+    where
+        I: IntoIterator<Item = &'b StmtListItem>,
+    {
+        let mut lexical = Vec::new();
+        let mut var = Vec::new();
+        for item in items {
+            let legacy = match item.fun_declaration() {
+                Some(&Fun { is_async: false, kind: FunctionKind::Named(_), .. }) => true,
+                _ => false,
+            };
+            lexical.extend(item.lexically_declared_ids().into_iter().map(|id| (id, legacy)));
+            var.extend(item.var_declared_ids());
+        }
+        self.check_declarations(lexical, var)
+    }
+
+    fn check_module(&mut self, items: &[ModItem]) -> Result<()> {
+        let lexical = items
+            .iter()
+            .flat_map(|item| item.lexically_declared_ids())
+            .map(|id| (id, false))
+            .collect();
+        let var = items.iter().flat_map(|item| item.var_declared_ids()).collect();
+        self.check_declarations(lexical, var)
+    }
+
+    // Each lexical name is paired with whether a duplicate is only a legacy
+    // error.
+    fn check_declarations(&mut self, lexical: Vec<(&Id, bool)>, var: Vec<&Id>) -> Result<()> {
+        let mut declared: HashMap<&Name, bool> = HashMap::new();
+        for (id, legacy) in lexical {
+            match declared.insert(&id.name, legacy) {
+                Some(true) if legacy => {
+                    let name = id.name.as_ref().to_string();
+                    self.sloppy_check(Error::DuplicateDeclaration(id.location, name))?;
+                }
+                Some(_) => {
+                    self.early_error(Error::DuplicateDeclaration(id.location, id.name.as_ref().to_string()))?;
+                }
+                None => {}
+            }
+        }
+        for id in var {
+            if declared.contains_key(&id.name) {
+                self.early_error(Error::LexicalVarConflict(id.location, id.name.as_ref().to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
     fn body_directives(&mut self) -> Result<Vec<Dir>> {
         let mut dirs = Vec::new();
 
-        while let Some(dir) = self.match_directive_opt()? {
+        loop {
+            let dir = match self.match_directive_opt() {
+                Ok(Some(dir)) => dir,
+                Ok(None) => break,
+                Err(error) => {
+                    if !self.tolerant {
+                        return Err(error);
+                    }
+                    self.errors.push(error);
+                    break;
+                }
+            };
             match dir.pragma() {
                 "use strict" => {
                     self.context.strict = Strict::Yes;
                 }
                 "use module" if !self.context.function => {
-                    self.set_module();
+                    self.set_module()?;
                 }
                 _ => {}
             }
             dirs.push(dir);
         }
 
+        // A "use strict" directive applies to the directives before it.
+        for dir in dirs.iter() {
+            if dir.string.has_legacy_escape() {
+                self.sloppy_check(Error::LegacyOctalEscape(dir.location))?;
+            }
+        }
+
         Ok(dirs)
     }
 
@@ -309,19 +498,22 @@ impl Parser {
         let mut stmts: Vec<StmtListItem> = Vec::new();
 
         loop {
-            match self.peek()?.value {
+            let module_item = match self.peek_statement()?.value {
                 TokenData::EOF => break,
-                TokenData::Reserved(Reserved::Import) | TokenData::Reserved(Reserved::Export) => {
-                    self.force_deferred_module_validation()?;
-                    let items = self.more_module_items(
-                        stmts.into_iter().map(|stmt| stmt.into_mod_item()).collect(),
-                    )?;
-                    return Ok(ProgramItems::Module(items));
-                }
-                _ => {}
+                TokenData::Reserved(Reserved::Import) => self.import_declaration_follows()?,
+                TokenData::Reserved(Reserved::Export) => true,
+                _ => false,
+            };
+            if module_item {
+                self.force_deferred_module_validation()?;
+                self.set_module()?;
+                let items = self.more_module_items(
+                    stmts.into_iter().map(|stmt| stmt.into_mod_item()).collect(),
+                )?;
+                return Ok(ProgramItems::Module(items));
             }
 
-            stmts.push(self.stmt_list_item(true)?);
+            stmts.push(self.recovering(|this| this.stmt_list_item(true), error_stmt, false)?);
         }
 
         Ok(ProgramItems::Script(stmts))
@@ -332,33 +524,468 @@ impl Parser {
     }
 
     fn more_module_items(&mut self, mut items: Vec<ModItem>) -> Result<Vec<ModItem>> {
+        let mut exported = HashSet::new();
+        let mut locals = Vec::new();
         loop {
-            match self.peek()?.value {
+            match self.peek_statement()?.value {
                 TokenData::EOF => break,
-                // ES6: import declaration
-                TokenData::Reserved(Reserved::Import) => unimplemented!(),
-                // ES6: export declaration
-                TokenData::Reserved(Reserved::Export) => unimplemented!(),
+                TokenData::Reserved(Reserved::Import) => {
+                    if self.import_declaration_follows()? {
+                        items.push(self.recovering(|this| this.import_declaration().map(ModItem::Import), error_item, false)?);
+                        continue;
+                    }
+                }
+                TokenData::Reserved(Reserved::Export) | TokenData::At => {
+                    let item = self.recovering(|this| this.decorated_module_item(), error_item, false)?;
+                    if let ModItem::Export(ref export) = item {
+                        for (name, location) in export.exported_names() {
+                            if !exported.insert(name.to_string()) {
+                                self.early_error(Error::DuplicateExport(location, name.to_string()))?;
+                            }
+                        }
+                        // The local names of an export list must be declared
+                        // somewhere in the module.
+                        if let Export::List(_, ref specs, _) = *export {
+                            locals.extend(specs.iter().map(|spec| spec.local.clone()));
+                        }
+                    }
+                    items.push(item);
+                    continue;
+                }
                 _ => {}
             }
 
-            items.push(ModItem::StmtListItem(self.stmt_list_item(true)?));
+            items.push(self.recovering(|this| this.stmt_list_item(true).map(ModItem::StmtListItem), error_item, false)?);
         }
 
+        self.check_module(&items)?;
+        self.check_exported_bindings(&items, locals)?;
         Ok(items)
     }
 
+    fn check_exported_bindings(&mut self, items: &[ModItem], locals: Vec<DotKey>) -> Result<()> {
+        let declared: HashSet<&str> = items
+            .iter()
+            .flat_map(|item| item.lexically_declared_ids().into_iter().chain(item.var_declared_ids()))
+            .map(|id| id.name.as_ref())
+            .collect();
+        for local in locals {
+            if !declared.contains(local.value.as_ref()) {
+                self.early_error(Error::UndeclaredExport(local.location, local.value.into_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    // An "import" followed by "(" or "." is the start of an expression
+    // statement rather than an import declaration.
+    fn import_declaration_follows(&mut self) -> Result<bool> {
+        let token = self.reread(TokenData::Reserved(Reserved::Import));
+        let result = match self.peek()?.value {
+            TokenData::LParen | TokenData::Dot => false,
+            _ => true,
+        };
+        self.lexer.unread_token(token);
+        Ok(result)
+    }
+
+    // ImportDeclaration ::=
+    //   "import" ImportClause FromClause ";"
+    //   "import" ModuleSpecifier ";"
+    fn import_declaration(&mut self) -> Result<Import> {
+        let span = self.start();
+        self.reread(TokenData::Reserved(Reserved::Import));
+
+        if let TokenData::String(_) = self.peek()?.value {
+            let source = self.module_specifier()?;
+            return span.end_with_auto_semi(self, Newline::Required, |semi| {
+                Import::ForEffect(None, source, semi)
+            });
+        }
+
+        let default = match self.peek()?.value {
+            TokenData::Identifier(_) => {
+                let id = self.id(true)?;
+                if !self.matches(TokenData::Comma)? {
+                    let source = self.from_clause()?;
+                    return span.end_with_auto_semi(self, Newline::Required, |semi| {
+                        Import::Named(None, Some(id), Vec::new(), source, semi)
+                    });
+                }
+                Some(id)
+            }
+            _ => None,
+        };
+
+        match self.peek()?.value {
+            TokenData::Star => {
+                self.reread(TokenData::Star);
+                self.expect(TokenData::Identifier(Name::Atom(Atom::As)))?;
+                let ns = self.id(true)?;
+                let source = self.from_clause()?;
+                span.end_with_auto_semi(self, Newline::Required, |semi| {
+                    Import::Namespace(None, default, ns, source, semi)
+                })
+            }
+            TokenData::LBrace => {
+                let specs = self.named_imports()?;
+                let source = self.from_clause()?;
+                span.end_with_auto_semi(self, Newline::Required, |semi| {
+                    Import::Named(None, default, specs, source, semi)
+                })
+            }
+            _ => self.unexpected("expected namespace import or named imports"),
+        }
+    }
+
+    // NamedImports ::= "{" (ImportSpecifier ("," ImportSpecifier)* ","?)? "}"
+    fn named_imports(&mut self) -> Result<Vec<ImportSpec>> {
+        self.reread(TokenData::LBrace);
+        let mut specs = Vec::new();
+        while !self.matches(TokenData::RBrace)? {
+            specs.push(self.import_specifier()?);
+            if !self.matches(TokenData::Comma)? {
+                self.expect(TokenData::RBrace)?;
+                break;
+            }
+        }
+        Ok(specs)
+    }
+
+    // ImportSpecifier ::=
+    //   ImportedBinding
+    //   IdentifierName "as" ImportedBinding
+    fn import_specifier(&mut self) -> Result<ImportSpec> {
+        self.span(&mut |this| {
+            if let TokenData::Reserved(_) = this.peek()?.value {
+                let imported = this.id_name()?;
+                this.expect(TokenData::Identifier(Name::Atom(Atom::As)))?;
+                let local = this.id(true)?;
+                return Ok(ImportSpec {
+                    location: None,
+                    imported: imported,
+                    local: local,
+                });
+            }
+
+            let id = this.id(true)?;
+            let local = if this.matches(TokenData::Identifier(Name::Atom(Atom::As)))? {
+                this.id(true)?
+            } else {
+                id.clone()
+            };
+            Ok(ImportSpec {
+                location: None,
+                imported: DotKey {
+                    location: id.location,
+                    value: id.name,
+                },
+                local: local,
+            })
+        })
+    }
+
+    // ExportDeclaration ::=
+    //   "export" "*" ("as" IdentifierName)? FromClause ";"
+    //   "export" ExportClause FromClause? ";"
+    //   "export" VariableStatement
+    //   "export" Declaration
+    //   "export" "default" HoistableDeclaration
+    //   "export" "default" ClassDeclaration
+    //   "export" "default" AssignmentExpression ";"
+    fn export_declaration(&mut self) -> Result<Export> {
+        let span = self.start();
+        self.reread(TokenData::Reserved(Reserved::Export));
+
+        if self.types {
+            if let Some(decl) = self.type_declaration_opt()? {
+                return Ok(Export::Decl(decl));
+            }
+        }
+        match self.peek()?.value {
+            TokenData::Star => {
+                self.reread(TokenData::Star);
+                if self.matches(TokenData::Identifier(Name::Atom(Atom::As)))? {
+                    let name = self.id_name()?;
+                    let source = self.from_clause()?;
+                    return span.end_with_auto_semi(self, Newline::Required, |semi| {
+                        Export::NamespaceFrom(None, name, source, semi)
+                    });
+                }
+                let source = self.from_clause()?;
+                span.end_with_auto_semi(self, Newline::Required, |semi| {
+                    Export::AllFrom(None, source, semi)
+                })
+            }
+            TokenData::LBrace => {
+                let (specs, reserved) = self.export_clause()?;
+                if self.peek()?.value == TokenData::Identifier(Name::Atom(Atom::From)) {
+                    let source = self.from_clause()?;
+                    return span.end_with_auto_semi(self, Newline::Required, |semi| {
+                        Export::ListFrom(None, specs, source, semi)
+                    });
+                }
+                // Without a FromClause, each local name is a reference to a
+                // binding of this module.
+                if let Some(token) = reserved {
+                    return Err(Error::UnexpectedToken(token.into_owned(), "expected identifier"));
+                }
+                for spec in &specs {
+                    self.new_id(false, spec.local.value.clone(), spec.local.location.unwrap())?;
+                }
+                span.end_with_auto_semi(self, Newline::Required, |semi| {
+                    Export::List(None, specs, semi)
+                })
+            }
+            TokenData::Reserved(Reserved::Var) => {
+                self.reread(TokenData::Reserved(Reserved::Var));
+                let dtors = self.comma_separated(Self::declarator)?;
+                span.end_with_auto_semi(self, Newline::Required, |semi| {
+                    Export::Var(None, dtors, semi)
+                })
+            }
+            TokenData::Reserved(Reserved::Function) => Ok(Export::Decl(self.function_declaration()?)),
+            TokenData::Identifier(Name::Atom(Atom::Async)) => {
+                self.reread(TokenData::Identifier(Name::Atom(Atom::Async)));
+                if !self.async_function_follows()? {
+                    return self.unexpected("expected function after async");
+                }
+                Ok(Export::Decl(self.async_function_declaration()?))
+            }
+            TokenData::Reserved(Reserved::Class) | TokenData::At => Ok(Export::Decl(self.class_declaration()?)),
+            TokenData::Reserved(Reserved::Const) => Ok(Export::Decl(self.const_declaration()?)),
+            TokenData::Identifier(Name::Atom(Atom::Let)) => {
+                let token = self.reread(TokenData::Identifier(Name::Atom(Atom::Let)));
+                Ok(Export::Decl(self.let_declaration(token.location.start)?))
+            }
+            TokenData::Reserved(Reserved::Default) => {
+                self.reread(TokenData::Reserved(Reserved::Default));
+                match self.peek()?.value {
+                    TokenData::Reserved(Reserved::Function) => {
+                        let fun = self.function(false, false, |this| this.id_opt(true))?;
+                        Ok(span.end(self, Export::DefaultFun(None, fun)))
+                    }
+                    TokenData::Identifier(Name::Atom(Atom::Async)) => {
+                        let token = self.reread(TokenData::Identifier(Name::Atom(Atom::Async)));
+                        if self.async_function_follows()? {
+                            let fun = self.function(true, false, |this| this.id_opt(true))?;
+                            return Ok(span.end(self, Export::DefaultFun(None, fun)));
+                        }
+                        self.lexer.unread_token(token);
+                        let expr = self.allow_in(true, |this| this.assignment_expression())?;
+                        span.end_with_auto_semi(self, Newline::Required, |semi| {
+                            Export::DefaultExpr(None, expr, semi)
+                        })
+                    }
+                    TokenData::Reserved(Reserved::Class) | TokenData::At => {
+                        let class = self.decorated_class(|this| this.id_opt(true))?;
+                        Ok(span.end(self, Export::DefaultClass(None, class)))
+                    }
+                    _ => {
+                        let expr = self.allow_in(true, |this| this.assignment_expression())?;
+                        span.end_with_auto_semi(self, Newline::Required, |semi| {
+                            Export::DefaultExpr(None, expr, semi)
+                        })
+                    }
+                }
+            }
+            _ => self.unexpected("expected export declaration"),
+        }
+    }
+
+    // ExportClause ::= "{" (ExportSpecifier ("," ExportSpecifier)* ","?)? "}"
+    //
+    // Also returns the first reserved word used as a local name, which is
+    // only legal when the clause is followed by a FromClause.
+    fn export_clause(&mut self) -> Result<(Vec<ExportSpec>, Option<Token<'a>>)> {
+        self.reread(TokenData::LBrace);
+        let mut specs = Vec::new();
+        let mut reserved = None;
+        while !self.matches(TokenData::RBrace)? {
+            if reserved.is_none() {
+                if let TokenData::Reserved(_) = self.peek()?.value {
+                    reserved = Some(self.peek()?.clone());
+                }
+            }
+            specs.push(self.export_specifier()?);
+            if !self.matches(TokenData::Comma)? {
+                self.expect(TokenData::RBrace)?;
+                break;
+            }
+        }
+        Ok((specs, reserved))
+    }
+
+    // ExportSpecifier ::= IdentifierName ("as" IdentifierName)?
+    fn export_specifier(&mut self) -> Result<ExportSpec> {
+        self.span(&mut |this| {
+            let local = this.id_name()?;
+            let exported = if this.matches(TokenData::Identifier(Name::Atom(Atom::As)))? {
+                this.id_name()?
+            } else {
+                local.clone()
+            };
+            Ok(ExportSpec {
+                location: None,
+                local: local,
+                exported: exported,
+            })
+        })
+    }
+
+    // FromClause ::= "from" ModuleSpecifier
+    fn from_clause(&mut self) -> Result<StringLiteral<'static>> {
+        self.expect(TokenData::Identifier(Name::Atom(Atom::From)))?;
+        self.module_specifier()
+    }
+
+    // ModuleSpecifier ::= StringLiteral
+    fn module_specifier(&mut self) -> Result<StringLiteral<'static>> {
+        let token = self.read()?;
+        match token.value {
+            TokenData::String(literal) => {
+                if literal.has_legacy_escape() {
+                    self.sloppy_check(Error::LegacyOctalEscape(Some(token.location)))?;
+                }
+                Ok(literal.into_owned())
+            }
+            _ => Err(Error::UnexpectedToken(token.into_owned(), "expected module specifier")),
+        }
+    }
+
     fn statement_list(&mut self) -> Result<Vec<StmtListItem>> {
         let mut items = Vec::new();
-        while !self.peek()?.follow_statement_list() {
+        while !self.peek_statement()?.follow_statement_list() {
             //println!("statement at: {:?}", self.peek()?.location().unwrap().start);
-            items.push(self.stmt_list_item(true)?);
+            items.push(self.recovering(|this| this.stmt_list_item(true), error_stmt, false)?);
         }
         Ok(items)
     }
 
+    // In tolerant mode, a statement or class member that fails to parse is
+    // recorded and skipped, leaving an error node in its place. Since a class
+    // member can begin with any token, skipping a member ends at a new line.
+    fn recovering<T, F, G>(&mut self, parse: F, error_node: G, member: bool) -> Result<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+        G: FnOnce(Option<Span>) -> T,
+    {
+        if !self.tolerant {
+            return parse(self);
+        }
+        let start = self.peek()?.location.start;
+        let context = self.context.clone();
+        let deferred = self.deferred.len();
+        let cover_errors = self.cover_errors.len();
+        match parse(self) {
+            Ok(item) => Ok(item),
+            Err(error) => {
+                // The token that the parse failed at may have been consumed.
+                // After a lex error the lexer is left where it gave up, which
+                // is at the end of a line for unterminated literals.
+                let lex_error = match error {
+                    Error::UnexpectedToken(ref token, _) | Error::FailedASI(ref token)
+                        if token.location.start.offset >= start.offset =>
+                    {
+                        self.lexer.seek(token.location.start.offset as usize, token.location.start);
+                        false
+                    }
+                    Error::LexError(_) => {
+                        let (index, posn) = (self.lexer.index(), self.lexer.posn());
+                        self.lexer.seek(index, posn);
+                        true
+                    }
+                    _ => false,
+                };
+                self.errors.push(error);
+                self.context = context;
+                self.deferred.truncate(deferred);
+                self.cover_errors.truncate(cover_errors);
+                let end = self.skip_statement(start, lex_error || member);
+                Ok(error_node(Some(Span { start: start, end: end })))
+            }
+        }
+    }
+
+    // In tolerant mode, characters that can't be lexed at the start of a
+    // statement are recorded and skipped.
+    fn peek_statement(&mut self) -> Result<&Token<'a>> {
+        if self.tolerant {
+            while let Err(error) = self.lexer.peek_token(false) {
+                self.errors.push(Error::LexError(error));
+                self.lexer.skip_char();
+            }
+        }
+        self.peek()
+    }
+
+    // Skips past a `;` or a balanced block, stopping early at a `}` that
+    // closes the enclosing block, at a statement keyword on a new line or at
+    // the end of the input. Characters that can't be lexed are skipped too.
+    // A `}` that begins a line to the left of the statement is taken to close
+    // the enclosing block even if a `{` skipped over was never closed, and
+    // with `any_line` set any new line ends the skip. Returns the end of the
+    // last token skipped.
+    fn skip_statement(&mut self, start: Posn, any_line: bool) -> Posn {
+        let mut end = self.lexer.seek_posn();
+        let mut depth = 0;
+        loop {
+            let token = match self.lexer.peek_token(false) {
+                Ok(token) => token.clone(),
+                Err(_) => {
+                    self.lexer.skip_char();
+                    end = self.lexer.posn();
+                    continue;
+                }
+            };
+            let moved = token.location.start.offset > start.offset;
+            match token.value {
+                TokenData::EOF => break,
+                TokenData::RBrace if depth == 0 && moved => break,
+                TokenData::RBrace if token.newline && token.location.start.column < start.column => break,
+                TokenData::LBrace => {
+                    depth += 1;
+                }
+                TokenData::RBrace if depth > 0 => {
+                    depth -= 1;
+                }
+                _ if depth == 0 && moved && token.newline && (any_line || token.starts_statement()) => break,
+                _ => {}
+            }
+            self.lexer.reread_token();
+            end = token.location.end;
+            if token.value == TokenData::Semi && depth == 0 {
+                break;
+            }
+        }
+        end
+    }
+
+    // The `}` that ends a statement list. A tolerant parse that runs out of
+    // input closes the open blocks instead of discarding them.
+    fn close_block(&mut self) -> Result<()> {
+        if self.tolerant && self.peek()?.value == TokenData::EOF {
+            let token = self.peek()?.clone();
+            self.errors.push(Error::UnexpectedToken(token.into_owned(), "expected '}' before the end of the input"));
+            return Ok(());
+        }
+        self.expect(TokenData::RBrace).map(|_| ())
+    }
+
     fn function_declaration(&mut self) -> Result<Decl> {
-        self.span(&mut |this| Ok(Decl::Fun(this.function(|this| this.id(true).map(Some))?)))
+        self.span(&mut |this| Ok(Decl::Fun(this.function(false, false, |this| this.id(true).map(Some))?)))
+    }
+
+    // The "async" keyword has already been consumed.
+    fn async_function_declaration(&mut self) -> Result<Decl> {
+        self.span(&mut |this| Ok(Decl::Fun(this.function(true, false, |this| this.id(true).map(Some))?)))
+    }
+
+    // Called after an "async" token to decide whether it starts an async
+    // function rather than being an identifier.
+    fn async_function_follows(&mut self) -> Result<bool> {
+        let next = self.peek_op()?;
+        Ok(next.value == TokenData::Reserved(Reserved::Function) && !next.newline)
     }
 
     fn formal_parameters(&mut self) -> Result<Params> {
@@ -366,6 +993,7 @@ impl Parser {
             this.expect(TokenData::LParen)?;
             let mut list = Vec::new();
             let mut rest = None;
+            let mut types = Vec::new();
             loop {
                 match this.peek()?.value {
                     TokenData::RParen => {
@@ -379,10 +1007,17 @@ impl Parser {
                                 patt: this.pattern()?,
                             })
                         })?);
+                        if this.types {
+                            types.push(this.annotation_opt()?);
+                        }
                         break;
                     }
                     _ => {
-                        list.push(this.parameter_pattern()?);
+                        let patt = this.pattern()?;
+                        if this.types {
+                            types.push(this.annotation_opt()?);
+                        }
+                        list.push(this.more_binding_element(patt)?);
                         if !this.matches(TokenData::Comma)? {
                             break;
                         }
@@ -394,41 +1029,23 @@ impl Parser {
                 location: None,
                 list: list,
                 rest: rest,
+                types: types,
             })
         })
     }
 
-    fn parameter_pattern(&mut self) -> Result<Patt<Id>> {
-        match self.peek()?.value {
-            TokenData::Identifier(_) => {
-                self.span(&mut |this| {
-                    let id = this.id(true)?;
-                    match this.peek()?.value {
-                        TokenData::Assign => {
-                            // TODO Should this use reread instead?
-                            this.read()?;
-                            // FIXME Allow expression instead of primary expression because if we do
-                            // this.expression()? we end up over parsing by treating property seperator
-                            // commas as part of expression. We likely need a different method to parse
-                            // these kinds of expression
-                            Ok(Patt::Assign(None, id, Box::new(this.primary_expression()?)))
-                        }
-                        // @Question Do we need this check?
-                        // Either the array/object pattern can end or there is another pattern to check for
-                        // hence we check for Comma or RParen/RBrack/RBrace
-                        TokenData::Comma
-                        | TokenData::RParen
-                        | TokenData::RBrack
-                        | TokenData::RBrace => Ok(Patt::Simple(id)),
-                        _ => this.unexpected("parameter pattern did not find a suitable next token"),
-                    }
-                })
-            }
-            _ => {
-                let patt = self.binding_pattern()?;
-                Ok(Patt::Compound(patt))
-            }
+    // BindingElement ::= (BindingIdentifier | BindingPattern) Initializer?
+    fn binding_element(&mut self) -> Result<Patt<Id>> {
+        let patt = self.pattern()?;
+        self.more_binding_element(patt)
+    }
+
+    fn more_binding_element(&mut self, patt: Patt<Id>) -> Result<Patt<Id>> {
+        if !self.matches(TokenData::Assign)? {
+            return Ok(patt);
         }
+        let init = self.allow_in(true, |this| this.assignment_expression())?;
+        Ok(Patt::Assign(span(&patt, &init), Box::new(patt), Box::new(init)))
     }
 
     fn single_arrow_parameter(&mut self) -> Result<Params> {
@@ -442,6 +1059,7 @@ impl Parser {
                 location: None,
                 list: vec![param],
                 rest: None,
+                types: vec![],
             })
         })
     }
@@ -483,13 +1101,8 @@ impl Parser {
             loop {
                 match this.peek()?.value {
                     TokenData::Comma => {
-                        this.read()?;
-                        match this.peek()?.value {
-                            TokenData::Comma => {
-                                list.push(None);
-                            }
-                            _ => (),
-                        }
+                        this.reread(TokenData::Comma);
+                        list.push(None);
                     }
                     TokenData::RBrack => {
                         break;
@@ -505,7 +1118,10 @@ impl Parser {
                         break;
                     }
                     _ => {
-                        list.push(Some(this.parameter_pattern()?));
+                        list.push(Some(this.binding_element()?));
+                        if !this.matches(TokenData::Comma)? {
+                            break;
+                        }
                     }
                 }
             }
@@ -514,51 +1130,41 @@ impl Parser {
         })
     }
 
+    // BindingProperty ::=
+    //   SingleNameBinding
+    //   PropertyName ":" BindingElement
     fn binding_property(&mut self) -> Result<PropPatt<Id>> {
         self.span(&mut |this| {
             let token = this.read()?;
             match token.value {
-                TokenData::Identifier(_) => match this.peek()?.value {
+                TokenData::Identifier(name) => match this.peek()?.value {
                     TokenData::Colon => {
-                        if let TokenData::Identifier(Name::String(s)) = token.value {
-                            let key = PropKey::Id(Some(token.location), s);
-                            this.expect(TokenData::Colon)?;
-                            let value = this.parameter_pattern()?;
-                            Ok(PropPatt::Regular(None, key, value))
-                        } else {
-                            this.unexpected("expected identifier with Name::String")
-                        }
+                        let key = PropKey::Id(Some(token.location), name.into_owned());
+                        this.reread(TokenData::Colon);
+                        let value = this.binding_element()?;
+                        Ok(PropPatt::Regular(None, key, value))
                     }
                     TokenData::Assign => {
-                        this.lexer.unread_token(token);
-                        let id = this.id(true)?;
-                        let id2 = id.clone();
-                        this.expect(TokenData::Assign)?;
-                        // FIXME Allow expression instead of primary expression because if we do
-                        // this.expression()? we end up over parsing by treating property seperator
-                        // commas as part of expression. We likely need a different method to parse
-                        // these kinds of expression
-                        let value = Box::new(this.primary_expression()?);
-                        Ok(PropPatt::Shorthand(None, id, Some(Patt::Assign(None, id2, value))))
+                        let id = this.new_id(true, name, token.location)?;
+                        this.reread(TokenData::Assign);
+                        let init = this.allow_in(true, |this| this.assignment_expression())?;
+                        let patt = Patt::Simple(id.clone());
+                        let value = Patt::Assign(span(&patt, &init), Box::new(patt), Box::new(init));
+                        Ok(PropPatt::Shorthand(None, id, Some(value)))
                     }
                     TokenData::Comma | TokenData::RBrace => {
-                        this.lexer.unread_token(token);
-                        let id = this.id(true)?;
+                        let id = this.new_id(true, name, token.location)?;
                         Ok(PropPatt::Shorthand(None, id, None))
                     }
                     _ => this.unexpected("expected either shorthand/regular/computed property"),
                 },
+                TokenData::PrivateName(_) => Err(Error::IllegalPrivateName(Some(token.location))),
                 _ => {
                     this.lexer.unread_token(token);
                     let key = this.property_key()?;
-                    match this.peek()?.value {
-                        TokenData::Colon => {
-                            this.expect(TokenData::Colon)?;
-                            let value = this.parameter_pattern()?;
-                            Ok(PropPatt::Regular(None, key, value))
-                        }
-                        _ => this.unexpected("expected computed property"),
-                    }
+                    this.expect(TokenData::Colon)?;
+                    let value = this.binding_element()?;
+                    Ok(PropPatt::Regular(None, key, value))
                 }
             }
         })
@@ -568,32 +1174,32 @@ impl Parser {
         self.span(&mut |this| {
             this.expect(TokenData::LBrace)?;
             let mut list = Vec::new();
-            // let mut rest = None;
+            let mut rest = None;
             loop {
                 match this.peek()?.value {
-                    TokenData::Comma => {
-                        this.read()?;
-                    }
                     TokenData::RBrace => {
                         break;
                     }
-                    // TokenData::Ellipsis => {
-                    //     rest = Some(this.span(&mut |this| {
-                    //         this.reread(TokenData::Ellipsis);
-                    //         Ok(RestPatt {
-                    //             location: None,
-                    //             patt: this.pattern()?,
-                    //         })
-                    //     })?);
-                    //     break;
-                    // }
-                    _ => {
-                        list.push(this.binding_property()?);
-                    }
+                    TokenData::Ellipsis => {
+                        rest = Some(this.span(&mut |this| {
+                            this.reread(TokenData::Ellipsis);
+                            Ok(RestPatt {
+                                location: None,
+                                patt: Patt::Simple(this.id(true)?),
+                            })
+                        })?);
+                        break;
+                    }
+                    _ => {
+                        list.push(this.binding_property()?);
+                        if !this.matches(TokenData::Comma)? {
+                            break;
+                        }
+                    }
                 }
             }
             this.expect(TokenData::RBrace)?;
-            Ok(CompoundPatt::Obj(None, list))
+            Ok(CompoundPatt::Obj(None, list, rest.map(Box::new)))
         })
     }
 
@@ -607,32 +1213,96 @@ impl Parser {
                 match check {
                     Check::Strict(error) => {
                         if strict == Strict::Yes && self.validate {
-                            return Err(error);
+                            return self.early_error(error);
                         } else {
                             self.deferred.push(Check::Strict(error));
                         }
                     }
                     Check::Module(error) => {
                         if self.goal == Goal::Module && self.validate {
-                            return Err(error);
+                            return self.early_error(error);
                         } else if self.goal != Goal::Script {
                             self.deferred.push(Check::Module(error));
                         }
                     }
+                    Check::Script(error) => {
+                        self.module_check(error)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Annex B extensions are errors unless the parser allows them, and even
+    // then they are errors in strict code.
+    fn legacy_check(&mut self, error: Error) -> Result<()> {
+        if !self.annex_b {
+            return if self.validate { self.early_error(error) } else { Ok(()) };
+        }
+        self.strict_check(|_| Some(Check::Strict(error)))
+    }
+
+    // Legacy octal literals and escapes, duplicate functions in blocks and var
+    // redeclarations of catch parameters have always been accepted in sloppy
+    // code, so unlike the other Annex B extensions they don't need the flag.
+    fn sloppy_check(&mut self, error: Error) -> Result<()> {
+        self.strict_check(|_| Some(Check::Strict(error)))
+    }
+
+    // Early errors stop the parse unless it's tolerant, in which case they're
+    // collected and the tree is kept.
+    fn early_error(&mut self, error: Error) -> Result<()> {
+        if self.tolerant {
+            self.errors.push(error);
+            return Ok(());
+        }
+        Err(error)
+    }
+
+    fn literal_check(&mut self, token: &Token) -> Result<()> {
+        match token.value {
+            TokenData::Number(ref literal) if literal.is_legacy() => {
+                self.sloppy_check(Error::LegacyOctalLiteral(Some(token.location)))
+            }
+            TokenData::String(ref literal) if literal.has_legacy_escape() => {
+                self.sloppy_check(Error::LegacyOctalEscape(Some(token.location)))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Like strict_check, but for errors in any code that isn't module code.
+    fn module_check(&mut self, error: Error) -> Result<()> {
+        match self.goal {
+            Goal::Module => {}
+            Goal::Script => {
+                if self.validate {
+                    return self.early_error(error);
                 }
             }
+            Goal::Unknown => {
+                self.deferred.push(Check::Script(error));
+            }
         }
         Ok(())
     }
 
-    fn function<F>(&mut self, get_id: F) -> Result<Fun>
+    // The "async" keyword, if any, has already been consumed. The name of a
+    // function expression is bound inside the function, so `yield` and
+    // `await` are treated there as they are in its body.
+    fn function<F>(&mut self, is_async: bool, expression: bool, get_id: F) -> Result<Fun>
     where
         F: Fn(&mut Self) -> Result<Option<Id>>,
     {
         self.span(&mut |this| {
             this.reread(TokenData::Reserved(Reserved::Function));
             let generator = this.matches(TokenData::Star)?;
-            let id = get_id(this)?;
+            let id = if expression {
+                this.in_function(generator, is_async, &get_id)?
+            } else {
+                get_id(this)?
+            };
             let kind = match (id, generator) {
                 (Some(id), true) => FunctionKind::Generator(id),
                 (Some(id), false) => FunctionKind::Named(id),
@@ -640,44 +1310,68 @@ impl Parser {
                 (None, false) => FunctionKind::Anonymous,
             };
 
-            let params = this.formal_parameters()?;
-            let body = this.function_body(&params.list)?;
+            let type_params = this.type_params_opt()?;
+            let (params, ret, body) = this.with_super(false, false, |this| {
+                let params = this.in_parameters(generator, is_async, |this| this.formal_parameters())?;
+                let ret = this.type_annotation_opt()?;
+                let body = this.function_body(&params, false, generator, is_async)?;
+                Ok((params, ret, body))
+            })?;
             Ok(Fun {
                 location: None,
                 kind: kind,
+                is_async: is_async,
+                type_params: type_params,
                 params: params,
+                ret: ret,
                 body: body,
                 body_expr: None,
             })
         })
     }
 
-    fn arrow_function(&mut self, single_param: bool) -> Result<Fun> {
+    fn arrow_function(&mut self, is_async: bool, single_param: bool) -> Result<Fun> {
         self.span(&mut |this| {
             let kind = FunctionKind::Arrow;
-            let params = match single_param {
-                true => this.single_arrow_parameter()?,
-                false => this.formal_parameters()?,
+            let generator = this.context.generator;
+            let in_async = is_async || this.context.is_async;
+            let (type_params, params, ret) = match single_param {
+                true => (vec![], this.in_parameters(generator, in_async, |this| this.single_arrow_parameter())?, None),
+                false => {
+                    let type_params = this.type_params_opt()?;
+                    let params = this.in_parameters(generator, in_async, |this| this.formal_parameters())?;
+                    (type_params, params, this.type_annotation_opt()?)
+                }
             };
             this.expect(TokenData::Arrow)?;
             match this.peek()?.value {
                 TokenData::LBrace => {
-                    let body = this.function_body(&params.list)?;
+                    let mut inner = this.context.new_function(false, is_async);
+                    inner.allow_arguments = this.context.allow_arguments;
+                    let body = this.function_body_in(inner, &params, true)?;
                     Ok(Fun {
                         location: None,
                         kind: kind,
+                        is_async: is_async,
+                        type_params: type_params,
                         params: params,
+                        ret: ret,
                         body: body,
                         body_expr: None,
                     })
                 }
                 _ => {
-                    let expr = this.expression()?;
+                    let expr = this.in_function(false, is_async, |this| this.expression())?;
+                    let body = empty_script();
+                    this.check_params(&params, &body, true)?;
                     Ok(Fun {
                         location: None,
                         kind: kind,
+                        is_async: is_async,
+                        type_params: type_params,
                         params: params,
-                        body: empty_script(),
+                        ret: ret,
+                        body: body,
                         body_expr: Some(Box::new(expr)),
                     })
                 }
@@ -685,15 +1379,21 @@ impl Parser {
         })
     }
 
-    fn function_body(&mut self, params: &[Patt<Id>]) -> Result<Script> {
-        let inner = self.context.new_function();
+    // Arrow functions and methods never allow duplicate parameters.
+    fn function_body(&mut self, params: &Params, unique: bool, generator: bool, is_async: bool) -> Result<Script> {
+        let inner = self.context.new_function(generator, is_async);
+        self.function_body_in(inner, params, unique)
+    }
+
+    fn function_body_in(&mut self, inner: Context, params: &Params, unique: bool) -> Result<Script> {
         let outer = replace(&mut self.context, inner);
         self.expect(TokenData::LBrace)?;
         // ES6: if the body has "use strict" check for simple parameters
         let body = self.script_body()?;
+        self.check_params(params, &body, unique)?;
         self.strict_check(|_| {
             if body.dirs.iter().any(|dir| dir.pragma() == "use strict") {
-                for param in params {
+                for param in params.list.iter() {
                     if let Patt::Compound(ref compound) = *param {
                         return Some(Check::Strict(Error::CompoundParamWithUseStrict(
                             compound.clone(),
@@ -703,11 +1403,537 @@ impl Parser {
             }
             None
         })?;
+        self.close_block()?;
+        self.context = outer;
+        Ok(body)
+    }
+
+    // Duplicate parameters are only allowed in a sloppy function with a simple
+    // parameter list, and the body may not redeclare a parameter lexically.
+    fn check_params(&mut self, params: &Params, body: &Script, unique: bool) -> Result<()> {
+        let mut bound = HashSet::new();
+        for id in params.bound_ids() {
+            if !bound.insert(&id.name) {
+                let error = Error::DuplicateParameter(id.location, id.name.as_ref().to_string());
+                if unique || !params.is_simple() {
+                    self.early_error(error)?;
+                } else {
+                    self.strict_check(|_| Some(Check::Strict(error)))?;
+                }
+            }
+        }
+        for item in body.items.iter().filter(|item| item.fun_declaration().is_none()) {
+            for id in item.lexically_declared_ids() {
+                if bound.contains(&id.name) {
+                    self.early_error(Error::DuplicateDeclaration(id.location, id.name.as_ref().to_string()))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn class_declaration(&mut self) -> Result<Decl> {
+        self.decorated_class(|this| this.id(true).map(Some)).map(Decl::Class)
+    }
+
+    // DecoratedClass ::= Decorator* "class" BindingIdentifier? ClassTail
+    fn decorated_class<F>(&mut self, get_id: F) -> Result<Class>
+    where
+        F: Fn(&mut Self) -> Result<Option<Id>>,
+    {
+        let decorators = self.decorators()?;
+        self.more_decorated_class(decorators, get_id)
+    }
+
+    fn more_decorated_class<F>(&mut self, decorators: Vec<Decorator>, get_id: F) -> Result<Class>
+    where
+        F: Fn(&mut Self) -> Result<Option<Id>>,
+    {
+        if self.peek()?.value != TokenData::Reserved(Reserved::Class) {
+            return Err(Error::IllegalDecorator(decorators[0].location));
+        }
+        let mut class = self.class(get_id)?;
+        class.decorators = decorators;
+        Ok(class)
+    }
+
+    fn decorators(&mut self) -> Result<Vec<Decorator>> {
+        let mut decorators = Vec::new();
+        while self.peek()?.value == TokenData::At {
+            decorators.push(self.decorator()?);
+        }
+        Ok(decorators)
+    }
+
+    // Decorator ::=
+    //   "@" Identifier ("." IdentifierName)* Arguments?
+    //   "@" "(" Expression ")"
+    fn decorator(&mut self) -> Result<Decorator> {
+        if !self.decorators {
+            return self.unexpected("decorators are not enabled");
+        }
+        let at = self.reread(TokenData::At);
+        let expr = match self.peek()?.value {
+            TokenData::LParen => self.paren_expression()?,
+            _ => {
+                let mut expr = Expr::Id(self.id(false)?);
+                while self.peek_op()?.value == TokenData::Dot {
+                    expr = self.deref_dot()?.append_to(expr);
+                }
+                if self.peek_op()?.value == TokenData::LParen {
+                    expr = self.arguments()?.append_to(expr);
+                }
+                expr
+            }
+        };
+        Ok(Decorator {
+            location: span(&Some(at.location), &expr),
+            expr: expr,
+        })
+    }
+
+    // An export declaration or a class declaration, either of which may be
+    // preceded by decorators. Decorators before "export" apply to the
+    // exported class.
+    fn decorated_module_item(&mut self) -> Result<ModItem> {
+        let decorators = self.decorators()?;
+        if decorators.is_empty() {
+            return self.export_declaration().map(ModItem::Export);
+        }
+        if self.peek()?.value != TokenData::Reserved(Reserved::Export) {
+            let class = self.more_decorated_class(decorators, |this| this.id(true).map(Some))?;
+            return Ok(StmtListItem::Decl(Decl::Class(class)).into_mod_item());
+        }
+        let mut export = self.export_declaration()?;
+        match export {
+            Export::Decl(Decl::Class(ref mut class)) | Export::DefaultClass(_, ref mut class)
+                if class.decorators.is_empty() =>
+            {
+                class.decorators = decorators;
+            }
+            _ => {
+                return Err(Error::IllegalDecorator(decorators[0].location));
+            }
+        }
+        Ok(ModItem::Export(export))
+    }
+
+    // ClassTail ::= TypeParams? ("extends" LHSExpression TypeArgs?)? ("implements" Type ("," Type)*)? "{" ClassBody "}"
+    fn class<F>(&mut self, get_id: F) -> Result<Class>
+    where
+        F: Fn(&mut Self) -> Result<Option<Id>>,
+    {
+        // All parts of a class declaration or expression are strict mode code.
+        let strict = replace(&mut self.context.strict, Strict::Yes);
+        let result = self.span(&mut |this| {
+            this.reread(TokenData::Reserved(Reserved::Class));
+            let id = get_id(this)?;
+            let type_params = this.type_params_opt()?;
+            let extends = if this.matches(TokenData::Reserved(Reserved::Extends))? {
+                Some(Box::new(this.superclass()?))
+            } else {
+                None
+            };
+            let implements = if this.types && this.matches(TokenData::Identifier(Name::Atom(Atom::Implements)))? {
+                this.comma_separated(Self::ty)?
+            } else {
+                Vec::new()
+            };
+            let body = this.in_class_body(|this| this.class_body(extends.is_some()))?;
+            Ok(Class {
+                location: None,
+                decorators: Vec::new(),
+                id: id,
+                type_params: type_params,
+                extends: extends,
+                implements: implements,
+                body: body,
+            })
+        });
+        replace(&mut self.context.strict, strict);
+        result
+    }
+
+    // The type arguments of a superclass, as in `extends B<T> {`, come before
+    // the class body rather than arguments.
+    fn superclass(&mut self) -> Result<Expr> {
+        let superclass = self.lhs_expression()?;
+        if !self.types || self.peek_op()?.value != TokenData::LAngle {
+            return Ok(superclass);
+        }
+        let (types, end) = self.type_arguments()?;
+        Ok(Expr::TypeArgs(span(&superclass, &Some(end.location)), Box::new(superclass), types))
+    }
+
+    // ClassBody ::= "{" (ClassElement | ";")* "}"
+    fn class_body(&mut self, derived: bool) -> Result<Vec<ClassMember>> {
+        self.expect(TokenData::LBrace)?;
+        let mut members = Vec::new();
+        let mut found_constructor = false;
+        loop {
+            match self.peek()?.value {
+                TokenData::RBrace => {
+                    break;
+                }
+                TokenData::Semi => {
+                    self.reread(TokenData::Semi);
+                }
+                _ => {
+                    let member = self.recovering(|this| this.class_member(derived), ClassMember::Error, true)?;
+                    if let ClassMember::Method(Method {
+                        kind: MethodKind::Constructor,
+                        ref key,
+                        ..
+                    }) = member
+                    {
+                        if found_constructor {
+                            return Err(Error::DuplicateConstructor(*key.tracking_ref()));
+                        }
+                        found_constructor = true;
+                    }
+                    members.push(member);
+                }
+            }
+        }
         self.expect(TokenData::RBrace)?;
+        Ok(members)
+    }
+
+    // ClassElement ::=
+    //   Decorator* "static"? MethodDefinition
+    //   Decorator* "static"? FieldDefinition ";"
+    //   ClassStaticBlock
+    fn class_member(&mut self, derived: bool) -> Result<ClassMember> {
+        self.span(&mut |this| {
+            let decorators = this.decorators()?;
+            let mut is_static = false;
+            if this.peek()?.value == TokenData::Identifier(Name::Atom(Atom::Static)) {
+                let token = this.lexer.reread_token();
+                match this.peek()?.value {
+                    // 'static' '(' is a method named "static", and 'static'
+                    // followed by '=' or the end of the element a field.
+                    TokenData::LParen | TokenData::Assign | TokenData::Semi | TokenData::RBrace => {
+                        this.lexer.unread_token(token);
+                    }
+                    TokenData::LBrace => {
+                        if let Some(decorator) = decorators.first() {
+                            return Err(Error::IllegalDecorator(decorator.location));
+                        }
+                        let body = this.static_block()?;
+                        return Ok(ClassMember::StaticBlock(None, body));
+                    }
+                    _ => {
+                        is_static = true;
+                    }
+                }
+            }
+
+            // Only the constructor of a class with an "extends" clause may
+            // call super().
+            let constructor = !is_static && match this.peek()?.value {
+                TokenData::Identifier(ref name) => name.as_ref() == "constructor",
+                TokenData::String(ref lit) => lit.value == "constructor",
+                _ => false,
+            };
+            let (kind, key, fun) = this.with_super(true, constructor && derived, |this| this.method_definition())?;
+            let fun = match fun {
+                Some(fun) => fun,
+                None => {
+                    return this.field_definition(decorators, is_static, key).map(ClassMember::Field);
+                }
+            };
+
+            let kind = match (is_static, key.name()) {
+                (false, Some("constructor")) => match (kind, &fun.kind) {
+                    // The constructor is part of the class, so only the
+                    // class itself may be decorated.
+                    (MethodKind::Method, &FunctionKind::Anonymous) if !fun.is_async => {
+                        if let Some(decorator) = decorators.first() {
+                            return Err(Error::IllegalDecorator(decorator.location));
+                        }
+                        MethodKind::Constructor
+                    }
+                    _ => {
+                        return Err(Error::IllegalConstructor(*key.tracking_ref()));
+                    }
+                },
+                (true, Some("prototype")) => {
+                    return Err(Error::StaticPrototype(*key.tracking_ref()));
+                }
+                _ => kind,
+            };
+            if let PropKey::Private(ref name) = key {
+                let private_kind = match kind {
+                    MethodKind::Get => PrivateKind::Getter,
+                    MethodKind::Set => PrivateKind::Setter,
+                    _ => PrivateKind::Method,
+                };
+                this.declare_private_name(name, private_kind, is_static)?;
+            }
+
+            Ok(ClassMember::Method(Method {
+                location: None,
+                decorators: decorators,
+                is_static: is_static,
+                kind: kind,
+                key: key,
+                fun: fun,
+            }))
+        })
+    }
+
+    // FieldDefinition ::= ClassElementName ("=" AssignmentExpression)?
+    fn field_definition(&mut self, decorators: Vec<Decorator>, is_static: bool, key: PropKey) -> Result<Field> {
+        match (is_static, key.name()) {
+            (_, Some("constructor")) => {
+                return Err(Error::IllegalConstructor(*key.tracking_ref()));
+            }
+            (true, Some("prototype")) => {
+                return Err(Error::StaticPrototype(*key.tracking_ref()));
+            }
+            _ => {}
+        }
+        if let PropKey::Private(ref name) = key {
+            self.declare_private_name(name, PrivateKind::Field, is_static)?;
+        }
+        let annot = if self.types { self.annotation_opt()? } else { None };
+        let value = if self.matches(TokenData::Assign)? {
+            // Initializers are evaluated like the bodies of methods.
+            Some(self.with_super(true, false, |this| {
+                this.in_function(false, false, |this| {
+                    this.allow_arguments(false, |this| {
+                        this.allow_in(true, |this| this.assignment_expression())
+                    })
+                })
+            })?)
+        } else {
+            None
+        };
+        let asi = {
+            let next = self.peek()?;
+            next.newline || next.value == TokenData::Semi || next.value == TokenData::RBrace
+        };
+        if !asi {
+            let token = self.read()?;
+            return Err(Error::FailedASI(token.into_owned()));
+        }
+        self.matches(TokenData::Semi)?;
+        Ok(Field {
+            location: None,
+            decorators: decorators,
+            is_static: is_static,
+            key: key,
+            annot: annot,
+            value: value,
+        })
+    }
+
+    // ClassStaticBlock ::= "static" "{" StatementList "}"
+    fn static_block(&mut self) -> Result<Script> {
+        let mut inner = self.context.new_function(false, false);
+        // A static block is not a function body, so it may not return.
+        inner.function = false;
+        inner.static_block = true;
+        inner.allow_arguments = false;
+        inner.allow_super_property = true;
+        inner.allow_super_call = false;
+        let outer = replace(&mut self.context, inner);
+        self.expect(TokenData::LBrace)?;
+        let body = self.span(&mut |this| {
+            let items = this.statement_list()?;
+            this.check_body(&items)?;
+            Ok(Script {
+                location: None,
+                hashbang: None,
+                dirs: vec![],
+                items: items,
+            })
+        })?;
+        self.close_block()?;
         self.context = outer;
         Ok(body)
     }
 
+    fn declare_private_name(&mut self, name: &PrivateName, kind: PrivateKind, is_static: bool) -> Result<()> {
+        if name.name == "constructor" {
+            return Err(Error::IllegalPrivateName(name.location));
+        }
+        match self.context.private_scope {
+            Some(ref scope) => scope.borrow_mut().declare(name, kind, is_static),
+            None => unreachable!(),
+        }
+    }
+
+    // A reference to a private name, resolved when the class body that
+    // encloses it ends.
+    fn private_name(&mut self) -> Result<PrivateName> {
+        let token = self.read()?;
+        let name = match token.value {
+            TokenData::PrivateName(name) => PrivateName {
+                location: Some(token.location),
+                name: name.into_owned(),
+            },
+            _ => {
+                return Err(Error::UnexpectedToken(token.into_owned(), "expected private name"));
+            }
+        };
+        match self.context.private_scope {
+            Some(ref scope) => {
+                scope.borrow_mut().references.push(name.clone());
+            }
+            None => {
+                return Err(Error::UndeclaredPrivateName(name.location, name.name));
+            }
+        }
+        Ok(name)
+    }
+
+    // MethodDefinition ::=
+    //   PropertyName "(" FormalParameters ")" FunctionBody
+    //   "*" PropertyName "(" FormalParameters ")" FunctionBody
+    //   "get" PropertyName "(" ")" FunctionBody
+    //   "set" PropertyName "(" FormalParameter ")" FunctionBody
+    //   "async" [no LineTerminator here] "*"? PropertyName "(" FormalParameters ")" FunctionBody
+    //
+    // A name that is not followed by a method is returned without a function,
+    // since it begins a field instead.
+    fn method_definition(&mut self) -> Result<(MethodKind, PropKey, Option<Fun>)> {
+        let first = self.read()?;
+        match first.value {
+            TokenData::Star => {
+                let key = self.property_key()?;
+                let fun = self.method_function(FunctionKind::AnonymousGenerator, false)?;
+                Ok((MethodKind::Method, key, Some(fun)))
+            }
+            TokenData::Identifier(Name::Atom(Atom::Async)) => {
+                if let Some((key, fun)) = self.async_method_opt()? {
+                    return Ok((MethodKind::Method, key, Some(fun)));
+                }
+                let key = PropKey::Id(Some(first.location), Name::Atom(Atom::Async));
+                self.more_method_opt(key)
+            }
+            TokenData::Identifier(Name::Atom(Atom::Get)) => {
+                if let Some(key) = self.property_key_opt()? {
+                    let fun = self.span(&mut |this| {
+                        let params = this.span(&mut |this| {
+                            this.expect(TokenData::LParen)?;
+                            this.expect(TokenData::RParen)?;
+                            Ok(Params {
+                                location: None,
+                                list: vec![],
+                                rest: None,
+                                types: vec![],
+                            })
+                        })?;
+                        let ret = this.type_annotation_opt()?;
+                        let body = this.function_body(&params, true, false, false)?;
+                        Ok(Fun {
+                            location: None,
+                            kind: FunctionKind::Anonymous,
+                            is_async: false,
+                            type_params: vec![],
+                            params: params,
+                            ret: ret,
+                            body: body,
+                            body_expr: None,
+                        })
+                    })?;
+                    return Ok((MethodKind::Get, key, Some(fun)));
+                }
+                let key = PropKey::Id(Some(first.location), Name::Atom(Atom::Get));
+                self.more_method_opt(key)
+            }
+            TokenData::Identifier(Name::Atom(Atom::Set)) => {
+                if let Some(key) = self.property_key_opt()? {
+                    let fun = self.span(&mut |this| {
+                        let params = this.span(&mut |this| {
+                            this.expect(TokenData::LParen)?;
+                            let patt = this.pattern()?;
+                            let mut types = vec![];
+                            if this.types {
+                                types.push(this.annotation_opt()?);
+                            }
+                            let param = this.more_binding_element(patt)?;
+                            this.expect(TokenData::RParen)?;
+                            Ok(Params {
+                                location: None,
+                                list: vec![param],
+                                rest: None,
+                                types: types,
+                            })
+                        })?;
+                        let body = this.function_body(&params, true, false, false)?;
+                        Ok(Fun {
+                            location: None,
+                            kind: FunctionKind::Anonymous,
+                            is_async: false,
+                            type_params: vec![],
+                            params: params,
+                            ret: None,
+                            body: body,
+                            body_expr: None,
+                        })
+                    })?;
+                    return Ok((MethodKind::Set, key, Some(fun)));
+                }
+                let key = PropKey::Id(Some(first.location), Name::Atom(Atom::Set));
+                self.more_method_opt(key)
+            }
+            _ => {
+                self.lexer.unread_token(first);
+                let key = self.property_key()?;
+                self.more_method_opt(key)
+            }
+        }
+    }
+
+    fn more_method_opt(&mut self, key: PropKey) -> Result<(MethodKind, PropKey, Option<Fun>)> {
+        let next = self.peek()?.value.clone();
+        if next != TokenData::LParen && !(self.types && next == TokenData::LAngle) {
+            return Ok((MethodKind::Method, key, None));
+        }
+        let fun = self.method_function(FunctionKind::Anonymous, false)?;
+        Ok((MethodKind::Method, key, Some(fun)))
+    }
+
+    // Called after an "async" token; returns None if it is just the name of
+    // a property or method.
+    fn async_method_opt(&mut self) -> Result<Option<(PropKey, Fun)>> {
+        if self.peek()?.newline {
+            return Ok(None);
+        }
+        let (kind, key) = if self.matches(TokenData::Star)? {
+            (FunctionKind::AnonymousGenerator, self.property_key()?)
+        } else {
+            match self.property_key_opt()? {
+                Some(key) => (FunctionKind::Anonymous, key),
+                None => { return Ok(None); }
+            }
+        };
+        let fun = self.method_function(kind, true)?;
+        Ok(Some((key, fun)))
+    }
+
+    fn method_function(&mut self, kind: FunctionKind, is_async: bool) -> Result<Fun> {
+        let generator = kind == FunctionKind::AnonymousGenerator;
+        self.span(&mut |this| {
+            let type_params = this.type_params_opt()?;
+            let params = this.in_parameters(generator, is_async, |this| this.formal_parameters())?;
+            let ret = this.type_annotation_opt()?;
+            let body = this.function_body(&params, true, generator, is_async)?;
+            Ok(Fun {
+                location: None,
+                kind: kind.clone(),
+                is_async: is_async,
+                type_params: type_params,
+                params: params,
+                ret: ret,
+                body: body,
+                body_expr: None,
+            })
+        })
+    }
+
     fn stmt_list_item(&mut self, allow_decl: bool) -> Result<StmtListItem> {
         (match self.peek()?.value {
             TokenData::Reserved(Reserved::Function) => {
@@ -716,6 +1942,12 @@ impl Parser {
                 }
                 return self.function_declaration().map(StmtListItem::Decl);
             }
+            TokenData::Reserved(Reserved::Class) | TokenData::At => {
+                if !allow_decl {
+                    return self.unexpected("class declaration not allowed");
+                }
+                return self.class_declaration().map(StmtListItem::Decl);
+            }
             TokenData::LBrace => self.block().map(Stmt::Block),
             TokenData::Reserved(Reserved::Var) => self.var_statement(),
             TokenData::Reserved(Reserved::Const) => {
@@ -738,11 +1970,31 @@ impl Parser {
             TokenData::Reserved(Reserved::For) => self.for_statement(),
             TokenData::Reserved(Reserved::Debugger) => self.debugger_statement(),
             TokenData::Identifier(_) => {
+                if self.types && allow_decl {
+                    if let Some(decl) = self.type_declaration_opt()? {
+                        return Ok(StmtListItem::Decl(decl));
+                    }
+                }
                 let token = self.lexer.reread_token();
+                // Inside a generator `yield` (and inside an async function
+                // `await`) can't be a label, and what follows it may be a
+                // regular expression.
+                if (self.context.generator && token.value == TokenData::Identifier(Name::Atom(Atom::Yield)))
+                    || (self.context.is_async && token.value == TokenData::Identifier(Name::Atom(Atom::Await)))
+                {
+                    self.lexer.unread_token(token);
+                    return self.expression_statement().map(StmtListItem::Stmt);
+                }
+                if token.value == TokenData::Identifier(Name::Atom(Atom::Async)) && self.async_function_follows()? {
+                    if !allow_decl {
+                        return self.unexpected("async function declaration not allowed");
+                    }
+                    return self.async_function_declaration().map(StmtListItem::Decl);
+                }
                 match self.peek_op()?.value {
                     TokenData::Colon => {
                         let id = self.new_id_from_token(false, token)?;
-                        self.labelled_statement(id)
+                        self.labelled_statement(id, allow_decl)
                     }
                     TokenData::Identifier(_) | TokenData::LBrace | TokenData::LBrack
                         if token.value == TokenData::Identifier(Name::Atom(Atom::Let)) =>
@@ -771,7 +2023,8 @@ impl Parser {
         })
     }
 
-    fn labelled_statement(&mut self, id: Id) -> Result<Stmt> {
+    // A label that is itself the body of a statement can't label a function.
+    fn labelled_statement(&mut self, id: Id, allow_fun: bool) -> Result<Stmt> {
         self.reread(TokenData::Colon);
 
         let mut labels = vec![id]; // vector of consecutive labels
@@ -786,11 +2039,36 @@ impl Parser {
         }
 
         let label_type = self.peek()?.label_type();
-        self.with_labels(labels, label_type, |this| this.statement())
+        self.with_labels(labels, label_type, |this| {
+            if allow_fun {
+                this.legacy_function_statement()
+            } else {
+                this.statement()
+            }
+        })
     }
 
-    fn expression_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
+    // Annex B allows a plain function declaration as the body of an if
+    // statement or a label in sloppy code.
+    fn legacy_function_statement(&mut self) -> Result<Stmt> {
+        if self.annex_b && self.peek()?.value == TokenData::Reserved(Reserved::Function) {
+            let token = self.reread(TokenData::Reserved(Reserved::Function));
+            let generator = self.peek()?.value == TokenData::Star;
+            self.lexer.unread_token(token);
+            if !generator {
+                let location = self.peek()?.location;
+                self.strict_check(|_| Some(Check::Strict(Error::LegacyFunctionDeclaration(location))))?;
+                return match self.function_declaration()? {
+                    Decl::Fun(fun) => Ok(Stmt::Fun(fun)),
+                    _ => unreachable!(),
+                };
+            }
+        }
+        self.statement()
+    }
+
+    fn expression_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
         let expr = self.allow_in(true, |this| this.expression())?;
         Ok(span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Expr(None, expr, semi))?)
     }
@@ -799,7 +2077,8 @@ impl Parser {
         self.span(&mut |this| {
             this.expect(TokenData::LBrace)?;
             let items = this.statement_list()?;
-            this.expect(TokenData::RBrace)?;
+            this.close_block()?;
+            this.check_block(&items)?;
             Ok(Block {
                 location: None,
                 items: items,
@@ -837,6 +2116,18 @@ impl Parser {
     }
 
     fn new_id(&mut self, binding: bool, name: Name, location: Span) -> Result<Id> {
+        // 14.4.1: `yield` is always a keyword inside generators.
+        if self.context.generator && name == Name::Atom(Atom::Yield) {
+            return Err(Error::ContextualKeyword(location, Atom::Yield));
+        }
+        // 15.8.1: likewise `await` inside async functions, and it's reserved
+        // in static blocks.
+        if (self.context.is_async || self.context.static_block) && name == Name::Atom(Atom::Await) {
+            return Err(Error::ContextualKeyword(location, Atom::Await));
+        }
+        if !binding && !self.context.allow_arguments && name == Name::Atom(Atom::Arguments) {
+            self.early_error(Error::ArgumentsInClassInitializer(location))?;
+        }
         self.strict_check(|_| {
             if binding && name.is_illegal_strict_binding() {
                 return Some(Check::Strict(Error::IllegalStrictBinding(
@@ -874,7 +2165,7 @@ impl Parser {
                     newline: newline,
                     value: data,
                 };
-                Err(Error::UnexpectedToken(token, "expected identifier"))
+                Err(Error::UnexpectedToken(token.into_owned(), "expected identifier"))
             }
         }
     }
@@ -894,27 +2185,32 @@ impl Parser {
         self.span(&mut |this| match this.peek()?.value {
             TokenData::Identifier(_) => {
                 let id = this.id(true)?;
+                let ty = this.type_annotation_opt()?;
                 let init = if this.matches(TokenData::Assign)? {
                     Some(this.assignment_expression()?)
                 } else {
                     None
                 };
-                Ok(Dtor::Simple(None, id, init))
+                Ok(Dtor::Simple(None, id, ty, init))
             }
             _ => {
                 let lhs = this.binding_pattern()?;
+                let ty = this.type_annotation_opt()?;
                 this.expect(TokenData::Assign)?;
                 let rhs = this.assignment_expression()?;
-                Ok(Dtor::Compound(None, lhs, rhs))
+                Ok(Dtor::Compound(None, lhs, ty, rhs))
             }
         })
     }
 
     fn const_declarator(&mut self) -> Result<ConstDtor> {
         let lhs = self.pattern()?;
+        let ty = self.type_annotation_opt()?;
         self.expect(TokenData::Assign)?;
         let rhs = self.assignment_expression()?;
-        Ok(ConstDtor::from_init(lhs, rhs))
+        let mut dtor: ConstDtor = dtor_from_init(lhs, rhs)?;
+        dtor.ty = ty;
+        Ok(dtor)
     }
 
     fn empty_statement(&mut self) -> Result<Stmt> {
@@ -928,10 +2224,10 @@ impl Parser {
         self.span(&mut |this| {
             this.expect(TokenData::Reserved(Reserved::If))?;
             let test = this.paren_expression()?;
-            let cons = Box::new(this.statement()?);
+            let cons = Box::new(this.legacy_function_statement()?);
             let alt = if this.peek()?.value == TokenData::Reserved(Reserved::Else) {
                 this.reread(TokenData::Reserved(Reserved::Else));
-                Some(Box::new(this.statement()?))
+                Some(Box::new(this.legacy_function_statement()?))
             } else {
                 None
             };
@@ -969,8 +2265,15 @@ impl Parser {
     fn for_statement(&mut self) -> Result<Stmt> {
         self.span(&mut |this| {
             this.reread(TokenData::Reserved(Reserved::For));
+            let await_token = if this.context.is_async {
+                this.matches_token(TokenData::Identifier(Name::Atom(Atom::Await)))?
+            } else {
+                None
+            };
             this.expect(TokenData::LParen)?;
-            match this.peek()?.value {
+            // `for (async of` would be ambiguous with an async arrow function.
+            let async_head = this.peek()?.value == TokenData::Identifier(Name::Atom(Atom::Async));
+            let stmt = match this.peek()?.value {
                 TokenData::Reserved(Reserved::Var) => this.for_var(),
                 TokenData::Identifier(Name::Atom(Atom::Let)) => this.for_let(),
                 TokenData::Reserved(Reserved::Const) => this.for_const(),
@@ -979,56 +2282,93 @@ impl Parser {
                     this.more_for(None)
                 }
                 _ => this.for_expr(),
-            }
+            }?;
+            this.check_for_declarations(&stmt)?;
+            Ok(match (await_token, stmt) {
+                (None, Stmt::ForOf(location, head, obj, body)) => {
+                    if let ForOfHead::Patt(Patt::Simple(Expr::Id(ref id))) = *head {
+                        if async_head {
+                            return Err(Error::ForOfAsync(id.location));
+                        }
+                    }
+                    Stmt::ForOf(location, head, obj, body)
+                }
+                (None, stmt) => stmt,
+                (Some(_), Stmt::ForOf(location, head, obj, body)) => Stmt::ForAwait(location, head, obj, body),
+                (Some(token), _) => {
+                    return Err(Error::UnexpectedToken(token.into_owned(), "for await requires a for-of loop"));
+                }
+            })
         })
     }
 
+    // The names declared by a let or const head may not be redeclared by
+    // the head itself or with var in the body.
+    fn check_for_declarations(&mut self, stmt: &Stmt) -> Result<()> {
+        let (lexical, body) = match *stmt {
+            Stmt::For(_, Some(ref head), _, _, ref body) => match **head {
+                ForHead::Let(_, ref dtors) => (dtors.iter().flat_map(|dtor| dtor.bound_ids()).collect(), body),
+                ForHead::Const(_, ref dtors) => (dtors.iter().flat_map(|dtor| dtor.patt.bound_ids()).collect(), body),
+                _ => return Ok(()),
+            },
+            Stmt::ForIn(_, ref head, _, ref body) => match **head {
+                ForInHead::Let(_, ref patt) | ForInHead::Const(_, ref patt) => (patt.bound_ids(), body),
+                _ => return Ok(()),
+            },
+            Stmt::ForOf(_, ref head, _, ref body) => match **head {
+                ForOfHead::Let(_, ref patt) | ForOfHead::Const(_, ref patt) => (patt.bound_ids(), body),
+                _ => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+        let lexical: Vec<(&Id, bool)> = lexical.into_iter().map(|id| (id, false)).collect();
+        self.check_declarations(lexical, body.var_declared_ids())
+    }
+
+    // The variable of an enumeration can't be annotated, so an annotation
+    // means a C-style loop.
+    fn for_annotation_opt(&mut self) -> Result<Option<Type>> {
+        let ty = self.type_annotation_opt()?;
+        if ty.is_some() {
+            match self.peek()?.value {
+                TokenData::Reserved(Reserved::In) | TokenData::Identifier(Name::Atom(Atom::Of)) => {
+                    return self.unexpected("type annotation in for-in or for-of head");
+                }
+                _ => {}
+            }
+        }
+        Ok(ty)
+    }
+
     // 'for' '(' 'var' .
     fn for_var(&mut self) -> Result<Stmt> {
         let var_token = self.reread(TokenData::Reserved(Reserved::Var));
         let var_location = Some(var_token.location);
         let lhs = self.pattern()?;
+        let ty = self.for_annotation_opt()?;
         match self.peek()?.value {
             // 'for' '(' 'var' id   '=' .
             // 'for' '(' 'var' patt '=' . ==> C-style
             TokenData::Assign => {
                 self.reread(TokenData::Assign);
+                let rhs = self.allow_in(false, |this| this.assignment_expression())?;
+                let legacy = self.peek()?.value == TokenData::Reserved(Reserved::In);
                 match lhs {
-                    Patt::Simple(id) => {
-                        let rhs = self.allow_in(false, |this| this.assignment_expression())?;
-                        match self.peek()?.value {
-                            // 'for' '(' 'var' id '=' expr ','  . ==> C-style
-                            // 'for' '(' 'var' id '=' expr ';'  . ==> C-style
-                            TokenData::Comma | TokenData::Semi => self.more_for_head(
-                                &var_location,
-                                Dtor::from_simple_init(id, rhs),
-                                ForHead::Var,
-                            ),
-                            // 'for' '(' 'var' id '=' expr 'in' . ==> legacy enumeration
-                            TokenData::Reserved(Reserved::In) => {
-                                self.reread(TokenData::Reserved(Reserved::In));
-                                let head = Box::new(ForInHead::VarInit(
-                                    span(&var_location, &rhs),
-                                    id,
-                                    rhs,
-                                ));
-                                self.more_for_in(head)
-                            }
-                            _ => self.unexpected("expected comma/semi/'in' after for ( var id = "),
-                        }
+                    // 'for' '(' 'var' id '=' expr 'in' . ==> legacy enumeration
+                    Patt::Simple(id) if legacy && ty.is_none() => {
+                        self.reread(TokenData::Reserved(Reserved::In));
+                        let location = span(&var_location, &rhs);
+                        self.legacy_check(Error::ForInInitializer(location))?;
+                        let head = Box::new(ForInHead::VarInit(location, id, rhs));
+                        self.more_for_in(head)
                     }
-                    // 'for' '(' 'var' patt '=' . ==> C-style
-                    Patt::Compound(patt) => {
-                        let rhs = self.allow_in(false, |this| this.assignment_expression())?;
-                        self.more_for_head(
-                            &var_location,
-                            Dtor::from_compound_init(patt, rhs),
-                            ForHead::Var,
-                        )
-                    }
-                    Patt::Assign(_, _, _) => {
-                        // TODO Probably this is illegal
-                        unimplemented!("for loop with assign pattern")
+                    // 'for' '(' 'var' id   '=' expr ',' . ==> C-style
+                    // 'for' '(' 'var' id   '=' expr ';' . ==> C-style
+                    // 'for' '(' 'var' patt '=' expr ',' . ==> C-style
+                    // 'for' '(' 'var' patt '=' expr ';' . ==> C-style
+                    lhs => {
+                        let dtor = annotate(dtor_from_init(lhs, rhs)?, ty);
+                        self.more_for_head(&var_location, dtor, ForHead::Var)
                     }
                 }
             }
@@ -1038,7 +2378,7 @@ impl Parser {
                 // 'for' '(' 'var' patt ',' . ==> syntax error
                 // 'for' '(' 'var' patt ';' . ==> syntax error
                 let dtor = match Dtor::from_init_opt(lhs, None) {
-                    Ok(dtor) => dtor,
+                    Ok(dtor) => annotate(dtor, ty),
                     Err(_) => {
                         return self.unexpected("expected assignment pattern in for (var");
                     }
@@ -1073,13 +2413,15 @@ impl Parser {
         let let_location = Some(let_token.location);
         // 'for' '(' 'let' . !{id, patt} ==> error
         let lhs = self.pattern()?;
+        let ty = self.for_annotation_opt()?;
         match self.peek()?.value {
             // 'for' '(' 'let' id   '=' . ==> C-style
             // 'for' '(' 'let' patt '=' . ==> C-style
             TokenData::Assign => {
                 self.reread(TokenData::Assign);
                 let rhs = self.allow_in(false, |this| this.assignment_expression())?;
-                self.more_for_head(&let_location, Dtor::from_init(lhs, rhs), ForHead::Let)
+                let dtor = annotate(dtor_from_init(lhs, rhs)?, ty);
+                self.more_for_head(&let_location, dtor, ForHead::Let)
             }
             TokenData::Comma | TokenData::Semi => {
                 // 'for' '(' 'let' id   ',' . ==> C-style
@@ -1087,7 +2429,7 @@ impl Parser {
                 // 'for' '(' 'let' patt ',' . ==> error
                 // 'for' '(' 'let' patt ';' . ==> error
                 let dtor = match Dtor::from_init_opt(lhs, None) {
-                    Ok(dtor) => dtor,
+                    Ok(dtor) => annotate(dtor, ty),
                     Err(_) => {
                         return self.unexpected("uninitialized pattern in for (let");
                     }
@@ -1122,13 +2464,15 @@ impl Parser {
         let const_location = Some(const_token.location);
         // 'for' '(' 'const' . !{id, patt} ==> error
         let lhs = self.pattern()?;
+        let ty = self.for_annotation_opt()?;
         match self.peek()?.value {
             // 'for' '(' 'const' {id, patt}   '=' . ==> C-style
             TokenData::Assign => {
                 self.reread(TokenData::Assign);
                 let dtors = self.allow_in(false, |this| {
                     let rhs = this.assignment_expression()?;
-                    let dtor = ConstDtor::from_init(lhs, rhs);
+                    let mut dtor: ConstDtor = dtor_from_init(lhs, rhs)?;
+                    dtor.ty = ty;
                     this.more_comma(dtor, Self::const_declarator)
                 })?;
                 let semi_location = Some(self.expect(TokenData::Semi)?.location);
@@ -1152,6 +2496,9 @@ impl Parser {
     }
 
     fn for_expr(&mut self) -> Result<Stmt> {
+        if let Some(stmt) = self.for_patt_opt()? {
+            return Ok(stmt);
+        }
         let lhs = self.allow_in(false, |this| this.expression())?;
         match self.peek()?.value {
             TokenData::Semi => {
@@ -1187,6 +2534,192 @@ impl Parser {
         }
     }
 
+    // 'for' '(' '[' .
+    // 'for' '(' '{' .
+    //
+    // A destructuring target may contain defaults like `{ a = 1 }`, which are
+    // not expressions, so it's parsed as a pattern when 'in' or 'of' follows
+    // it and as an expression otherwise.
+    fn for_patt_opt(&mut self) -> Result<Option<Stmt>> {
+        match self.peek()?.value {
+            TokenData::LBrack | TokenData::LBrace => {}
+            _ => {
+                return Ok(None);
+            }
+        }
+        let posn = self.lexer.seek_posn();
+        let index = self.lexer.seek_index();
+        let deferred = self.deferred.len();
+        let cover_errors = self.cover_errors.len();
+        if let Ok(patt) = self.assignment_pattern() {
+            match self.peek()?.value {
+                TokenData::Reserved(Reserved::In) => {
+                    self.reread(TokenData::Reserved(Reserved::In));
+                    let head = Box::new(ForInHead::Patt(Patt::Compound(patt)));
+                    return self.more_for_in(head).map(Some);
+                }
+                TokenData::Identifier(Name::Atom(Atom::Of)) => {
+                    self.reread(TokenData::Identifier(Name::Atom(Atom::Of)));
+                    let head = Box::new(ForOfHead::Patt(Patt::Compound(patt)));
+                    return self.more_for_of(head).map(Some);
+                }
+                _ => {}
+            }
+        }
+        self.deferred.truncate(deferred);
+        self.cover_errors.truncate(cover_errors);
+        self.lexer.seek(index, posn);
+        Ok(None)
+    }
+
+    // AssignmentPattern ::=
+    //   ObjectAssignmentPattern
+    //   ArrayAssignmentPattern
+    fn assignment_pattern(&mut self) -> Result<CompoundPatt<Expr>> {
+        self.span(&mut |this| {
+            if this.matches(TokenData::LBrack)? {
+                this.array_assignment_pattern()
+            } else {
+                this.expect(TokenData::LBrace)?;
+                this.object_assignment_pattern()
+            }
+        })
+    }
+
+    // ArrayAssignmentPattern ::=
+    //   "[" (AssignmentElement? ",")* (AssignmentElement | "..." DestructuringAssignmentTarget)? "]"
+    fn array_assignment_pattern(&mut self) -> Result<CompoundPatt<Expr>> {
+        let mut list = Vec::new();
+        let mut rest = None;
+        loop {
+            match self.peek()?.value {
+                TokenData::Comma => {
+                    self.reread(TokenData::Comma);
+                    list.push(None);
+                }
+                TokenData::RBrack => {
+                    break;
+                }
+                TokenData::Ellipsis => {
+                    rest = Some(self.span(&mut |this| {
+                        this.reread(TokenData::Ellipsis);
+                        Ok(RestPatt {
+                            location: None,
+                            patt: this.destructuring_target()?,
+                        })
+                    })?);
+                    break;
+                }
+                _ => {
+                    list.push(Some(self.assignment_element()?));
+                    if !self.matches(TokenData::Comma)? {
+                        break;
+                    }
+                }
+            }
+        }
+        self.expect(TokenData::RBrack)?;
+        Ok(CompoundPatt::Arr(None, list, rest.map(Box::new)))
+    }
+
+    // ObjectAssignmentPattern ::=
+    //   "{" (AssignmentProperty ",")* (AssignmentProperty | "..." DestructuringAssignmentTarget)? "}"
+    fn object_assignment_pattern(&mut self) -> Result<CompoundPatt<Expr>> {
+        let mut list = Vec::new();
+        let mut rest = None;
+        loop {
+            match self.peek()?.value {
+                TokenData::RBrace => {
+                    break;
+                }
+                TokenData::Ellipsis => {
+                    rest = Some(self.span(&mut |this| {
+                        this.reread(TokenData::Ellipsis);
+                        Ok(RestPatt {
+                            location: None,
+                            patt: Patt::Simple(this.simple_assignment_target()?),
+                        })
+                    })?);
+                    break;
+                }
+                _ => {
+                    list.push(self.assignment_property()?);
+                    if !self.matches(TokenData::Comma)? {
+                        break;
+                    }
+                }
+            }
+        }
+        self.expect(TokenData::RBrace)?;
+        Ok(CompoundPatt::Obj(None, list, rest.map(Box::new)))
+    }
+
+    // AssignmentProperty ::=
+    //   IdentifierReference Initializer?
+    //   PropertyName ":" AssignmentElement
+    fn assignment_property(&mut self) -> Result<PropPatt<Expr>> {
+        self.span(&mut |this| {
+            let token = this.read()?;
+            match token.value {
+                TokenData::Identifier(name) => match this.peek()?.value {
+                    TokenData::Colon => {
+                        let key = PropKey::Id(Some(token.location), name.into_owned());
+                        this.reread(TokenData::Colon);
+                        let value = this.assignment_element()?;
+                        Ok(PropPatt::Regular(None, key, value))
+                    }
+                    TokenData::Assign => {
+                        let id = this.new_id(false, name, token.location)?;
+                        this.reread(TokenData::Assign);
+                        let init = this.allow_in(true, |this| this.assignment_expression())?;
+                        let patt = Patt::Simple(id.clone());
+                        let value = Patt::Assign(span(&patt, &init), Box::new(patt), Box::new(init));
+                        Ok(PropPatt::Shorthand(None, id, Some(value)))
+                    }
+                    TokenData::Comma | TokenData::RBrace => {
+                        let id = this.new_id(false, name, token.location)?;
+                        Ok(PropPatt::Shorthand(None, id, None))
+                    }
+                    _ => this.unexpected("expected either shorthand/regular/computed property"),
+                },
+                TokenData::PrivateName(_) => Err(Error::IllegalPrivateName(Some(token.location))),
+                _ => {
+                    this.lexer.unread_token(token);
+                    let key = this.property_key()?;
+                    this.expect(TokenData::Colon)?;
+                    let value = this.assignment_element()?;
+                    Ok(PropPatt::Regular(None, key, value))
+                }
+            }
+        })
+    }
+
+    // AssignmentElement ::= DestructuringAssignmentTarget Initializer?
+    fn assignment_element(&mut self) -> Result<Patt<Expr>> {
+        let target = self.destructuring_target()?;
+        if !self.matches(TokenData::Assign)? {
+            return Ok(target);
+        }
+        let init = self.allow_in(true, |this| this.assignment_expression())?;
+        Ok(Patt::Assign(span(&target, &init), Box::new(target), Box::new(init)))
+    }
+
+    // DestructuringAssignmentTarget ::=
+    //   AssignmentPattern
+    //   LHSExpression
+    fn destructuring_target(&mut self) -> Result<Patt<Expr>> {
+        match self.peek()?.value {
+            TokenData::LBrack | TokenData::LBrace => self.assignment_pattern().map(Patt::Compound),
+            _ => self.simple_assignment_target().map(Patt::Simple),
+        }
+    }
+
+    fn simple_assignment_target(&mut self) -> Result<Expr> {
+        let expr = self.lhs_expression()?;
+        let location = *expr.tracking_ref();
+        expr.into_assignable().map_err(|err| Error::InvalidLHS(location, err))
+    }
+
     // 'for' '(' dtor .
     fn more_for_head<F>(&mut self, start: &Option<Span>, dtor: Dtor, op: F) -> Result<Stmt>
     where
@@ -1280,7 +2813,7 @@ impl Parser {
                 TokenData::Reserved(Reserved::Default) => {
                     if found_default {
                         let token = self.reread(TokenData::Reserved(Reserved::Default));
-                        return Err(Error::DuplicateDefault(token));
+                        return Err(Error::DuplicateDefault(token.into_owned()));
                     }
                     found_default = true;
                     cases.push(self.default()?);
@@ -1290,7 +2823,8 @@ impl Parser {
                 }
             }
         }
-        self.expect(TokenData::RBrace)?;
+        self.close_block()?;
+        self.check_block(cases.iter().flat_map(|case| case.body.iter()))?;
         Ok(cases)
     }
 
@@ -1332,7 +2866,7 @@ impl Parser {
             Some(id)
         } else {
             if !self.context.iteration && !self.context.switch {
-                return Err(Error::IllegalBreak(break_token));
+                return Err(Error::IllegalBreak(break_token.into_owned()));
             }
             None
         };
@@ -1356,7 +2890,7 @@ impl Parser {
             Some(id)
         } else {
             if !self.context.iteration {
-                return Err(Error::IllegalContinue(continue_token));
+                return Err(Error::IllegalContinue(continue_token.into_owned()));
             }
             None
         };
@@ -1384,7 +2918,7 @@ impl Parser {
     fn with_statement(&mut self) -> Result<Stmt> {
         self.span(&mut |this| {
             let token = this.reread(TokenData::Reserved(Reserved::With));
-            this.strict_check(|_| Some(Check::Strict(Error::StrictWith(token))))?;
+            this.strict_check(|_| Some(Check::Strict(Error::StrictWith(token.into_owned()))))?;
             let obj = this.paren_expression()?;
             let body = Box::new(this.statement()?);
             Ok(Stmt::With(None, obj, body))
@@ -1395,7 +2929,7 @@ impl Parser {
         let span = self.start();
         let token = self.reread(TokenData::Reserved(Reserved::Throw));
         if !self.has_arg_same_line()? {
-            return Err(Error::ThrowArgument(token));
+            return Err(Error::ThrowArgument(token.into_owned()));
         }
         let arg = self.allow_in(true, |this| this.expression())?;
         span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Throw(None, arg, semi))
@@ -1408,7 +2942,7 @@ impl Parser {
             match this.peek()?.value {
                 TokenData::Reserved(Reserved::Catch) | TokenData::Reserved(Reserved::Finally) => {}
                 _ => {
-                    return Err(Error::OrphanTry(this.read()?));
+                    return Err(Error::OrphanTry(this.read()?.into_owned()));
                 }
             }
             let catch = this.catch_opt()?.map(Box::new);
@@ -1427,6 +2961,7 @@ impl Parser {
                     this.expect(TokenData::RParen)?;
 
                     let body = this.block()?;
+                    this.check_catch(&param, &body)?;
                     Ok(Catch {
                         location: None,
                         param: param,
@@ -1438,6 +2973,33 @@ impl Parser {
         }
     }
 
+    // Annex B lets the block of sloppy code redeclare a simple catch
+    // parameter with var.
+    fn check_catch(&mut self, param: &Patt<Id>, body: &Block) -> Result<()> {
+        let mut bound = HashSet::new();
+        for id in param.bound_ids() {
+            if !bound.insert(&id.name) {
+                self.early_error(Error::DuplicateDeclaration(id.location, id.name.as_ref().to_string()))?;
+            }
+        }
+        for id in body.items.iter().flat_map(|item| item.lexically_declared_ids()) {
+            if bound.contains(&id.name) {
+                self.early_error(Error::DuplicateDeclaration(id.location, id.name.as_ref().to_string()))?;
+            }
+        }
+        for id in body.items.iter().flat_map(|item| item.var_declared_ids()) {
+            if bound.contains(&id.name) {
+                let error = Error::LexicalVarConflict(id.location, id.name.as_ref().to_string());
+                if param.is_simple() {
+                    self.sloppy_check(error)?;
+                } else {
+                    self.early_error(error)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     fn finally_opt(&mut self) -> Result<Option<Block>> {
         Ok(match self.peek()?.value {
             TokenData::Reserved(Reserved::Finally) => {
@@ -1448,88 +3010,921 @@ impl Parser {
         })
     }
 
-    fn debugger_statement(&mut self) -> Result<Stmt> {
-        let span = self.start();
-        self.reread(TokenData::Reserved(Reserved::Debugger));
-        Ok(span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Debugger(None, semi))?)
+    fn debugger_statement(&mut self) -> Result<Stmt> {
+        let span = self.start();
+        self.reread(TokenData::Reserved(Reserved::Debugger));
+        Ok(span.end_with_auto_semi(self, Newline::Required, |semi| Stmt::Debugger(None, semi))?)
+    }
+
+    fn paren_expression(&mut self) -> Result<Expr> {
+        self.expect(TokenData::LParen)?;
+        let result = self.allow_in(true, |this| this.expression())?;
+        self.expect(TokenData::RParen)?;
+        // println!("parsed right paren expression");
+        let match_arrow = self.matches_op(TokenData::Arrow)?;
+        // println!("match arrow {}", match_arrow);
+        match match_arrow {
+            true => self.unexpected("unexpected arrow after paren expression"),
+            false => Ok(result),
+        }
+    }
+
+    // PrimaryExpression ::=
+    //   "this"
+    //   IdentifierReference
+    //   Literal
+    //   ArrayLiteral
+    //   ObjectLiteral
+    //   FunctionExpression
+    //   ArrowFunctionExpression
+    //   ClassExpression
+    //   GeneratorExpression
+    //   RegularExpressionLiteral
+    //   "(" Expression ")"
+    fn primary_expression(&mut self) -> Result<Expr> {
+        let posn = self.lexer.seek_posn();
+        let index = self.lexer.seek_index();
+        // println!("primary {}", index);
+        // println!("primary {:?}", self.peek()?);
+
+        let token = self.read()?;
+        let location = token.location;
+        self.literal_check(&token)?;
+        Ok(match token.value {
+            TokenData::Identifier(Name::Atom(Atom::Async)) if !self.peek_op()?.newline => {
+                return self.async_expression(token);
+            }
+            TokenData::Identifier(name) => {
+                let match_arrow = self.matches_op(TokenData::Arrow)?;
+                if match_arrow {
+                    self.lexer.seek(index, posn);
+                    self.arrow_function(false, true).map(Expr::Fun)?
+                } else {
+                    Expr::Id(self.new_id(false, name, location)?)
+                }
+            }
+            TokenData::Reserved(Reserved::Null) => Expr::Null(Some(location)),
+            TokenData::Reserved(Reserved::This) => Expr::This(Some(location)),
+            TokenData::Reserved(Reserved::True) => Expr::True(Some(location)),
+            TokenData::Reserved(Reserved::False) => Expr::False(Some(location)),
+            TokenData::Number(literal) => Expr::Number(Some(location), literal.into_owned()),
+            TokenData::BigInt(literal) => Expr::BigInt(Some(location), literal.into_owned()),
+            TokenData::String(literal) => Expr::String(Some(location), literal.into_owned()),
+            TokenData::RegExp(literal) => Expr::RegExp(Some(location), literal.into_owned()),
+            TokenData::Template(_) | TokenData::TemplateHead(_) => {
+                self.lexer.unread_token(token);
+                return Ok(Expr::Template(self.template_literal(false)?));
+            }
+            TokenData::LBrack => {
+                return self.array_literal(token);
+            }
+            TokenData::LBrace => {
+                return self.object_literal(token);
+            }
+            TokenData::LAngle if self.jsx => {
+                self.lexer.set_mode(Mode::JSXTag);
+                let result = self.jsx_element(token)?;
+                self.lexer.set_mode(Mode::Normal);
+                return Ok(result);
+            }
+            TokenData::Reserved(Reserved::Function) => {
+                self.lexer.unread_token(token);
+                return Ok(Expr::Fun(self.function(false, true, |this| this.id_opt(true))?));
+            }
+            TokenData::Reserved(Reserved::Class) => {
+                self.lexer.unread_token(token);
+                return Ok(Expr::Class(self.class(|this| this.id_opt(true))?));
+            }
+            TokenData::At if self.decorators => {
+                self.lexer.unread_token(token);
+                return Ok(Expr::Class(self.decorated_class(|this| this.id_opt(true))?));
+            }
+            TokenData::LParen => {
+                self.lexer.unread_token(token);
+                let errors = self.errors.len();
+                let cover_errors = self.cover_errors.len();
+                return match self.paren_expression() {
+                    Err(error) => {
+                        self.lexer.seek(index, posn);
+                        self.errors.truncate(errors);
+                        self.cover_errors.truncate(cover_errors);
+                        self.arrow_function(false, false).map(Expr::Fun).map_err(|arrow_error| {
+                            cover_or_arrow_error(error, arrow_error)
+                        })
+                    }
+                    // A return type annotation makes the parameters of an
+                    // arrow function look like a parenthesized expression.
+                    Ok(expr) => {
+                        if self.types && self.context.allow_arrow_return_type && self.peek_op()?.value == TokenData::Colon {
+                            let after_posn = self.lexer.seek_posn();
+                            let after_index = self.lexer.seek_index();
+                            self.lexer.seek(index, posn);
+                            if let Some(fun) = self.speculate(|this| this.arrow_function(false, false)) {
+                                return Ok(Expr::Fun(fun));
+                            }
+                            self.lexer.seek(after_index, after_posn);
+                        }
+                        Ok(expr)
+                    }
+                };
+            }
+            TokenData::LAngle if self.types => {
+                self.lexer.unread_token(token);
+                return self.arrow_function(false, false).map(Expr::Fun);
+            }
+            // ES6: more cases
+            _ => {
+                return Err(Error::UnexpectedToken(token.into_owned(), "unhandled primary expression"));
+            }
+        })
+    }
+
+    // JSXElement ::=
+    //   "<" JSXElementName JSXAttribute* "/" ">"
+    //   "<" JSXElementName JSXAttribute* ">" JSXChild* "<" "/" JSXElementName ">"
+    // JSXFragment ::=
+    //   "<" ">" JSXChild* "<" "/" ">"
+    //
+    // Called after the "<" with the lexer in JSX tag mode, which is where it
+    // is left after the final ">".
+    fn jsx_element(&mut self, start: Token) -> Result<Expr> {
+        let start_location = Some(start.location);
+        if self.matches(TokenData::RAngle)? {
+            let (children, end) = self.jsx_children(None)?;
+            return Ok(Expr::JSXFragment(JSXFragment {
+                location: span(&start_location, &Some(end.location)),
+                children: children,
+            }));
+        }
+        let name = self.jsx_name(true)?;
+        let mut attrs = Vec::new();
+        loop {
+            match self.peek()?.value {
+                TokenData::Slash => {
+                    self.reread(TokenData::Slash);
+                    let end = self.expect(TokenData::RAngle)?;
+                    return Ok(Expr::JSXElement(JSXElement {
+                        location: span(&start_location, &Some(end.location)),
+                        name: name,
+                        attrs: attrs,
+                        children: None,
+                    }));
+                }
+                TokenData::RAngle => {
+                    self.reread(TokenData::RAngle);
+                    break;
+                }
+                _ => {
+                    attrs.push(self.jsx_attribute()?);
+                }
+            }
+        }
+        let (children, end) = self.jsx_children(Some(&name))?;
+        Ok(Expr::JSXElement(JSXElement {
+            location: span(&start_location, &Some(end.location)),
+            name: name,
+            attrs: attrs,
+            children: Some(children),
+        }))
+    }
+
+    // Reads children up to and including the closing tag, which must match
+    // the given name, or be empty for a fragment.
+    fn jsx_children(&mut self, open: Option<&JSXName>) -> Result<(Vec<JSXChild>, Token<'a>)> {
+        self.lexer.set_mode(Mode::JSXChild);
+        let mut children = Vec::new();
+        loop {
+            let token = self.read()?;
+            match token.value {
+                TokenData::JSXText(text) => {
+                    children.push(JSXChild::Text(Some(token.location), text));
+                }
+                TokenData::LBrace => {
+                    children.push(self.jsx_child_expression(token)?);
+                }
+                TokenData::LAngle => {
+                    self.lexer.set_mode(Mode::JSXTag);
+                    if self.matches(TokenData::Slash)? {
+                        let close = if self.peek()?.value == TokenData::RAngle {
+                            None
+                        } else {
+                            Some(self.jsx_name(true)?)
+                        };
+                        if open.map(JSXName::qualified) != close.as_ref().map(JSXName::qualified) {
+                            let location = match close {
+                                Some(ref name) => span(&Some(token.location), name),
+                                None => Some(token.location)
+                            };
+                            return Err(Error::MismatchedJSXTag(location));
+                        }
+                        let end = self.expect(TokenData::RAngle)?;
+                        return Ok((children, end));
+                    }
+                    children.push(match self.jsx_element(token)? {
+                        Expr::JSXElement(element) => JSXChild::Element(element),
+                        Expr::JSXFragment(fragment) => JSXChild::Fragment(fragment),
+                        _ => unreachable!(),
+                    });
+                    self.lexer.set_mode(Mode::JSXChild);
+                }
+                _ => {
+                    return Err(Error::UnexpectedToken(token.into_owned(), "unterminated JSX element"));
+                }
+            }
+        }
+    }
+
+    // JSXChildExpression ::=
+    //   "{" "}"
+    //   "{" "..." AssignmentExpression "}"
+    //   "{" AssignmentExpression "}"
+    fn jsx_child_expression(&mut self, start: Token) -> Result<JSXChild> {
+        self.lexer.set_mode(Mode::Normal);
+        let start_location = Some(start.location);
+        let spread = self.matches(TokenData::Ellipsis)?;
+        let expr = if !spread && self.peek()?.value == TokenData::RBrace {
+            None
+        } else {
+            Some(self.allow_in(true, |this| this.assignment_expression())?)
+        };
+        let end = self.expect(TokenData::RBrace)?;
+        self.lexer.set_mode(Mode::JSXChild);
+        let location = span(&start_location, &Some(end.location));
+        Ok(match expr {
+            Some(expr) if spread => JSXChild::Spread(location, expr),
+            expr => JSXChild::Expr(location, expr),
+        })
+    }
+
+    // JSXAttribute ::=
+    //   "{" "..." AssignmentExpression "}"
+    //   JSXAttributeName ("=" JSXAttributeValue)?
+    // JSXAttributeValue ::=
+    //   JSXString
+    //   "{" AssignmentExpression "}"
+    //   JSXElement
+    //   JSXFragment
+    fn jsx_attribute(&mut self) -> Result<JSXAttr> {
+        let token = self.read()?;
+        if token.value == TokenData::LBrace {
+            let (expr, end) = self.jsx_attribute_expression(true)?;
+            return Ok(JSXAttr::Spread(span(&Some(token.location), &Some(end.location)), expr));
+        }
+        self.lexer.unread_token(token);
+        let name = self.jsx_name(false)?;
+        if !self.matches(TokenData::Assign)? {
+            return Ok(JSXAttr::Regular(*name.tracking_ref(), name, None));
+        }
+        let token = self.read()?;
+        let location = Some(token.location);
+        let value = match token.value {
+            TokenData::String(literal) => JSXAttrValue::String(location, literal.into_owned()),
+            TokenData::LBrace => {
+                let (expr, end) = self.jsx_attribute_expression(false)?;
+                JSXAttrValue::Expr(span(&location, &Some(end.location)), expr)
+            }
+            TokenData::LAngle => match self.jsx_element(token)? {
+                Expr::JSXElement(element) => JSXAttrValue::Element(element),
+                Expr::JSXFragment(fragment) => JSXAttrValue::Fragment(fragment),
+                _ => unreachable!(),
+            },
+            _ => {
+                return Err(Error::UnexpectedToken(token.into_owned(), "expected JSX attribute value"));
+            }
+        };
+        Ok(JSXAttr::Regular(span(&name, &value), name, Some(value)))
+    }
+
+    // Called after the "{" of an attribute value or spread attribute.
+    fn jsx_attribute_expression(&mut self, spread: bool) -> Result<(Expr, Token<'a>)> {
+        self.lexer.set_mode(Mode::Normal);
+        if spread {
+            self.expect(TokenData::Ellipsis)?;
+        }
+        let expr = self.allow_in(true, |this| this.assignment_expression())?;
+        let end = self.expect(TokenData::RBrace)?;
+        self.lexer.set_mode(Mode::JSXTag);
+        Ok((expr, end))
+    }
+
+    // JSXElementName ::=
+    //   JSXIdentifier
+    //   JSXIdentifier ":" JSXIdentifier
+    //   JSXIdentifier ("." JSXIdentifier)+
+    //
+    // Attribute names may not be member expressions.
+    fn jsx_name(&mut self, allow_member: bool) -> Result<JSXName> {
+        let id = self.jsx_id()?;
+        if self.matches(TokenData::Colon)? {
+            let name = self.jsx_id()?;
+            return Ok(JSXName::Namespaced(span(&id, &name), id, name));
+        }
+        let mut result = JSXName::Id(id);
+        while allow_member && self.matches(TokenData::Dot)? {
+            let property = self.jsx_id()?;
+            let location = span(&result, &property);
+            result = JSXName::Member(location, Box::new(result), property);
+        }
+        Ok(result)
+    }
+
+    fn jsx_id(&mut self) -> Result<JSXId> {
+        let token = self.read()?;
+        match token.value {
+            TokenData::JSXName(name) => Ok(JSXId {
+                location: Some(token.location),
+                name: name.into_owned(),
+            }),
+            _ => Err(Error::UnexpectedToken(token.into_owned(), "expected JSX identifier")),
+        }
+    }
+
+    // Attempts a parse that the type annotation syntax makes ambiguous,
+    // rewinding everything it did if it fails.
+    fn speculate<T, F>(&mut self, parse: F) -> Option<T>
+    where
+        F: FnOnce(&mut Self) -> Result<T>,
+    {
+        let posn = self.lexer.seek_posn();
+        let index = self.lexer.seek_index();
+        let context = self.context.clone();
+        let deferred = self.deferred.len();
+        let errors = self.errors.len();
+        let cover_errors = self.cover_errors.len();
+        match parse(self) {
+            Ok(result) => Some(result),
+            Err(_) => {
+                self.lexer.seek(index, posn);
+                self.context = context;
+                self.deferred.truncate(deferred);
+                self.errors.truncate(errors);
+                self.cover_errors.truncate(cover_errors);
+                None
+            }
+        }
+    }
+
+    // TypeAnnotation ::= ":" Type
+    fn type_annotation_opt(&mut self) -> Result<Option<Type>> {
+        if !self.types || !self.matches(TokenData::Colon)? {
+            return Ok(None);
+        }
+        self.ty().map(Some)
+    }
+
+    // Annotation ::= "?"? TypeAnnotation?
+    fn annotation_opt(&mut self) -> Result<Option<Annot>> {
+        match self.peek()?.value {
+            TokenData::Question | TokenData::Colon => {}
+            _ => { return Ok(None); }
+        }
+        self.span(&mut |this| {
+            let optional = this.matches(TokenData::Question)?;
+            Ok(Annot {
+                location: None,
+                optional: optional,
+                ty: this.type_annotation_opt()?,
+            })
+        }).map(Some)
+    }
+
+    // A type name is never a reference to a variable, so it is exempt from
+    // the restrictions on identifiers.
+    fn type_id(&mut self) -> Result<Id> {
+        let token = self.read()?;
+        match token.value {
+            TokenData::Identifier(name) => Ok(Id::new(name, Some(token.location))),
+            _ => Err(Error::UnexpectedToken(token.into_owned(), "expected type name")),
+        }
+    }
+
+    // The ">" that closes a list of type arguments or parameters may have
+    // been lexed as the start of a longer operator like ">>" or ">=".
+    fn type_close(&mut self) -> Result<Token<'a>> {
+        let token = self.read_op()?;
+        match token.value {
+            TokenData::RAngle => Ok(token),
+            TokenData::RShift
+          | TokenData::URShift
+          | TokenData::GEq
+          | TokenData::RShiftAssign
+          | TokenData::URShiftAssign => {
+                let start = token.location.start;
+                let end = Posn {
+                    offset: start.offset + 1,
+                    line: start.line,
+                    column: start.column + 1,
+                };
+                self.lexer.seek(end.offset as usize, end);
+                Ok(Token {
+                    location: Span { start: start, end: end },
+                    newline: token.newline,
+                    value: TokenData::RAngle,
+                })
+            }
+            _ => Err(Error::UnexpectedToken(token.into_owned(), "expected '>'")),
+        }
+    }
+
+    // TypeParameters ::= "<" TypeParameter+[","] ">"
+    fn type_params_opt(&mut self) -> Result<Vec<TypeParam>> {
+        if !self.types || !self.matches(TokenData::LAngle)? {
+            return Ok(Vec::new());
+        }
+        let params = self.comma_separated(Self::type_param)?;
+        self.type_close()?;
+        Ok(params)
+    }
+
+    // TypeParameter ::= Identifier ("extends" Type)? ("=" Type)?
+    fn type_param(&mut self) -> Result<TypeParam> {
+        self.span(&mut |this| {
+            let id = this.type_id()?;
+            let constraint = if this.matches(TokenData::Reserved(Reserved::Extends))? {
+                Some(this.ty()?)
+            } else {
+                None
+            };
+            let default = if this.matches(TokenData::Assign)? {
+                Some(this.ty()?)
+            } else {
+                None
+            };
+            Ok(TypeParam {
+                location: None,
+                id: id,
+                constraint: constraint,
+                default: default,
+            })
+        })
+    }
+
+    // TypeArguments ::= "<" Type+[","] ">"
+    fn type_arguments(&mut self) -> Result<(Vec<Type>, Token<'a>)> {
+        self.expect(TokenData::LAngle)?;
+        let types = self.comma_separated(Self::ty)?;
+        let end = self.type_close()?;
+        Ok((types, end))
+    }
+
+    // Type arguments in an expression are only recognized before arguments
+    // or a template, since otherwise "<" is a relational operator.
+    fn expr_type_arguments_opt(&mut self) -> Result<Option<(Vec<Type>, Token<'a>)>> {
+        if !self.types || self.peek_op()?.value != TokenData::LAngle {
+            return Ok(None);
+        }
+        Ok(self.speculate(|this| {
+            let args = this.type_arguments()?;
+            match this.peek_op()?.value {
+                TokenData::LParen | TokenData::Template(_) | TokenData::TemplateHead(_) => Ok(args),
+                _ => this.unexpected("expected arguments after type arguments"),
+            }
+        }))
+    }
+
+    // Type ::= NonConditionalType ("extends" NonConditionalType "?" Type ":" Type)?
+    fn ty(&mut self) -> Result<Type> {
+        let check = self.non_conditional_type()?;
+        {
+            let next = self.peek_op()?;
+            if next.value != TokenData::Reserved(Reserved::Extends) || next.newline {
+                return Ok(check);
+            }
+        }
+        self.reread(TokenData::Reserved(Reserved::Extends));
+        let extends = self.non_conditional_type()?;
+        self.expect(TokenData::Question)?;
+        let cons = self.ty()?;
+        self.expect(TokenData::Colon)?;
+        let alt = self.ty()?;
+        Ok(Type::Conditional(span(&check, &alt), Box::new(check), Box::new(extends), Box::new(cons), Box::new(alt)))
+    }
+
+    // NonConditionalType ::=
+    //   FunctionType
+    //   "|"? IntersectionType ("|" IntersectionType)*
+    fn non_conditional_type(&mut self) -> Result<Type> {
+        match self.peek()?.value {
+            TokenData::LAngle => {
+                return self.fun_type();
+            }
+            TokenData::LParen => {
+                if let Some(ty) = self.speculate(|this| this.fun_type()) {
+                    return Ok(ty);
+                }
+            }
+            _ => {}
+        }
+        let leading = self.matches(TokenData::BitOr)?;
+        let first = self.intersection_type()?;
+        if !leading && self.peek_op()?.value != TokenData::BitOr {
+            return Ok(first);
+        }
+        let mut types = vec![first];
+        while self.matches_op(TokenData::BitOr)? {
+            types.push(self.intersection_type()?);
+        }
+        if types.len() == 1 {
+            return Ok(types.pop().unwrap());
+        }
+        let location = self.vec_span(&types);
+        Ok(Type::Union(location, types))
+    }
+
+    // IntersectionType ::= "&"? TypeOperator ("&" TypeOperator)*
+    fn intersection_type(&mut self) -> Result<Type> {
+        let leading = self.matches(TokenData::BitAnd)?;
+        let first = self.type_operator()?;
+        if !leading && self.peek_op()?.value != TokenData::BitAnd {
+            return Ok(first);
+        }
+        let mut types = vec![first];
+        while self.matches_op(TokenData::BitAnd)? {
+            types.push(self.type_operator()?);
+        }
+        if types.len() == 1 {
+            return Ok(types.pop().unwrap());
+        }
+        let location = self.vec_span(&types);
+        Ok(Type::Intersection(location, types))
+    }
+
+    // TypeOperator ::= "keyof"* ArrayType
+    fn type_operator(&mut self) -> Result<Type> {
+        if let TokenData::Identifier(Name::String(ref name)) = self.peek()?.value {
+            if name != "keyof" {
+                return self.array_type();
+            }
+        } else {
+            return self.array_type();
+        }
+        let token = self.lexer.reread_token();
+        let is_operator = match self.peek()?.value {
+            TokenData::Identifier(_)
+          | TokenData::LParen
+          | TokenData::LBrack
+          | TokenData::LBrace
+          | TokenData::Reserved(Reserved::Typeof) => true,
+            _ => false,
+        };
+        if !is_operator {
+            self.lexer.unread_token(token);
+            return self.array_type();
+        }
+        let ty = self.type_operator()?;
+        Ok(Type::Keyof(span(&Some(token.location), &ty), Box::new(ty)))
+    }
+
+    // ArrayType ::= PrimaryType ("[" "]")*
+    fn array_type(&mut self) -> Result<Type> {
+        let mut ty = self.primary_type()?;
+        loop {
+            {
+                let next = self.peek_op()?;
+                if next.value != TokenData::LBrack || next.newline {
+                    break;
+                }
+            }
+            self.reread(TokenData::LBrack);
+            let end = self.expect(TokenData::RBrack)?;
+            ty = Type::Array(span(&ty, &Some(end.location)), Box::new(ty));
+        }
+        Ok(ty)
+    }
+
+    // PrimaryType ::=
+    //   "(" Type ")"
+    //   "[" Type*[","] "]"
+    //   ObjectType
+    //   "typeof" TypeName
+    //   "void" | "null" | "this" | "true" | "false"
+    //   NumberLiteral
+    //   StringLiteral
+    //   TypeName TypeArguments?
+    fn primary_type(&mut self) -> Result<Type> {
+        let token = self.read()?;
+        let location = Some(token.location);
+        Ok(match token.value {
+            TokenData::LParen => {
+                let ty = self.ty()?;
+                let end = self.expect(TokenData::RParen)?;
+                Type::Paren(span(&location, &Some(end.location)), Box::new(ty))
+            }
+            TokenData::LBrack => {
+                let mut types = Vec::new();
+                while self.peek()?.value != TokenData::RBrack {
+                    types.push(self.ty()?);
+                    if !self.matches(TokenData::Comma)? {
+                        break;
+                    }
+                }
+                let end = self.expect(TokenData::RBrack)?;
+                Type::Tuple(span(&location, &Some(end.location)), types)
+            }
+            TokenData::LBrace => {
+                let span = SpanTracker::new(token.location.start);
+                self.lexer.unread_token(token);
+                let members = self.type_members()?;
+                return Ok(span.end(self, Type::Object(None, members)));
+            }
+            TokenData::Reserved(Reserved::Typeof) => {
+                let name = self.type_name()?;
+                Type::Typeof(span(&location, &name), name)
+            }
+            TokenData::Reserved(Reserved::Void) => Type::Void(location),
+            TokenData::Reserved(Reserved::Null) => Type::Null(location),
+            TokenData::Reserved(Reserved::This) => Type::This(location),
+            TokenData::Reserved(Reserved::True) => Type::True(location),
+            TokenData::Reserved(Reserved::False) => Type::False(location),
+            TokenData::Number(literal) => Type::Number(location, literal.into_owned()),
+            TokenData::String(literal) => Type::String(location, literal.into_owned()),
+            TokenData::Identifier(_) => {
+                self.lexer.unread_token(token);
+                let name = self.type_name()?;
+                if self.peek_op()?.value != TokenData::LAngle {
+                    let location = *name.tracking_ref();
+                    return Ok(Type::Ref(location, name, Vec::new()));
+                }
+                let (args, end) = self.type_arguments()?;
+                Type::Ref(span(&name, &Some(end.location)), name, args)
+            }
+            _ => {
+                return Err(Error::UnexpectedToken(token.into_owned(), "expected type"));
+            }
+        })
+    }
+
+    // TypeName ::= Identifier ("." Identifier)*
+    fn type_name(&mut self) -> Result<TypeName> {
+        let mut name = TypeName::Id(self.type_id()?);
+        while self.matches_op(TokenData::Dot)? {
+            let id = self.type_id()?;
+            name = TypeName::Qualified(span(&name, &id), Box::new(name), id);
+        }
+        Ok(name)
+    }
+
+    // FunctionType ::= TypeParameters? "(" ParamType*[","] ")" "=>" Type
+    fn fun_type(&mut self) -> Result<Type> {
+        let fun = self.span(&mut |this| {
+            let (type_params, params) = this.type_signature()?;
+            this.expect(TokenData::Arrow)?;
+            Ok(FunType {
+                location: None,
+                type_params: type_params,
+                params: params,
+                ret: Some(this.ty()?),
+            })
+        })?;
+        Ok(Type::Fun(*fun.tracking_ref(), Box::new(fun)))
+    }
+
+    // MethodSignature ::= TypeParameters? "(" ParamType*[","] ")" TypeAnnotation?
+    fn method_signature(&mut self) -> Result<FunType> {
+        self.span(&mut |this| {
+            let (type_params, params) = this.type_signature()?;
+            Ok(FunType {
+                location: None,
+                type_params: type_params,
+                params: params,
+                ret: this.type_annotation_opt()?,
+            })
+        })
+    }
+
+    fn type_signature(&mut self) -> Result<(Vec<TypeParam>, Vec<ParamType>)> {
+        let type_params = self.type_params_opt()?;
+        self.expect(TokenData::LParen)?;
+        let mut params = Vec::new();
+        while self.peek()?.value != TokenData::RParen {
+            params.push(self.param_type()?);
+            if !self.matches(TokenData::Comma)? {
+                break;
+            }
+        }
+        self.expect(TokenData::RParen)?;
+        Ok((type_params, params))
+    }
+
+    // ParamType ::= "..."? Identifier "?"? TypeAnnotation?
+    fn param_type(&mut self) -> Result<ParamType> {
+        self.span(&mut |this| {
+            let rest = this.matches(TokenData::Ellipsis)?;
+            let id = this.type_id()?;
+            let optional = this.matches(TokenData::Question)?;
+            Ok(ParamType {
+                location: None,
+                rest: rest,
+                id: id,
+                optional: optional,
+                ty: this.type_annotation_opt()?,
+            })
+        })
+    }
+
+    // ObjectType ::= "{" (TypeMember (";" | ",")?)* "}"
+    fn type_members(&mut self) -> Result<Vec<TypeMember>> {
+        self.expect(TokenData::LBrace)?;
+        let mut members = Vec::new();
+        while self.peek()?.value != TokenData::RBrace {
+            members.push(self.type_member()?);
+            if !self.matches(TokenData::Semi)? && !self.matches(TokenData::Comma)? {
+                let next = self.peek()?;
+                if !next.newline && next.value != TokenData::RBrace {
+                    return self.unexpected("expected ';' or ',' after type member");
+                }
+            }
+        }
+        self.reread(TokenData::RBrace);
+        Ok(members)
+    }
+
+    // TypeMember ::=
+    //   MethodSignature
+    //   "[" Identifier TypeAnnotation "]" TypeAnnotation
+    //   "readonly"? PropertyKey "?"? TypeAnnotation?
+    //   PropertyKey "?"? MethodSignature
+    fn type_member(&mut self) -> Result<TypeMember> {
+        match self.peek()?.value {
+            TokenData::LParen | TokenData::LAngle => {
+                return self.method_signature().map(TypeMember::Call);
+            }
+            TokenData::LBrack => {
+                if let Some(member) = self.speculate(|this| this.index_signature()) {
+                    return Ok(member);
+                }
+            }
+            _ => {}
+        }
+        self.span(&mut |this| {
+            let readonly = this.readonly_opt()?;
+            let key = this.property_key()?;
+            let optional = this.matches(TokenData::Question)?;
+            match this.peek()?.value {
+                TokenData::LParen | TokenData::LAngle if !readonly => {
+                    let fun = this.method_signature()?;
+                    Ok(TypeMember::Method(None, key, optional, fun))
+                }
+                _ => Ok(TypeMember::Prop(PropType {
+                    location: None,
+                    readonly: readonly,
+                    key: key,
+                    optional: optional,
+                    ty: this.type_annotation_opt()?,
+                })),
+            }
+        })
+    }
+
+    // A "readonly" that isn't itself the property key.
+    fn readonly_opt(&mut self) -> Result<bool> {
+        match self.peek()?.value {
+            TokenData::Identifier(Name::String(ref name)) if name == "readonly" => {}
+            _ => { return Ok(false); }
+        }
+        let token = self.lexer.reread_token();
+        let readonly = match self.peek()?.value {
+            TokenData::Colon
+          | TokenData::Question
+          | TokenData::LParen
+          | TokenData::LAngle
+          | TokenData::Semi
+          | TokenData::Comma
+          | TokenData::RBrace => false,
+            _ => true,
+        };
+        if !readonly {
+            self.lexer.unread_token(token);
+        }
+        Ok(readonly)
+    }
+
+    fn index_signature(&mut self) -> Result<TypeMember> {
+        self.span(&mut |this| {
+            this.expect(TokenData::LBrack)?;
+            let id = this.type_id()?;
+            this.expect(TokenData::Colon)?;
+            let key = this.ty()?;
+            this.expect(TokenData::RBrack)?;
+            this.expect(TokenData::Colon)?;
+            let ty = this.ty()?;
+            Ok(TypeMember::Index(None, id, key, ty))
+        })
     }
 
-    fn paren_expression(&mut self) -> Result<Expr> {
-        self.expect(TokenData::LParen)?;
-        let result = self.allow_in(true, |this| this.expression())?;
-        self.expect(TokenData::RParen)?;
-        // println!("parsed right paren expression");
-        let match_arrow = self.matches_op(TokenData::Arrow)?;
-        // println!("match arrow {}", match_arrow);
-        match match_arrow {
-            true => self.unexpected("unexpected arrow after paren expression"),
-            false => Ok(result),
+    // TypeDeclaration ::=
+    //   "type" Identifier TypeParameters? "=" Type ";"
+    //   "interface" Identifier TypeParameters? ("extends" Type+[","])? ObjectType
+    //
+    // Both words are only keywords when followed by a name on the same line.
+    fn type_declaration_opt(&mut self) -> Result<Option<Decl>> {
+        let is_alias = match self.peek()?.value {
+            TokenData::Identifier(Name::Atom(Atom::Interface)) => false,
+            TokenData::Identifier(Name::String(ref name)) if name == "type" => true,
+            _ => { return Ok(None); }
+        };
+        let token = self.lexer.reread_token();
+        match *self.peek_op()? {
+            Token { value: TokenData::Identifier(_), newline: false, .. } => {}
+            _ => {
+                self.lexer.unread_token(token);
+                return Ok(None);
+            }
+        }
+        let span = SpanTracker::new(token.location.start);
+        let id = self.type_id()?;
+        let type_params = self.type_params_opt()?;
+        if is_alias {
+            self.expect(TokenData::Assign)?;
+            let ty = self.ty()?;
+            return span.end_with_auto_semi(self, Newline::Required, |semi| {
+                Decl::TypeAlias(TypeAlias {
+                    location: None,
+                    id: id,
+                    type_params: type_params,
+                    ty: ty,
+                    semi: semi,
+                })
+            }).map(Some);
         }
+        let extends = if self.matches(TokenData::Reserved(Reserved::Extends))? {
+            self.comma_separated(Self::ty)?
+        } else {
+            Vec::new()
+        };
+        let body = self.type_members()?;
+        Ok(Some(span.end(self, Decl::Interface(Interface {
+            location: None,
+            id: id,
+            type_params: type_params,
+            extends: extends,
+            body: body,
+        }))))
     }
 
-    // PrimaryExpression ::=
-    //   "this"
-    //   IdentifierReference
-    //   Literal
-    //   ArrayLiteral
-    //   ObjectLiteral
-    //   FunctionExpression
-    //   ArrowFunctionExpression
-    //   ClassExpression
-    //   GeneratorExpression
-    //   RegularExpressionLiteral
-    //   "(" Expression ")"
-    fn primary_expression(&mut self) -> Result<Expr> {
-        let posn = self.lexer.posn();
-        let index = self.lexer.seek_index();
-        // println!("primary {}", index);
-        // println!("primary {:?}", self.peek()?);
-
-        let token = self.read()?;
-        let location = token.location;
-        Ok(match token.value {
-            TokenData::Identifier(name) => {
-                let match_arrow = self.matches_op(TokenData::Arrow)?;
+    // An "async" token not followed by a newline:
+    //   "async" "function" ...
+    //   "async" Identifier "=>" ...
+    //   "async" Arguments "=>" ...
+    //   "async" Arguments
+    //   "async"
+    fn async_expression(&mut self, token: Token<'a>) -> Result<Expr> {
+        match self.peek_op()?.value {
+            TokenData::Reserved(Reserved::Function) => {
+                return Ok(Expr::Fun(self.function(true, true, |this| this.id_opt(true))?));
+            }
+            TokenData::Identifier(_) => {
+                let param = self.lexer.reread_token();
+                let match_arrow = self.peek_op()?.value == TokenData::Arrow;
+                self.lexer.unread_token(param);
                 if match_arrow {
-                    self.lexer.seek(index, posn);
-                    self.arrow_function(true).map(Expr::Fun)?
-                } else {
-                    Expr::Id(self.new_id(false, name, location)?)
+                    return self.arrow_function(true, true).map(Expr::Fun);
                 }
             }
-            TokenData::Reserved(Reserved::Null) => Expr::Null(Some(location)),
-            TokenData::Reserved(Reserved::This) => Expr::This(Some(location)),
-            TokenData::Reserved(Reserved::True) => Expr::True(Some(location)),
-            TokenData::Reserved(Reserved::False) => Expr::False(Some(location)),
-            TokenData::Number(literal) => Expr::Number(Some(location), literal),
-            TokenData::String(literal) => Expr::String(Some(location), literal),
-            TokenData::RegExp(literal) => Expr::RegExp(Some(location), literal),
-            TokenData::LBrack => {
-                return self.array_literal(token);
-            }
-            TokenData::LBrace => {
-                return self.object_literal(token);
-            }
-            TokenData::Reserved(Reserved::Function) => {
+            // `async` itself as the parameter of a plain arrow function.
+            TokenData::Arrow => {
                 self.lexer.unread_token(token);
-                return Ok(Expr::Fun(self.function(|this| this.id_opt(true))?));
+                return self.arrow_function(false, true).map(Expr::Fun);
+            }
+            // A generic async arrow, or a call of `async` with type arguments.
+            TokenData::LAngle => {
+                if self.types {
+                    if let Some(fun) = self.speculate(|this| this.arrow_function(true, false)) {
+                        return Ok(Expr::Fun(fun));
+                    }
+                }
             }
             TokenData::LParen => {
-                self.lexer.unread_token(token);
-
-                return match self.paren_expression() {
-                    Err(_) => {
-                        self.lexer.seek(index, posn);
-                        self.arrow_function(false).map(Expr::Fun)
+                if self.types {
+                    if let Some(fun) = self.speculate(|this| this.arrow_function(true, false)) {
+                        return Ok(Expr::Fun(fun));
                     }
-                    expr => expr,
+                }
+                let posn = self.lexer.seek_posn();
+                let index = self.lexer.seek_index();
+                let errors = self.errors.len();
+                let cover_errors = self.cover_errors.len();
+                let call_error = match self.arguments() {
+                    Ok(args) => {
+                        if self.peek_op()?.value != TokenData::Arrow {
+                            let callee = self.new_id_from_token(false, token)?;
+                            return Ok(args.append_to(Expr::Id(callee)));
+                        }
+                        None
+                    }
+                    Err(error) => Some(error),
                 };
+                self.lexer.seek(index, posn);
+                self.errors.truncate(errors);
+                self.cover_errors.truncate(cover_errors);
+                return self.arrow_function(true, false).map(Expr::Fun).map_err(|arrow_error| match call_error {
+                    Some(error) => cover_or_arrow_error(error, arrow_error),
+                    None => arrow_error,
+                });
             }
-            // ES6: more cases
-            _ => {
-                return Err(Error::UnexpectedToken(token, "unhandled primary expression"));
-            }
-        })
+            _ => {}
+        }
+        Ok(Expr::Id(self.new_id_from_token(false, token)?))
     }
 
     fn array_literal(&mut self, start: Token) -> Result<Expr> {
@@ -1545,20 +3940,26 @@ impl Parser {
                 if !this.matches(TokenData::Comma)? {
                     break;
                 }
+                if this.peek()?.value == TokenData::RBrack {
+                    if let Some(&mut Some(ExprListItem::Spread(_, _, ref mut comma))) = elts.last_mut() {
+                        *comma = true;
+                    }
+                }
             }
             let end_location = Some(this.expect(TokenData::RBrack)?.location);
             Ok(Expr::Arr(span(&start_location, &end_location), elts))
         })
     }
 
-    fn expr_list_item(&mut self) -> Result<ExprListItem> {
+    // An element of an array literal may be a nested pattern.
+    fn expr_list_item(&mut self, element: bool) -> Result<ExprListItem> {
         match self.peek()?.value {
             TokenData::Ellipsis => self.span(&mut |this| {
                 this.reread(TokenData::Ellipsis);
-                let expr = this.assignment_expression()?;
-                Ok(ExprListItem::Spread(None, expr))
+                let expr = this.cover_assignment_expression(element)?;
+                Ok(ExprListItem::Spread(None, expr, false))
             }),
-            _ => Ok(ExprListItem::Expr(self.assignment_expression()?)),
+            _ => Ok(ExprListItem::Expr(self.cover_assignment_expression(element)?)),
         }
     }
 
@@ -1566,22 +3967,49 @@ impl Parser {
         if self.peek()?.value == TokenData::Comma {
             return Ok(None);
         }
-        self.expr_list_item().map(Some)
+        self.expr_list_item(true).map(Some)
     }
 
     fn object_literal(&mut self, start: Token) -> Result<Expr> {
         let start_location = Some(start.location);
         let mut props = Vec::new();
+        let mut proto = false;
+        let mut duplicate_proto = None;
         loop {
             if self.peek()?.value == TokenData::RBrace {
                 break;
             }
-            props.push(self.object_property()?);
+            let prop = self.object_property()?;
+            match prop {
+                // Private names only name class elements.
+                Prop::Regular(_, PropKey::Private(ref name), _)
+              | Prop::Method(PropKey::Private(ref name), _) => {
+                    return Err(Error::IllegalPrivateName(name.location));
+                }
+                Prop::Regular(ref location, ref key, PropVal::Init(_)) if key.name() == Some("__proto__") => {
+                    if proto && duplicate_proto.is_none() {
+                        duplicate_proto = Some(*location);
+                    }
+                    proto = true;
+                }
+                _ => {}
+            }
+            props.push(prop);
             if !self.matches(TokenData::Comma)? {
                 break;
             }
+            if self.peek()?.value == TokenData::RBrace {
+                if let Some(&mut Prop::Spread(_, _, ref mut comma)) = props.last_mut() {
+                    *comma = true;
+                }
+            }
         }
         let end_location = Some(self.expect(TokenData::RBrace)?.location);
+        // `__proto__: value` sets the prototype, so it may appear only once,
+        // unless the literal turns out to be a destructuring pattern.
+        if let Some(location) = duplicate_proto {
+            self.cover_errors.push(Error::DuplicateProto(location));
+        }
         Ok(Expr::Obj(span(&start_location, &end_location), props))
     }
 
@@ -1589,7 +4017,7 @@ impl Parser {
         Ok(match self.peek()?.value {
             TokenData::Colon => {
                 self.skip()?;
-                let val = self.allow_in(true, |this| this.assignment_expression())?;
+                let val = self.allow_in(true, |this| this.cover_assignment_expression(true))?;
                 Prop::Regular(
                     span(key.tracking_ref(), val.tracking_ref()),
                     key,
@@ -1597,22 +4025,14 @@ impl Parser {
                 )
             }
             TokenData::LParen => {
-                let params = self.formal_parameters()?;
-                let body = self.function_body(&params.list)?;
-                Prop::Method(
-                    key,
-                    Fun {
-                        location: span(&None, body.tracking_ref()),
-                        kind: FunctionKind::Anonymous,
-                        params: params,
-                        body: body,
-                        body_expr: None,
-                    },
-                )
+                let fun = self.with_super(true, false, |this| {
+                    this.method_function(FunctionKind::Anonymous, false)
+                })?;
+                Prop::Method(key, fun)
             }
             TokenData::Comma | TokenData::RBrace => {
                 if let PropKey::Id(location, name) = key {
-                    Prop::Shorthand(self.new_id(false, Name::from(name), location.unwrap())?)
+                    Prop::Shorthand(self.new_id(false, name, location.unwrap())?)
                 } else {
                     return self.unexpected("expected property key to be identifier because it's followed by comma/rbrace");
                 }
@@ -1626,11 +4046,16 @@ impl Parser {
     fn property_key_opt(&mut self) -> Result<Option<PropKey>> {
         let token = self.read()?;
         let location = Some(token.location);
+        self.literal_check(&token)?;
         Ok(Some(match token.value {
-            TokenData::Identifier(name) => PropKey::Id(location, name.into_string()),
-            TokenData::Reserved(word) => PropKey::Id(location, word.into_string()),
-            TokenData::String(s) => PropKey::String(location, s),
-            TokenData::Number(n) => PropKey::Number(location, n),
+            TokenData::Identifier(name) => PropKey::Id(location, name.into_owned()),
+            TokenData::Reserved(word) => PropKey::Id(location, Name::from(word.into_string())),
+            TokenData::String(s) => PropKey::String(location, s.into_owned()),
+            TokenData::Number(n) => PropKey::Number(location, n.into_owned()),
+            TokenData::PrivateName(name) => PropKey::Private(PrivateName {
+                location: location,
+                name: name.into_owned(),
+            }),
             TokenData::LBrack => {
                 let expr = self.expression()?;
                 self.expect(TokenData::RBrack)?;
@@ -1654,11 +4079,24 @@ impl Parser {
     fn object_property(&mut self) -> Result<Prop> {
         let first = self.read()?;
         match first.value {
+            TokenData::Identifier(Name::Atom(Atom::Async)) => {
+                if let Some((key, fun)) = self.with_super(true, false, |this| this.async_method_opt())? {
+                    return Ok(Prop::Method(key, fun));
+                }
+                let key_location = Some(first.location);
+                self.more_prop_init(PropKey::Id(key_location, Name::Atom(Atom::Async)))
+            }
             TokenData::Identifier(Name::Atom(Atom::Get)) => {
                 if let Some(key) = self.property_key_opt()? {
                     let paren_location = Some(self.expect(TokenData::LParen)?.location);
                     self.expect(TokenData::RParen)?;
-                    let body = self.function_body(&vec![])?;
+                    let params = Params {
+                        location: None,
+                        list: vec![],
+                        rest: None,
+                        types: vec![],
+                    };
+                    let body = self.with_super(true, false, |this| this.function_body(&params, true, false, false))?;
                     let val_location = span(&paren_location, &body);
                     let prop_location = span(&key, &body);
                     return Ok(Prop::Regular(
@@ -1668,14 +4106,21 @@ impl Parser {
                     ));
                 }
                 let key_location = Some(first.location);
-                self.more_prop_init(PropKey::Id(key_location, "get".to_string()))
+                self.more_prop_init(PropKey::Id(key_location, Name::Atom(Atom::Get)))
             }
             TokenData::Identifier(Name::Atom(Atom::Set)) => {
                 if let Some(key) = self.property_key_opt()? {
                     let paren_location = Some(self.expect(TokenData::LParen)?.location);
-                    let param = self.pattern()?;
+                    let param = self.in_function(false, false, |this| this.pattern())?;
                     self.expect(TokenData::RParen)?;
-                    let body = self.function_body(&[param.clone()])?;
+                    let params = Params {
+                        location: None,
+                        list: vec![param],
+                        rest: None,
+                        types: vec![],
+                    };
+                    let body = self.with_super(true, false, |this| this.function_body(&params, true, false, false))?;
+                    let param = params.list.into_iter().next().unwrap();
                     let val_location = span(&paren_location, &body);
                     let prop_location = span(&key, &body);
                     return Ok(Prop::Regular(
@@ -1685,26 +4130,22 @@ impl Parser {
                     ));
                 }
                 let key_location = Some(first.location);
-                self.more_prop_init(PropKey::Id(key_location, "set".to_string()))
+                self.more_prop_init(PropKey::Id(key_location, Name::Atom(Atom::Set)))
             }
             TokenData::Star => {
                 let key = self.property_key()?;
-                let params = self.formal_parameters()?;
-                let body = self.function_body(&params.list)?;
-                Ok(Prop::Method(
-                    key,
-                    Fun {
-                        location: span(&None, body.tracking_ref()),
-                        kind: FunctionKind::AnonymousGenerator,
-                        params: params,
-                        body: body,
-                        body_expr: None,
-                    },
-                ))
+                let fun = self.with_super(true, false, |this| {
+                    this.method_function(FunctionKind::AnonymousGenerator, false)
+                })?;
+                Ok(Prop::Method(key, fun))
+            }
+            TokenData::Ellipsis => {
+                let val = self.allow_in(true, |this| this.assignment_expression())?;
+                Ok(Prop::Spread(span(&Some(first.location), val.tracking_ref()), val, false))
             }
             TokenData::Reserved(_) => match self.peek()?.value {
                 TokenData::Comma | TokenData::RBrace => {
-                    return Err(Error::UnexpectedToken(first, "unexpected reserved token used as shorthand property key"));
+                    return Err(Error::UnexpectedToken(first.into_owned(), "unexpected reserved token used as shorthand property key"));
                 }
                 _ => {
                     self.lexer.unread_token(first);
@@ -1722,8 +4163,19 @@ impl Parser {
 
     // MemberBaseExpression ::=
     //   PrimaryExpression
+    //   SuperProperty
     //   "new" "." "target"
+    //   "import" "." "meta"
     fn member_base_expression(&mut self) -> Result<Expr> {
+        match self.peek()?.value {
+            TokenData::Reserved(Reserved::Super) => {
+                return self.super_expression(false);
+            }
+            TokenData::Reserved(Reserved::Import) => {
+                return self.import_expression(false);
+            }
+            _ => {}
+        }
         if let Some(new) = self.matches_token(TokenData::Reserved(Reserved::New))? {
             self.expect(TokenData::Dot)?;
             let target_location = Some(
@@ -1737,7 +4189,6 @@ impl Parser {
 
     // "new"+n . (MemberBaseExpression | "super" Deref) Deref* Arguments<n Suffix*
     fn new_expression(&mut self, news: Vec<Token>) -> Result<Expr> {
-        // ES6: if let Some(super) = self.match_token(TokenData::Reserved(Reserved::Super))? {
         let base = self.member_base_expression()?;
         self.more_new_expression(news, base)
     }
@@ -1747,6 +4198,13 @@ impl Parser {
         while let Some(deref) = self.deref_opt()? {
             base = deref.append_to(base);
         }
+        if let Some((types, end)) = self.expr_type_arguments_opt()? {
+            base = Suffix::TypeArgs(types, end).append_to(base);
+        }
+        let token = self.peek_op()?;
+        if token.value == TokenData::QuestionDot {
+            return Err(Error::IllegalOptionalChain(token.location));
+        }
         let mut has_args = true;
         for new in news.into_iter().rev() {
             has_args = has_args && self.peek_op()?.value == TokenData::LParen;
@@ -1763,31 +4221,106 @@ impl Parser {
     // CallExpression ::=
     //   (MemberBaseExpression | "super" Suffix) Suffix*
     fn call_expression(&mut self) -> Result<Expr> {
-        // ES6: super
-        let base = self.primary_expression()?;
+        let base = match self.peek()?.value {
+            TokenData::Reserved(Reserved::Super) => self.super_expression(true)?,
+            TokenData::Reserved(Reserved::Import) => self.import_expression(true)?,
+            _ => self.primary_expression()?,
+        };
         self.more_suffixes(base)
     }
 
+    // ImportCall ::= "import" "(" AssignmentExpression ")"
+    // ImportMeta ::= "import" "." "meta"
+    fn import_expression(&mut self, allow_call: bool) -> Result<Expr> {
+        let token = self.reread(TokenData::Reserved(Reserved::Import));
+        let location = Some(token.location);
+        match self.peek_op()?.value {
+            TokenData::Dot => {
+                self.reread(TokenData::Dot);
+                let meta = self.expect(TokenData::Identifier(Name::Atom(Atom::Meta)))?;
+                self.module_check(Error::ImportMetaOutsideModule(token.location))?;
+                Ok(Expr::ImportMeta(span(&location, &Some(meta.location))))
+            }
+            TokenData::LParen if allow_call => {
+                self.reread(TokenData::LParen);
+                let specifier = self.allow_in(true, |this| this.assignment_expression())?;
+                let end = self.expect(TokenData::RParen)?;
+                Ok(Expr::Import(span(&location, &Some(end.location)), Box::new(specifier)))
+            }
+            _ => Err(Error::UnexpectedToken(token.into_owned(), "expected import call or import.meta")),
+        }
+    }
+
+    // SuperProperty ::=
+    //   "super" "[" Expression "]"
+    //   "super" "." IdentifierName
+    // SuperCall ::= "super" Arguments
+    fn super_expression(&mut self, allow_call: bool) -> Result<Expr> {
+        let token = self.reread(TokenData::Reserved(Reserved::Super));
+        let location = Some(token.location);
+        match self.peek_op()?.value {
+            TokenData::Dot | TokenData::LBrack => {
+                if !self.context.allow_super_property {
+                    return Err(Error::IllegalSuperProperty(token.location));
+                }
+                Ok(match self.deref_opt()? {
+                    Some(Deref::Dot(key)) => Expr::SuperDot(span(&location, &key), key),
+                    Some(Deref::PrivateDot(key)) => {
+                        return Err(Error::IllegalPrivateName(key.location));
+                    }
+                    Some(Deref::Brack(prop, end)) => {
+                        Expr::SuperBrack(span(&location, &Some(end.location)), Box::new(prop))
+                    }
+                    _ => unreachable!(),
+                })
+            }
+            TokenData::LParen if allow_call => {
+                if !self.context.allow_super_call {
+                    return Err(Error::IllegalSuperCall(token.location));
+                }
+                let args = self.arguments()?;
+                Ok(Expr::SuperCall(span(&location, &Some(args.end.location)), args.args))
+            }
+            _ => Err(Error::UnexpectedToken(token.into_owned(), "expected super property or call")),
+        }
+    }
+
+    fn non_null_follows(&mut self) -> Result<bool> {
+        let next = self.peek_op()?;
+        Ok(next.value == TokenData::Bang && !next.newline)
+    }
+
     // Suffix ::=
     //   Deref
     //   Arguments
-    fn suffix_opt(&mut self) -> Result<Option<Suffix>> {
+    //   "!"
+    //   TypeArguments
+    fn suffix_opt(&mut self) -> Result<Option<Suffix<'a>>> {
+        if let Some((types, end)) = self.expr_type_arguments_opt()? {
+            return Ok(Some(Suffix::TypeArgs(types, end)));
+        }
+        if self.types && self.non_null_follows()? {
+            return Ok(Some(Suffix::NonNull(self.reread(TokenData::Bang))));
+        }
         match self.peek_op()?.value {
             TokenData::Dot => self.deref_dot().map(|deref| Some(Suffix::Deref(deref))),
             TokenData::LBrack => self.deref_brack().map(|deref| Some(Suffix::Deref(deref))),
+            TokenData::Template(_) | TokenData::TemplateHead(_) => {
+                self.deref_template().map(|deref| Some(Suffix::Deref(deref)))
+            }
             TokenData::LParen => self.arguments().map(|args| Some(Suffix::Arguments(args))),
             _ => Ok(None),
         }
     }
 
     // Arguments ::= "(" Argument*[","] ")"
-    fn arguments(&mut self) -> Result<Arguments> {
+    fn arguments(&mut self) -> Result<Arguments<'a>> {
         self.allow_in(true, |this| {
             this.expect(TokenData::LParen)?;
             let mut args = Vec::new();
             if this.peek()?.value != TokenData::RParen {
                 loop {
-                    args.push(this.expr_list_item()?);
+                    args.push(this.expr_list_item(false)?);
                     if !this.matches(TokenData::Comma)? {
                         break;
                     }
@@ -1804,15 +4337,60 @@ impl Parser {
     // Deref ::=
     //   "[" Expression "]"
     //   "." IdentifierName
-    fn deref_opt(&mut self) -> Result<Option<Deref>> {
+    //   TemplateLiteral
+    fn deref_opt(&mut self) -> Result<Option<Deref<'a>>> {
         match self.peek_op()?.value {
             TokenData::LBrack => self.deref_brack().map(Some),
             TokenData::Dot => self.deref_dot().map(Some),
+            TokenData::Template(_) | TokenData::TemplateHead(_) => self.deref_template().map(Some),
             _ => Ok(None),
         }
     }
 
-    fn deref_brack(&mut self) -> Result<Deref> {
+    fn deref_template(&mut self) -> Result<Deref<'a>> {
+        Ok(Deref::Template(self.template_literal(true)?))
+    }
+
+    // TemplateLiteral ::=
+    //   NoSubstitutionTemplate
+    //   TemplateHead Expression (TemplateMiddle Expression)* TemplateTail
+    fn template_literal(&mut self, tagged: bool) -> Result<Template> {
+        self.span(&mut |this| {
+            let mut quasis = Vec::new();
+            let mut exprs = Vec::new();
+            let mut token = this.read()?;
+            loop {
+                let (part, done) = match token.value {
+                    TokenData::Template(part) | TokenData::TemplateTail(part) => (part, true),
+                    TokenData::TemplateHead(part) | TokenData::TemplateMiddle(part) => (part, false),
+                    _ => unreachable!(),
+                };
+                if !tagged && part.cooked.is_none() {
+                    return Err(Error::IllegalTemplateEscape(token.location));
+                }
+                quasis.push(TemplateElement {
+                    location: Some(token.location),
+                    cooked: part.cooked.map(|cooked| cooked.into_owned()),
+                    raw: part.raw.into_owned(),
+                });
+                if done {
+                    break;
+                }
+                exprs.push(this.allow_in(true, |this| this.expression())?);
+                if this.peek()?.value != TokenData::RBrace {
+                    return this.unexpected("expected '}' to close template substitution");
+                }
+                token = this.lexer.read_template_continuation().map_err(Error::LexError)?;
+            }
+            Ok(Template {
+                location: None,
+                quasis: quasis,
+                exprs: exprs,
+            })
+        })
+    }
+
+    fn deref_brack(&mut self) -> Result<Deref<'a>> {
         self.reread(TokenData::LBrack);
         let expr = self.allow_in(true, |this| this.expression())?;
         let end = self.expect(TokenData::RBrack)?;
@@ -1824,28 +4402,85 @@ impl Parser {
         Ok(DotKey {
             location: Some(token.location),
             value: match token.value {
-                TokenData::Identifier(name) => name.into_string(),
-                TokenData::Reserved(word) => word.into_string(),
+                TokenData::Identifier(name) => name.into_owned(),
+                TokenData::Reserved(word) => Name::from(word.into_string()),
                 _ => {
-                    return Err(Error::UnexpectedToken(token, "token is neither identifier nor reserved"));
+                    return Err(Error::UnexpectedToken(token.into_owned(), "token is neither identifier nor reserved"));
                 }
             },
         })
     }
 
-    fn deref_dot(&mut self) -> Result<Deref> {
+    fn deref_dot(&mut self) -> Result<Deref<'a>> {
         self.reread(TokenData::Dot);
+        if let TokenData::PrivateName(_) = self.peek()?.value {
+            return Ok(Deref::PrivateDot(self.private_name()?));
+        }
         Ok(Deref::Dot(self.id_name()?))
     }
 
-    // MemberBaseExpression . Suffix*
+    // MemberBaseExpression . (Suffix | OptionalLink)*
     fn more_suffixes(&mut self, mut result: Expr) -> Result<Expr> {
-        while let Some(suffix) = self.suffix_opt()? {
-            result = suffix.append_to(result);
+        let mut chain = false;
+        loop {
+            let token = self.peek_op()?;
+            match token.value {
+                TokenData::QuestionDot => {
+                    result = self.optional_link(result)?;
+                    chain = true;
+                    continue;
+                }
+                TokenData::Template(_) | TokenData::TemplateHead(_) if chain => {
+                    return Err(Error::IllegalOptionalChain(token.location));
+                }
+                _ => {}
+            }
+            match self.suffix_opt()? {
+                Some(suffix) => { result = suffix.append_to(result); }
+                None => { break; }
+            }
+        }
+        if chain {
+            let location = *result.tracking_ref();
+            result = Expr::Chain(location, Box::new(result));
         }
         Ok(result)
     }
 
+    // OptionalLink ::=
+    //   "?." Arguments
+    //   "?." "[" Expression "]"
+    //   "?." IdentifierName
+    //   "?." PrivateName
+    fn optional_link(&mut self, base: Expr) -> Result<Expr> {
+        let token = self.reread(TokenData::QuestionDot);
+        match self.peek()?.value {
+            TokenData::LParen => {
+                let args = self.arguments()?;
+                let location = span(&base, &Some(args.end.location));
+                Ok(Expr::OptionalCall(location, Box::new(base), args.args))
+            }
+            TokenData::LBrack => {
+                self.reread(TokenData::LBrack);
+                let prop = self.allow_in(true, |this| this.expression())?;
+                let end = self.expect(TokenData::RBrack)?;
+                let location = span(&base, &Some(end.location));
+                Ok(Expr::OptionalBrack(location, Box::new(base), Box::new(prop)))
+            }
+            TokenData::Template(_) | TokenData::TemplateHead(_) => {
+                Err(Error::IllegalOptionalChain(token.location))
+            }
+            TokenData::PrivateName(_) => {
+                let key = self.private_name()?;
+                Ok(Expr::OptionalPrivateDot(span(&base, &key), Box::new(base), key))
+            }
+            _ => {
+                let key = self.id_name()?;
+                Ok(Expr::OptionalDot(span(&base, &key), Box::new(base), key))
+            }
+        }
+    }
+
     // LHSExpression ::=
     //   NewExpression
     //   CallExpression
@@ -1899,16 +4534,23 @@ impl Parser {
         for prefix in prefixes.into_iter().rev() {
             match prefix {
                 Prefix::Unop(op) => {
+                    if op.tag == UnopTag::Delete && arg.is_private_reference() {
+                        return Err(Error::IllegalPrivateName(*arg.tracking_ref()));
+                    }
                     let location = span(&op, &arg);
                     arg = Expr::Unop(location, op, Box::new(arg));
                 }
+                Prefix::Await(location) => {
+                    let location = span(&Some(location), &arg);
+                    arg = Expr::Await(location, Box::new(arg));
+                }
                 _ => {
                     let arg_location = *arg.tracking_ref();
                     arg = match arg.into_assignable().map(Box::new) {
                         Ok(target) => match prefix {
                             Prefix::Inc(location) => Expr::PreInc(Some(location), target),
                             Prefix::Dec(location) => Expr::PreDec(Some(location), target),
-                            Prefix::Unop(_) => unreachable!(),
+                            Prefix::Unop(_) | Prefix::Await(_) => unreachable!(),
                         },
                         Err(cover_err) => {
                             return Err(Error::InvalidLHS(arg_location, cover_err));
@@ -1929,6 +4571,12 @@ impl Parser {
         Ok(match token.value {
             TokenData::Inc => Some(Prefix::Inc(token.location)),
             TokenData::Dec => Some(Prefix::Dec(token.location)),
+            TokenData::Identifier(Name::Atom(Atom::Await)) if self.context.is_async => {
+                if self.context.parameters {
+                    self.early_error(Error::AwaitInParameters(token.location))?;
+                }
+                Some(Prefix::Await(token.location))
+            }
             _ => {
                 self.lexer.unread_token(token);
                 self.match_unop()?.map(Prefix::Unop)
@@ -1988,14 +4636,39 @@ impl Parser {
     // ConditionalExpression ::=
     //   UnaryExpression (Infix UnaryExpression)* ("?" AssignmentExpression ":" AssignmentExpression)?
     fn conditional_expression(&mut self) -> Result<Expr> {
-        let left = self.unary_expression()?;
-        let test = self.more_infix_expressions(left)?;
+        let prefix = self.unary_prefix_opt()?;
+        let left = self.infix_operand()?;
+        let test = self.more_infix_expressions(left, prefix, 0)?;
         self.more_conditional(test)
     }
 
+    fn infix_operand(&mut self) -> Result<Expr> {
+        match self.peek()?.value {
+            TokenData::PrivateName(_) => self.private_in_expression(),
+            _ => self.unary_expression(),
+        }
+    }
+
+    // PrivateInExpression ::= PrivateName "in" ShiftExpression
+    fn private_in_expression(&mut self) -> Result<Expr> {
+        let key = self.private_name()?;
+        let token = self.read_op()?;
+        if token.value != TokenData::Reserved(Reserved::In) || !self.context.allow_in {
+            return Err(Error::UnexpectedToken(token.into_owned(), "expected 'in' after private name"));
+        }
+        let prefix = self.unary_prefix_opt()?;
+        let left = self.unary_expression()?;
+        let right = self.more_infix_expressions(left, prefix, BinopTag::In.precedence())?;
+        Ok(Expr::PrivateIn(span(&key, &right), key, Box::new(right)))
+    }
+
     fn more_conditional(&mut self, left: Expr) -> Result<Expr> {
         if self.matches_op(TokenData::Question)? {
-            let cons = self.allow_in(true, |this| this.assignment_expression())?;
+            let cons = if self.types {
+                self.consequent()?
+            } else {
+                self.allow_in(true, |this| this.assignment_expression())?
+            };
             self.expect(TokenData::Colon)?;
             let alt = self.assignment_expression()?;
             let location = span(&cons, &alt);
@@ -2009,14 +4682,96 @@ impl Parser {
         Ok(left)
     }
 
+    // With type annotations, `a ? (b) : c => d` could have an arrow function
+    // with a return type as its consequent. That's only taken when a ":"
+    // follows it; otherwise the ":" belongs to the conditional.
+    fn consequent(&mut self) -> Result<Expr> {
+        let cons = self.speculate(|this| {
+            let cons = this.allow_in(true, |this| this.assignment_expression())?;
+            if this.peek_op()?.value != TokenData::Colon {
+                return this.unexpected("expected ':' in conditional expression");
+            }
+            Ok(cons)
+        });
+        if let Some(cons) = cons {
+            return Ok(cons);
+        }
+        let allow = replace(&mut self.context.allow_arrow_return_type, false);
+        let cons = self.allow_in(true, |this| this.assignment_expression());
+        self.context.allow_arrow_return_type = allow;
+        cons
+    }
+
     // AssignmentExpression ::=
     //   YieldPrefix* "yield"
     //   YieldPrefix* ConditionalExpression (("=" | AssignmentOperator) AssignmentExpression)?
     fn assignment_expression(&mut self) -> Result<Expr> {
+        self.cover_assignment_expression(false)
+    }
+
+    // The errors found in an array or object literal are held back until it
+    // is known not to be a pattern: that is, until it isn't the target of an
+    // assignment or an element of a literal that may itself be a pattern.
+    fn cover_assignment_expression(&mut self, element: bool) -> Result<Expr> {
+        if self.context.generator && self.peek()?.value == TokenData::Identifier(Name::Atom(Atom::Yield)) {
+            if self.context.parameters {
+                let location = self.peek()?.location;
+                self.early_error(Error::YieldInParameters(location))?;
+            }
+            return self.yield_expression();
+        }
+        let pending = self.cover_errors.len();
         let left = self.conditional_expression()?;
+        if self.cover_errors.len() > pending {
+            match left {
+                Expr::Obj(_, _) | Expr::Arr(_, _) if self.peek_op()?.value == TokenData::Assign => {
+                    self.cover_errors.truncate(pending);
+                }
+                Expr::Obj(_, _) | Expr::Arr(_, _) if element => {}
+                _ => {
+                    let error = self.cover_errors.swap_remove(pending);
+                    self.cover_errors.truncate(pending);
+                    if self.validate {
+                        self.early_error(error)?;
+                    }
+                }
+            }
+        }
         self.more_assignment(left)
     }
 
+    // YieldExpression ::=
+    //   "yield"
+    //   "yield" [no LineTerminator here] AssignmentExpression
+    //   "yield" [no LineTerminator here] "*" AssignmentExpression
+    fn yield_expression(&mut self) -> Result<Expr> {
+        let token = self.reread(TokenData::Identifier(Name::Atom(Atom::Yield)));
+        let location = Some(token.location);
+        let next = self.peek()?;
+        if next.newline {
+            return Ok(Expr::Yield(location, None));
+        }
+        match next.value {
+            TokenData::Star => {
+                self.reread(TokenData::Star);
+                let arg = self.assignment_expression()?;
+                Ok(Expr::YieldDelegate(span(&location, &arg), Box::new(arg)))
+            }
+            TokenData::RParen
+          | TokenData::RBrack
+          | TokenData::RBrace
+          | TokenData::Comma
+          | TokenData::Semi
+          | TokenData::Colon
+          | TokenData::Reserved(Reserved::In)
+          | TokenData::EOF => Ok(Expr::Yield(location, None)),
+            _ => {
+                let arg = self.assignment_expression()?;
+                Ok(Expr::Yield(span(&location, &arg), Some(Box::new(arg))))
+            }
+        }
+    }
+
     fn more_assignment(&mut self, left: Expr) -> Result<Expr> {
         let token = self.read_op()?;
         let left_location = *left.tracking_ref();
@@ -2027,6 +4782,20 @@ impl Parser {
             //         return Err(Error::InvalidLHS(left_location, cover_err));
             //     }
             // };
+            match left {
+                // Optional chains and meta properties are never references.
+                Expr::Chain(_, _) | Expr::ImportMeta(_) | Expr::NewTarget(_) => {
+                    return Err(Error::InvalidLHS(left_location, cover::Error::InvalidAssignTarget(left_location)));
+                }
+                // Destructuring targets stay expressions in the tree, but
+                // must be valid as patterns.
+                Expr::Obj(_, _) | Expr::Arr(_, _) => {
+                    if let Err(cover_err) = left.check_simple_or_compound_pattern() {
+                        return Err(Error::InvalidLHS(left_location, cover_err));
+                    }
+                }
+                _ => {}
+            }
             let right = self.assignment_expression()?;
             let location = span(&left, &right);
             return Ok(Expr::Assign(location, Box::new(Assign::Expr(left)), Box::new(right)));
@@ -2050,23 +4819,81 @@ impl Parser {
         Ok(left)
     }
 
-    fn more_infix_expressions(&mut self, left: Expr) -> Result<Expr> {
+    // The prefix is the location of the unary operator that begins the left
+    // operand, if any, since "-a ** b" is ambiguous and must be parenthesized.
+    // Operators that bind no tighter than min_precedence end the expression.
+    fn more_infix_expressions(&mut self, left: Expr, mut prefix: Option<Span>, min_precedence: u32) -> Result<Expr> {
         let mut stack = Stack::new();
         let mut operand = left;
-        while let Some(op) = self.match_infix()? {
-            stack.extend(operand, op);
+        loop {
+            // "as" binds like a relational operator, but its right side is a
+            // type, or "const", rather than an operand.
+            if self.types && BinopTag::Lt.precedence() > min_precedence && self.as_follows()? {
+                operand = stack.reduce(operand, BinopTag::Lt.precedence());
+                self.reread(TokenData::Identifier(Name::Atom(Atom::As)));
+                let ty = match self.peek()?.value {
+                    TokenData::Reserved(Reserved::Const) => Type::Const(Some(self.read()?.location)),
+                    _ => self.ty()?,
+                };
+                operand = Expr::As(span(&operand, &ty), Box::new(operand), ty);
+                prefix = None;
+                continue;
+            }
+            let op = match self.match_infix(min_precedence)? {
+                Some(op) => op,
+                None => { break; }
+            };
+            if let Some(location) = prefix {
+                if op.is_exponent() {
+                    return Err(Error::UnaryBeforeExponent(location));
+                }
+            }
+            // "#x in obj" is a relational expression, so it may only follow
+            // operators that bind more loosely.
+            let relational = op.precedence() >= BinopTag::In.precedence();
+            stack.extend(operand, op)?;
             //println!("{}\n", stack);
-            operand = self.unary_expression()?;
+            prefix = self.unary_prefix_opt()?;
+            if relational {
+                if let TokenData::PrivateName(_) = self.peek()?.value {
+                    let token = self.read()?;
+                    return Err(Error::UnexpectedToken(token.into_owned(), "unexpected private name"));
+                }
+            }
+            operand = self.infix_operand()?;
         }
         Ok(stack.finish(operand))
     }
 
-    fn match_infix(&mut self) -> Result<Option<Infix>> {
+    fn as_follows(&mut self) -> Result<bool> {
+        let next = self.peek_op()?;
+        Ok(next.value == TokenData::Identifier(Name::Atom(Atom::As)) && !next.newline)
+    }
+
+    // The location of the operator starting a UnaryExpression that is not
+    // an UpdateExpression.
+    fn unary_prefix_opt(&mut self) -> Result<Option<Span>> {
+        let is_async = self.context.is_async;
+        let token = self.peek()?;
+        Ok(match token.value {
+            TokenData::Reserved(Reserved::Delete)
+          | TokenData::Reserved(Reserved::Void)
+          | TokenData::Reserved(Reserved::Typeof)
+          | TokenData::Plus
+          | TokenData::Minus
+          | TokenData::Tilde
+          | TokenData::Bang => Some(token.location),
+            TokenData::Identifier(Name::Atom(Atom::Await)) if is_async => Some(token.location),
+            _ => None,
+        })
+    }
+
+    fn match_infix(&mut self, min_precedence: u32) -> Result<Option<Infix>> {
         let token = self.read_op()?;
         let result = token.to_binop(self.context.allow_in).map_or_else(
             || token.to_logop().map(Infix::Logop),
             |op| Some(Infix::Binop(op)),
-        );
+        ).filter(|op| op.precedence() > min_precedence);
         if result.is_none() {
             self.lexer.unread_token(token);
         }
@@ -3,7 +3,10 @@ use std::fmt::{Display, Formatter};
 use std::{cmp, usize};
 use joker::track::span;
 use easter::expr::Expr;
-use easter::punc::{Binop, Logop, Precedence};
+use easter::punc::{Binop, BinopTag, Logop, LogopTag, Precedence};
+
+use error::Error;
+use result::Result;
 
 #[derive(Debug)]
 pub enum Infix {
@@ -12,7 +15,18 @@ pub enum Infix {
 }
 
 impl Infix {
+    pub fn is_exponent(&self) -> bool {
+        match *self {
+            Infix::Binop(ref op) => op.tag == BinopTag::Exp,
+            Infix::Logop(_) => false
+        }
+    }
+
+    // Exponentiation is the only right-associative infix operator.
     fn groups_left(&self, right: &Infix) -> bool {
+        if self.is_exponent() && right.is_exponent() {
+            return false;
+        }
         self.precedence() >= right.precedence()
     }
 }
@@ -114,22 +128,47 @@ impl<'a> Display for FrameExpr<'a> {
     }
 }
 
+// Since parenthesized operands never reach the stack, a single stack holds
+// exactly one unparenthesized operator chain, which may not mix `??` with
+// `||` or `&&`.
 #[derive(Debug)]
 pub struct Stack {
-    frames: Vec<Frame>
+    frames: Vec<Frame>,
+    coalesce: bool,
+    logical: bool
 }
 
 impl Stack {
     pub fn new() -> Stack {
-        Stack { frames: Vec::new() }
+        Stack { frames: Vec::new(), coalesce: false, logical: false }
     }
 
-    pub fn extend(&mut self, mut left: Expr, op: Infix) {
+    pub fn extend(&mut self, mut left: Expr, op: Infix) -> Result<()> {
+        if let Infix::Logop(ref op) = op {
+            match op.tag {
+                LogopTag::Coalesce => { self.coalesce = true; }
+                LogopTag::Or | LogopTag::And => { self.logical = true; }
+            }
+            if self.coalesce && self.logical {
+                return Err(Error::MixedCoalesce(op.location));
+            }
+        }
         let mut len;
         while { len = self.frames.len(); len > 0 } && self.frames[len - 1].op.groups_left(&op) {
             left = self.frames.pop().unwrap().fill(left);
         }
         self.frames.push(Frame { left: left, op: op });
+        Ok(())
+    }
+
+    // Fills in the frames whose operators bind at least as tightly as the
+    // given precedence, leaving the rest of the stack to be extended.
+    pub fn reduce(&mut self, mut right: Expr, precedence: u32) -> Expr {
+        let mut len;
+        while { len = self.frames.len(); len > 0 } && self.frames[len - 1].precedence() >= precedence {
+            right = self.frames.pop().unwrap().fill(right);
+        }
+        right
     }
 
     pub fn finish(mut self, mut right: Expr) -> Expr {
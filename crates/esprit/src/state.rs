@@ -3,52 +3,52 @@ use result::Result;
 use error::Error;
 use parser::Parser;
 
-pub trait State {
+pub trait State<'a> {
     fn skip(&mut self) -> Result<()>;
-    fn read(&mut self) -> Result<Token>;
-    fn read_op(&mut self) -> Result<Token>;
-    fn peek(&mut self) -> Result<&Token>;
-    fn peek_op(&mut self) -> Result<&Token>;
-    fn expect(&mut self, expected: TokenData) -> Result<Token>;
-    fn matches_token(&mut self, expected: TokenData) -> Result<Option<Token>>;
+    fn read(&mut self) -> Result<Token<'a>>;
+    fn read_op(&mut self) -> Result<Token<'a>>;
+    fn peek(&mut self) -> Result<&Token<'a>>;
+    fn peek_op(&mut self) -> Result<&Token<'a>>;
+    fn expect(&mut self, expected: TokenData) -> Result<Token<'a>>;
+    fn matches_token(&mut self, expected: TokenData) -> Result<Option<Token<'a>>>;
     fn matches(&mut self, expected: TokenData) -> Result<bool>;
     fn matches_op(&mut self, expected: TokenData) -> Result<bool>;
-    fn reread(&mut self, expected: TokenData) -> Token;
+    fn reread(&mut self, expected: TokenData) -> Token<'a>;
     fn has_arg_same_line(&mut self) -> Result<bool>;
 }
 
-impl State for Parser {
+impl<'a> State<'a> for Parser<'a> {
     fn skip(&mut self) -> Result<()> {
         self.lexer.skip_token(false).map_err(Error::LexError)
     }
 
-    fn read(&mut self) -> Result<Token> {
+    fn read(&mut self) -> Result<Token<'a>> {
         self.lexer.read_token(false).map_err(Error::LexError)
     }
 
-    fn read_op(&mut self) -> Result<Token> {
+    fn read_op(&mut self) -> Result<Token<'a>> {
         self.lexer.read_token(true).map_err(Error::LexError)
     }
 
-    fn peek(&mut self) -> Result<&Token> {
+    fn peek(&mut self) -> Result<&Token<'a>> {
         self.lexer.peek_token(false).map_err(Error::LexError)
     }
 
-    fn peek_op(&mut self) -> Result<&Token> {
+    fn peek_op(&mut self) -> Result<&Token<'a>> {
         self.lexer.peek_token(true).map_err(Error::LexError)
     }
 
     // TODO Add more meaningful message here
-    fn expect(&mut self, expected: TokenData) -> Result<Token> {
+    fn expect(&mut self, expected: TokenData) -> Result<Token<'a>> {
         // println!("expect {}", self.lexer.index());
         let token = self.read()?;
         if token.value != expected {
-            return Err(Error::UnexpectedToken(token, "expect call"));
+            return Err(Error::UnexpectedToken(token.into_owned(), "expect call"));
         }
         Ok(token)
     }
 
-    fn matches_token(&mut self, expected: TokenData) -> Result<Option<Token>> {
+    fn matches_token(&mut self, expected: TokenData) -> Result<Option<Token<'a>>> {
         let token = self.read()?;
         if token.value != expected {
             self.lexer.unread_token(token);
@@ -75,7 +75,7 @@ impl State for Parser {
         Ok(true)
     }
 
-    fn reread(&mut self, expected: TokenData) -> Token {
+    fn reread(&mut self, expected: TokenData) -> Token<'a> {
         debug_assert!(self.lexer.repeek_token().value == expected);
         self.lexer.reread_token()
         // debug_assert!(self.peek().map(|actual| actual.value == expected).unwrap_or(false));
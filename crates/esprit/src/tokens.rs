@@ -5,6 +5,7 @@ use context::LabelType;
 pub trait First {
     fn first_binding(&self) -> bool;
     fn pragma(&self) -> Option<&str>;
+    fn starts_statement(&self) -> bool;
 }
 
 pub trait Follows {
@@ -12,7 +13,7 @@ pub trait Follows {
     fn expression_continuation(&self) -> bool;
 }
 
-impl First for Token {
+impl<'a> First for Token<'a> {
     // first(LexicalBinding) =
     //   first(BindingIdentifier)
     // U first(BindingPattern)
@@ -37,9 +38,36 @@ impl First for Token {
             _ => None
         }
     }
+
+    // The reserved words that can't continue an expression and only begin
+    // statements or declarations.
+    fn starts_statement(&self) -> bool {
+        match self.value {
+            TokenData::Reserved(Reserved::Var)
+          | TokenData::Reserved(Reserved::Const)
+          | TokenData::Reserved(Reserved::Function)
+          | TokenData::Reserved(Reserved::Class)
+          | TokenData::Reserved(Reserved::If)
+          | TokenData::Reserved(Reserved::For)
+          | TokenData::Reserved(Reserved::While)
+          | TokenData::Reserved(Reserved::Do)
+          | TokenData::Reserved(Reserved::Return)
+          | TokenData::Reserved(Reserved::Break)
+          | TokenData::Reserved(Reserved::Continue)
+          | TokenData::Reserved(Reserved::Switch)
+          | TokenData::Reserved(Reserved::Throw)
+          | TokenData::Reserved(Reserved::Try)
+          | TokenData::Reserved(Reserved::With)
+          | TokenData::Reserved(Reserved::Debugger)
+          | TokenData::Reserved(Reserved::Import)
+          | TokenData::Reserved(Reserved::Export)
+          | TokenData::At => true,
+            _ => false
+        }
+    }
 }
 
-impl Follows for Token {
+impl<'a> Follows for Token<'a> {
     // follow(StatementList) =
     //   follow(CaseClause)
     // U follow(DefaultClause)
@@ -101,9 +129,11 @@ impl Follows for Token {
           | TokenData::Colon
           | TokenData::Arrow
           | TokenData::Number(_)
+          | TokenData::BigInt(_)
           | TokenData::String(_)
           | TokenData::RegExp(_)
-          | TokenData::Identifier(_) => false,
+          | TokenData::Identifier(_)
+          | TokenData::PrivateName(_) => false,
 
             // 5. All others are continuations.
             _ => true
@@ -115,7 +145,7 @@ pub trait HasLabelType {
     fn label_type(&self) -> LabelType;
 }
 
-impl HasLabelType for Token {
+impl<'a> HasLabelType for Token<'a> {
     fn label_type(&self) -> LabelType {
         match self.value {
             TokenData::Reserved(Reserved::Do)
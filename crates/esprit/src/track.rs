@@ -15,7 +15,7 @@ pub trait Tracking {
             T: TrackingMut;
 }
 
-impl Tracking for Parser {
+impl<'a> Tracking for Parser<'a> {
     fn vec_span<T: TrackingRef>(&self, v: &Vec<T>) -> Option<Span> {
         let len = v.len();
         if len == 0 {
@@ -68,6 +68,11 @@ impl SpanTracker {
         SpanTracker { start: start }
     }
 
+    pub fn end<T: TrackingMut>(&self, parser: &Parser, mut value: T) -> T {
+        *value.tracking_mut() = Some(Span { start: self.start, end: parser.posn() });
+        value
+    }
+
     pub fn end_with_auto_semi<T, F>(&self, parser: &mut Parser, newline: Newline, cons: F)
         -> Result<T>
       where F: FnOnce(Semi) -> T,
@@ -90,7 +95,7 @@ impl SpanTracker {
             &Token { newline: found_newline, .. } => {
                 if newline == Newline::Required && !found_newline {
                     let token = parser.read()?;
-                    return Err(Error::FailedASI(token));
+                    return Err(Error::FailedASI(token.into_owned()));
                 }
                 let mut result = cons(Semi::Inserted);
                 *result.tracking_mut() = Some(Span { start: self.start, end: before });
@@ -0,0 +1,276 @@
+extern crate esprit;
+extern crate easter;
+extern crate joker;
+
+use esprit::script;
+use esprit::error::Error;
+use easter::class::{Class, ClassMember, Field, MethodKind};
+use easter::decl::Decl;
+use easter::expr::Expr;
+use easter::obj::PropKey;
+use easter::stmt::{Stmt, StmtListItem};
+use joker::word::Atom;
+
+fn class_declaration(source: &str) -> Class {
+    match script(source).map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Decl(Decl::Class(class)))) => class,
+        result => panic!("expected a class declaration in {:?}, got {:?}", source, result)
+    }
+}
+
+// The expression statements in the bodies of a class's methods.
+fn method_expressions(class: Class) -> Vec<Expr> {
+    class.body.into_iter().flat_map(|member| match member {
+        ClassMember::Method(method) => method.fun.body.items,
+        member => panic!("expected a method, got {:?}", member)
+    }).map(|item| match item {
+        StmtListItem::Stmt(Stmt::Expr(_, expr, _)) => expr,
+        item => panic!("expected an expression statement, got {:?}", item)
+    }).collect()
+}
+
+// The static flag, kind and name of each method, with `None` for computed names.
+fn methods(class: &Class) -> Vec<(bool, MethodKind, Option<&str>)> {
+    class.body.iter().map(|member| match *member {
+        ClassMember::Method(ref method) => (method.is_static, method.kind, method.key.name()),
+        ref member => panic!("expected a method, got {:?}", member)
+    }).collect()
+}
+
+#[test]
+fn class_declarations() {
+    let class = class_declaration("class A extends B { constructor() {} static m() {} get x() {} set x(v) {} [k]() {} static constructor() {} }");
+    assert_eq!(class.id.as_ref().map(|id| id.name.as_ref()), Some("A"));
+    match class.extends {
+        Some(ref expr) => match **expr {
+            Expr::Id(ref id) => assert_eq!(id.name.as_ref(), "B"),
+            ref expr => panic!("expected a superclass name, got {:?}", expr)
+        },
+        None => panic!("expected a superclass")
+    }
+    assert_eq!(methods(&class), vec![
+        (false, MethodKind::Constructor, Some("constructor")),
+        (true, MethodKind::Method, Some("m")),
+        (false, MethodKind::Get, Some("x")),
+        (false, MethodKind::Set, Some("x")),
+        (false, MethodKind::Method, None),
+        (true, MethodKind::Method, Some("constructor"))
+    ]);
+    assert!(class.location.is_some());
+}
+
+#[test]
+fn class_expressions() {
+    match script("(class extends f() {});").map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(Stmt::Expr(_, Expr::Class(ref class), _)))) => {
+            assert!(class.id.is_none());
+            assert!(class.extends.is_some());
+            assert!(class.body.is_empty());
+        }
+        result => panic!("expected a class expression, got {:?}", result)
+    }
+    match script("x = class C { 'constructor'() {} };").map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(Stmt::Expr(_, Expr::Assign(_, _, ref value), _)))) => match **value {
+            Expr::Class(ref class) => {
+                assert_eq!(class.id.as_ref().map(|id| id.name.as_ref()), Some("C"));
+                assert_eq!(methods(class), vec![(false, MethodKind::Constructor, Some("constructor"))]);
+            }
+            ref expr => panic!("expected a class expression, got {:?}", expr)
+        },
+        result => panic!("expected an assignment, got {:?}", result)
+    }
+}
+
+#[test]
+fn constructor_errors() {
+    match script("class A { constructor() {} constructor() {} }") {
+        Err(Error::DuplicateConstructor(_)) => {}
+        result => panic!("expected a duplicate constructor error, got {:?}", result)
+    }
+    for source in &["class A { get constructor() {} }", "class A { *constructor() {} }", "class A { async constructor() {} }"] {
+        match script(source) {
+            Err(Error::IllegalConstructor(_)) => {}
+            result => panic!("expected an illegal constructor error in {:?}, got {:?}", source, result)
+        }
+    }
+    match script("class A { static prototype() {} }") {
+        Err(Error::StaticPrototype(_)) => {}
+        result => panic!("expected a static prototype error, got {:?}", result)
+    }
+    assert!(script("class A { prototype() {} static constructor() {} }").is_ok());
+}
+
+#[test]
+fn super_expressions() {
+    let exprs = method_expressions(class_declaration("class A extends B { constructor() { super(a, ...b); } m() { super.x; super[y]; super.f(); } }"));
+    match exprs[0] {
+        Expr::SuperCall(_, ref args) => assert_eq!(args.len(), 2),
+        ref expr => panic!("expected a super call, got {:?}", expr)
+    }
+    match exprs[1] {
+        Expr::SuperDot(_, ref key) => assert_eq!(key.value.as_ref(), "x"),
+        ref expr => panic!("expected a super property, got {:?}", expr)
+    }
+    match exprs[2] {
+        Expr::SuperBrack(_, ref prop) => match **prop {
+            Expr::Id(ref id) => assert_eq!(id.name.as_ref(), "y"),
+            ref expr => panic!("expected a name, got {:?}", expr)
+        },
+        ref expr => panic!("expected a computed super property, got {:?}", expr)
+    }
+    match exprs[3] {
+        Expr::Call(_, ref callee, _) => match **callee {
+            Expr::SuperDot(_, ref key) => assert_eq!(key.value.as_ref(), "f"),
+            ref expr => panic!("expected a super property, got {:?}", expr)
+        },
+        ref expr => panic!("expected a call, got {:?}", expr)
+    }
+    assert!(script("({ m() { super.x; }, get y() { return super.y; } })").is_ok());
+    assert!(script("class A { x = super.y; static { super.z; } }").is_ok());
+    assert!(script("class A extends B { constructor() { (() => super())(); } }").is_ok());
+}
+
+#[test]
+fn super_outside_methods() {
+    for source in &["super.x", "function f() { super.x }", "x = { f: function () { super.x } }"] {
+        match script(source) {
+            Err(Error::IllegalSuperProperty(_)) => {}
+            result => panic!("expected an illegal super property in {:?}, got {:?}", source, result)
+        }
+    }
+    for source in &["super()", "class A { constructor() { super(); } }", "class A extends B { m() { super(); } }", "x = { m() { super(); } }"] {
+        match script(source) {
+            Err(Error::IllegalSuperCall(_)) => {}
+            result => panic!("expected an illegal super call in {:?}, got {:?}", source, result)
+        }
+    }
+}
+
+fn is_contextual_await<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::ContextualKeyword(_, Atom::Await)) => true,
+        _ => false
+    }
+}
+
+fn is_arguments_in_initializer<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::ArgumentsInClassInitializer(_)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn class_fields() {
+    let class = class_declaration("class A { x; y = 1; static z = 2; 'w'; [k] = 3; #p = 4; static #q; }");
+    let fields: Vec<(bool, Option<&str>, bool)> = class.body.iter().map(|member| match *member {
+        ClassMember::Field(ref field) => (field.is_static, field.key.name(), field.value.is_some()),
+        ref member => panic!("expected a field, got {:?}", member)
+    }).collect();
+    assert_eq!(fields, vec![
+        (false, Some("x"), false),
+        (false, Some("y"), true),
+        (true, Some("z"), true),
+        (false, Some("w"), false),
+        (false, None, true),
+        (false, None, true),
+        (true, None, false)
+    ]);
+    match class.body[5] {
+        ClassMember::Field(Field { key: PropKey::Private(ref name), .. }) => assert_eq!(name.name, "p"),
+        ref member => panic!("expected a private field, got {:?}", member)
+    }
+    match script("class A { static prototype = 1 }") {
+        Err(Error::StaticPrototype(_)) => {}
+        result => panic!("expected a static prototype error, got {:?}", result)
+    }
+    match script("class A { 'constructor' = 1 }") {
+        Err(Error::IllegalConstructor(_)) => {}
+        result => panic!("expected an illegal constructor error, got {:?}", result)
+    }
+}
+
+#[test]
+fn private_names() {
+    let class = class_declaration("class A { #x; #m() {} get #a() {} set #a(v) {} n() { #x in this; this.#m(); this?.#a; } }");
+    match class.body[1] {
+        ClassMember::Method(ref method) => match method.key {
+            PropKey::Private(ref name) => assert_eq!(name.name, "m"),
+            ref key => panic!("expected a private method name, got {:?}", key)
+        },
+        ref member => panic!("expected a method, got {:?}", member)
+    }
+    let exprs = match class.body.into_iter().last() {
+        Some(ClassMember::Method(method)) => method.fun.body.items,
+        member => panic!("expected a method, got {:?}", member)
+    };
+    match exprs[..] {
+        [StmtListItem::Stmt(Stmt::Expr(_, Expr::PrivateIn(_, ref x, _), _)),
+         StmtListItem::Stmt(Stmt::Expr(_, Expr::Call(_, ref callee, _), _)),
+         StmtListItem::Stmt(Stmt::Expr(_, Expr::Chain(_, ref chain), _))] => {
+            assert_eq!(x.name, "x");
+            match **callee {
+                Expr::PrivateDot(_, _, ref m) => assert_eq!(m.name, "m"),
+                ref expr => panic!("expected a private member, got {:?}", expr)
+            }
+            match **chain {
+                Expr::OptionalPrivateDot(_, _, ref a) => assert_eq!(a.name, "a"),
+                ref expr => panic!("expected an optional private member, got {:?}", expr)
+            }
+        }
+        ref items => panic!("unexpected method body {:?}", items)
+    }
+    assert!(script("class A { #x; m() { class B { n() { this.#x; } } } }").is_ok());
+}
+
+#[test]
+fn private_name_errors() {
+    for source in &["class A { #x; #x; }", "class A { get #x() {} get #x() {} }", "class A { #x; static set #x(v) {} }"] {
+        match script(source) {
+            Err(Error::DuplicatePrivateName(_, ref name)) => assert_eq!(name, "x"),
+            result => panic!("expected a duplicate private name in {:?}, got {:?}", source, result)
+        }
+    }
+    for source in &["this.#y", "class A { #x; m() { this.#y; } }", "class A { m() { #y in this; } }"] {
+        match script(source) {
+            Err(Error::UndeclaredPrivateName(_, ref name)) => assert_eq!(name, "y"),
+            result => panic!("expected an undeclared private name in {:?}, got {:?}", source, result)
+        }
+    }
+    for source in &["class A { #constructor; }", "class A { #x; m() { delete this.#x; } }"] {
+        match script(source) {
+            Err(Error::IllegalPrivateName(_)) => {}
+            result => panic!("expected an illegal private name in {:?}, got {:?}", source, result)
+        }
+    }
+}
+
+#[test]
+fn static_blocks() {
+    let class = class_declaration("class A { static { x = 1; } static {} }");
+    let sizes: Vec<usize> = class.body.iter().map(|member| match *member {
+        ClassMember::StaticBlock(_, ref body) => body.items.len(),
+        ref member => panic!("expected a static block, got {:?}", member)
+    }).collect();
+    assert_eq!(sizes, vec![1, 0]);
+}
+
+#[test]
+fn await_in_static_blocks() {
+    assert!(script("class A { static { function f() { await; } } }").is_ok());
+    assert!(is_contextual_await(script("class A { static { await } }")));
+    assert!(is_contextual_await(script("class A { static { await x; } }")));
+    assert!(is_contextual_await(script("class A { static { let await; } }")));
+}
+
+#[test]
+fn arguments_in_class_initializers() {
+    assert!(script("class A { x = 1; m() { return arguments; } }").is_ok());
+    assert!(script("class A { x = function () { return arguments; }; }").is_ok());
+    assert!(script("class A { static { function f() { arguments; } } }").is_ok());
+    assert!(is_arguments_in_initializer(script("class A { x = arguments }")));
+    assert!(is_arguments_in_initializer(script("class A { static x = () => arguments; }")));
+    assert!(is_arguments_in_initializer(script("class A { x = () => { arguments; }; }")));
+    assert!(is_arguments_in_initializer(script("class A { static { arguments; } }")));
+    assert!(is_arguments_in_initializer(script("class A { static { () => arguments; } }")));
+}
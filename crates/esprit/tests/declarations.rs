@@ -0,0 +1,57 @@
+extern crate esprit;
+
+use esprit::{script, strict, module};
+use esprit::error::Error;
+
+#[test]
+fn duplicate_declarations() {
+    match script("let a; let a;") {
+        Err(Error::DuplicateDeclaration(_, ref name)) => { assert_eq!(name, "a"); }
+        result => { panic!("expected a duplicate declaration, got {:?}", result); }
+    }
+    assert!(script("const a = 1; class a {}").is_err());
+    assert!(script("{ let a; } let a;").is_ok());
+    assert!(script("var a; var a;").is_ok());
+    assert!(script("var a; function a() {}").is_ok());
+    assert!(script("function a() {} function a() {}").is_ok());
+    assert!(module("function a() {} function a() {}").is_err());
+}
+
+#[test]
+fn lexical_var_conflicts() {
+    match script("let a; var a;") {
+        Err(Error::LexicalVarConflict(_, ref name)) => { assert_eq!(name, "a"); }
+        result => { panic!("expected a lexical/var conflict, got {:?}", result); }
+    }
+    assert!(script("let a; { var a; }").is_err());
+    assert!(script("{ let a; var a; }").is_err());
+    assert!(script("{ let a; } var a;").is_ok());
+    assert!(script("function f() { var a; } let a;").is_ok());
+}
+
+#[test]
+fn duplicate_parameters() {
+    assert!(script("function f(a, a) {}").is_ok());
+    match strict("function f(a, a) {}") {
+        Err(Error::DuplicateParameter(_, ref name)) => { assert_eq!(name, "a"); }
+        result => { panic!("expected a duplicate parameter, got {:?}", result); }
+    }
+    assert!(script("function f(a, a) { 'use strict'; }").is_err());
+    assert!(script("function f(a, [a]) {}").is_err());
+    assert!(script("function f(a, a = 1) {}").is_err());
+    assert!(script("(a, a) => 1").is_err());
+    assert!(script("({ m(a, a) {} })").is_err());
+    assert!(script("function f(a) { let a; }").is_err());
+    assert!(script("function f(a) { var a; }").is_ok());
+}
+
+#[test]
+fn duplicate_labels() {
+    match script("a: a: ;") {
+        Err(Error::DuplicateLabel(ref id)) => { assert_eq!(id.name.as_ref(), "a"); }
+        result => { panic!("expected a duplicate label, got {:?}", result); }
+    }
+    assert!(script("a: { a: ; }").is_err());
+    assert!(script("a: ; a: ;").is_ok());
+    assert!(script("a: { function f() { a: ; } }").is_ok());
+}
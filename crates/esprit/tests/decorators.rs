@@ -0,0 +1,89 @@
+extern crate esprit;
+extern crate easter;
+
+use esprit::Parser;
+use esprit::error::Error;
+use esprit::result::Result;
+use easter::class::{Class, ClassMember, Decorator};
+use easter::decl::{Decl, Export};
+use easter::expr::Expr;
+use easter::stmt::{ModItem, Script, Stmt, StmtListItem};
+
+fn decorated(source: &str) -> Result<Script> {
+    let mut parser = Parser::from_source(source);
+    parser.decorators = true;
+    parser.script(false)
+}
+
+fn class_declaration(source: &str) -> Class {
+    match decorated(source).map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Decl(Decl::Class(class)))) => class,
+        result => panic!("expected a class declaration in {:?}, got {:?}", source, result)
+    }
+}
+
+fn is_illegal_decorator<T>(result: Result<T>) -> bool {
+    match result {
+        Err(Error::IllegalDecorator(_)) => true,
+        _ => false
+    }
+}
+
+// The name of each decorator, or of the method a decorator call calls.
+fn names(decorators: &[Decorator]) -> Vec<&str> {
+    decorators.iter().map(|decorator| match decorator.expr {
+        Expr::Id(ref id) => id.name.as_ref(),
+        Expr::Call(_, ref callee, _) => match **callee {
+            Expr::Dot(_, _, ref key) => key.value.as_ref(),
+            ref expr => panic!("unexpected decorator callee {:?}", expr)
+        },
+        ref expr => panic!("unexpected decorator {:?}", expr)
+    }).collect()
+}
+
+#[test]
+fn class_and_member_decorators() {
+    let class = class_declaration("@a @b.c(d) class A { @e m() {} @f x = 1; @g @h static i() {} j() {} }");
+    assert_eq!(names(&class.decorators), vec!["a", "c"]);
+    let members: Vec<Vec<&str>> = class.body.iter().map(|member| match *member {
+        ClassMember::Method(ref method) => names(&method.decorators),
+        ClassMember::Field(ref field) => names(&field.decorators),
+        ref member => panic!("unexpected class member {:?}", member)
+    }).collect();
+    assert_eq!(members, vec![vec!["e"], vec!["f"], vec!["g", "h"], vec![]]);
+}
+
+#[test]
+fn decorated_class_expressions_and_exports() {
+    match decorated("x = @a class {};").map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(Stmt::Expr(_, Expr::Assign(_, _, ref value), _)))) => match **value {
+            Expr::Class(ref class) => assert_eq!(names(&class.decorators), vec!["a"]),
+            ref expr => panic!("expected a class expression, got {:?}", expr)
+        },
+        result => panic!("expected an assignment, got {:?}", result)
+    }
+    for source in &["export @a class A {}", "@a export class A {}"] {
+        let mut parser = Parser::from_source(source);
+        parser.decorators = true;
+        match parser.module().map(|module| module.items.into_iter().next()) {
+            Ok(Some(ModItem::Export(Export::Decl(Decl::Class(ref class))))) => {
+                assert_eq!(names(&class.decorators), vec!["a"]);
+            }
+            result => panic!("expected an exported class in {:?}, got {:?}", source, result)
+        }
+    }
+}
+
+#[test]
+fn decorator_errors() {
+    assert!(is_illegal_decorator(decorated("@a function f() {}")));
+    assert!(is_illegal_decorator(decorated("@a let x;")));
+    assert!(is_illegal_decorator(decorated("class A { @a static {} }")));
+    assert!(is_illegal_decorator(decorated("class A { @a constructor() {} }")));
+    assert!(is_illegal_decorator(decorated("class A { @a 'constructor'() {} }")));
+    assert!(decorated("class A { @a static constructor() {} @b ['constructor']() {} }").is_ok());
+    match esprit::script("@a class A {}") {
+        Err(Error::UnexpectedToken(_, _)) => {}
+        result => panic!("expected decorators to be rejected, got {:?}", result)
+    }
+}
@@ -0,0 +1,163 @@
+extern crate esprit;
+extern crate easter;
+extern crate joker;
+
+use std::mem::replace;
+use esprit::script;
+use esprit::error::Error;
+use easter::decl::Decl;
+use easter::expr::{Expr, ExprListItem};
+use easter::fun::{Fun, FunctionKind};
+use easter::obj::{Prop, PropVal};
+use easter::patt::Patt;
+use easter::stmt::{Stmt, StmtListItem};
+use joker::word::Atom;
+
+fn expression(source: &str) -> Expr {
+    match script(source).map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(Stmt::Expr(_, expr, _)))) => expr,
+        result => panic!("expected an expression statement in {:?}, got {:?}", source, result)
+    }
+}
+
+fn assert_async_parameter(fun: &Fun) {
+    assert!(!fun.is_async);
+    match &fun.params.list[..] {
+        [Patt::Simple(ref id)] => assert_eq!(id.name.as_ref(), "async"),
+        list => panic!("expected a single `async` parameter, got {:?}", list)
+    }
+}
+
+// The expression statements in the body of a function declaration.
+fn body_expressions(source: &str) -> (Fun, Vec<Expr>) {
+    match script(source).map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Decl(Decl::Fun(mut fun)))) => {
+            let items = replace(&mut fun.body.items, Vec::new());
+            (fun, items.into_iter().map(|item| match item {
+                StmtListItem::Stmt(Stmt::Expr(_, expr, _)) => expr,
+                item => panic!("expected an expression statement, got {:?}", item)
+            }).collect())
+        }
+        result => panic!("expected a function declaration in {:?}, got {:?}", source, result)
+    }
+}
+
+fn is_yield_in_parameters<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::YieldInParameters(_)) => true,
+        _ => false
+    }
+}
+
+fn is_contextual_keyword<T>(result: Result<T, Error>, expected: Atom) -> bool {
+    match result {
+        Err(Error::ContextualKeyword(_, atom)) => atom == expected,
+        _ => false
+    }
+}
+
+#[test]
+fn yield_expressions() {
+    let (fun, exprs) = body_expressions("function* g() { yield; yield a, b; yield* c; yield\nd }");
+    match fun.kind {
+        FunctionKind::Generator(ref id) => assert_eq!(id.name.as_ref(), "g"),
+        ref kind => panic!("expected a generator, got {:?}", kind)
+    }
+    match exprs[..] {
+        [Expr::Yield(_, None),
+         Expr::Seq(_, ref seq),
+         Expr::YieldDelegate(_, _),
+         Expr::Yield(_, None),
+         Expr::Id(_)] => match seq[0] {
+            Expr::Yield(_, Some(_)) => {}
+            ref expr => panic!("expected a yield with an argument, got {:?}", expr)
+        },
+        ref exprs => panic!("unexpected generator body {:?}", exprs)
+    }
+    let (_, exprs) = body_expressions("function g() { yield; yield = 1; }");
+    match exprs[..] {
+        [Expr::Id(ref id), Expr::Assign(_, _, _)] => assert_eq!(id.name.as_ref(), "yield"),
+        ref exprs => panic!("expected yield to be an identifier, got {:?}", exprs)
+    }
+    match expression("({ *g() {}, m: function* () {} })") {
+        Expr::Obj(_, ref props) => match props[..] {
+            [Prop::Method(_, ref method), Prop::Regular(_, _, PropVal::Init(Expr::Fun(ref fun)))] => {
+                assert_eq!(method.kind, FunctionKind::AnonymousGenerator);
+                assert_eq!(fun.kind, FunctionKind::AnonymousGenerator);
+            }
+            ref props => panic!("unexpected properties {:?}", props)
+        },
+        expr => panic!("expected an object, got {:?}", expr)
+    }
+}
+
+#[test]
+fn yield_in_generator_parameters() {
+    assert!(script("function g(a = yield) {}").is_ok());
+    assert!(script("function* g(a = function* () { yield }) {}").is_ok());
+    assert!(is_yield_in_parameters(script("function* g(a = yield) {}")));
+    assert!(is_yield_in_parameters(script("function* g(a = yield 1) {}")));
+    assert!(is_yield_in_parameters(script("function* g() { function* h(a = yield) {} }")));
+    assert!(is_yield_in_parameters(script("x = { *m(a = yield) {} }")));
+    assert!(is_contextual_keyword(script("function* g(yield) {}"), Atom::Yield));
+}
+
+#[test]
+fn generator_expression_names() {
+    assert!(script("function* yield() {}").is_ok());
+    assert!(script("x = function yield() {}").is_ok());
+    assert!(script("x = function* g() {}").is_ok());
+    assert!(is_contextual_keyword(script("x = function* yield() {}"), Atom::Yield));
+    assert!(is_contextual_keyword(script("function* g() { function* yield() {} }"), Atom::Yield));
+    assert!(is_contextual_keyword(script("function* g() { var yield; }"), Atom::Yield));
+    assert!(is_contextual_keyword(script("'use strict'; var yield;"), Atom::Yield));
+}
+
+#[test]
+fn await_in_async_parameters() {
+    assert!(script("function f(a = await) {}").is_ok());
+    assert!(script("async function f(a = async () => await x) {}").is_ok());
+    assert!(script("async function f(a = await x) {}").is_err());
+    assert!(script("x = async (a = await x) => 1").is_err());
+    assert!(script("async function f() { (a = await x) => 1 }").is_err());
+    assert!(script("x = async (await) => 1").is_err());
+    assert!(script("x = async await => 1").is_err());
+    assert!(script("x = async (a) => 1").is_ok());
+    assert!(script("x = async(await)").is_ok());
+}
+
+#[test]
+fn async_as_arrow_parameter() {
+    match expression("async => 1") { Expr::Fun(fun) => assert_async_parameter(&fun), expr => panic!("{:?}", expr) }
+    match expression("async\n=> 1") { Expr::Fun(fun) => assert_async_parameter(&fun), expr => panic!("{:?}", expr) }
+    match expression("x = async => 1") { Expr::Assign(_, _, right) => match *right {
+        Expr::Fun(fun) => assert_async_parameter(&fun),
+        expr => panic!("{:?}", expr)
+    }, expr => panic!("{:?}", expr) }
+    match expression("f(async => 1)") { Expr::Call(_, _, mut args) => match args.pop() {
+        Some(ExprListItem::Expr(Expr::Fun(fun))) => assert_async_parameter(&fun),
+        arg => panic!("{:?}", arg)
+    }, expr => panic!("{:?}", expr) }
+    assert!(script("async => await").is_ok());
+    assert!(script("x = async\n=> 1").is_ok());
+    assert!(script("async function f() { async => 1 }").is_ok());
+    assert!(script("async => {}; async\n(a) => 1").is_err());
+}
+
+#[test]
+fn async_expression_names() {
+    assert!(script("async function await() {}").is_ok());
+    assert!(script("x = function await() {}").is_ok());
+    assert!(script("x = async function await() {}").is_err());
+    assert!(script("x = async function* await() {}").is_err());
+}
+
+#[test]
+fn for_of_async() {
+    assert!(script("for (async of x);").is_err());
+    assert!(script("for ((async) of x);").is_ok());
+    assert!(script("for (async.x of y);").is_ok());
+    assert!(script("for (async of => {};;);").is_ok());
+    assert!(script("for (async in x);").is_ok());
+    assert!(script("async function f() { for await (async of x); }").is_ok());
+}
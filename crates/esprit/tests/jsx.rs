@@ -0,0 +1,98 @@
+extern crate esprit;
+extern crate easter;
+
+use esprit::Parser;
+use esprit::error::Error;
+use esprit::result::Result;
+use easter::expr::Expr;
+use easter::jsx::{JSXAttr, JSXAttrValue, JSXChild, JSXElement, JSXName};
+use easter::stmt::{Script, Stmt, StmtListItem};
+
+fn jsx(source: &str) -> Result<Script> {
+    let mut parser = Parser::from_source(source);
+    parser.jsx = true;
+    parser.script(false)
+}
+
+fn element(source: &str) -> JSXElement {
+    match jsx(source).map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(Stmt::Expr(_, Expr::JSXElement(element), _)))) => element,
+        result => panic!("expected a JSX element in {:?}, got {:?}", source, result)
+    }
+}
+
+#[test]
+fn elements() {
+    let element = element("<a b=\"c\" {...d} e={f} g>x{y}{/* z */}<b.c/></a>");
+    assert_eq!(element.name.qualified(), "a");
+    match element.attrs[..] {
+        [JSXAttr::Regular(_, ref b, Some(JSXAttrValue::String(_, ref c))),
+         JSXAttr::Spread(_, Expr::Id(_)),
+         JSXAttr::Regular(_, ref e, Some(JSXAttrValue::Expr(_, Expr::Id(_)))),
+         JSXAttr::Regular(_, ref g, None)] => {
+            assert_eq!((b.qualified(), &c.value[..]), (String::from("b"), "c"));
+            assert_eq!((e.qualified(), g.qualified()), (String::from("e"), String::from("g")));
+        }
+        ref attrs => panic!("unexpected attributes {:?}", attrs)
+    }
+    match element.children.as_ref().map(|children| &children[..]) {
+        Some([JSXChild::Text(_, ref text), JSXChild::Expr(_, Some(Expr::Id(_))), JSXChild::Expr(_, None), JSXChild::Element(ref child)]) => {
+            assert_eq!(text.value, "x");
+            assert_eq!(child.name.qualified(), "b.c");
+            assert!(child.children.is_none());
+        }
+        children => panic!("unexpected children {:?}", children)
+    }
+}
+
+#[test]
+fn names_and_text() {
+    let element = element("<a-b:c d-e>&amp;&#65;</a-b:c>");
+    match element.name {
+        JSXName::Namespaced(_, ref namespace, ref name) => {
+            assert_eq!((&namespace.name[..], &name.name[..]), ("a-b", "c"));
+        }
+        ref name => panic!("expected a namespaced name, got {:?}", name)
+    }
+    match element.children.as_ref().map(|children| &children[..]) {
+        Some([JSXChild::Text(_, ref text)]) => {
+            assert_eq!((&text.value[..], &text.raw[..]), ("&A", "&amp;&#65;"));
+        }
+        children => panic!("unexpected children {:?}", children)
+    }
+}
+
+#[test]
+fn fragments() {
+    match jsx("<>a<></></>").map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(Stmt::Expr(_, Expr::JSXFragment(fragment), _)))) => {
+            match fragment.children[..] {
+                [JSXChild::Text(_, _), JSXChild::Fragment(ref inner)] => assert!(inner.children.is_empty()),
+                ref children => panic!("unexpected children {:?}", children)
+            }
+        }
+        result => panic!("expected a JSX fragment, got {:?}", result)
+    }
+}
+
+#[test]
+fn jsx_errors() {
+    for source in &["<a></b>", "<></a>", "<a.b></a>"] {
+        match jsx(source) {
+            Err(Error::MismatchedJSXTag(_)) => {}
+            result => panic!("expected a mismatched tag error in {:?}, got {:?}", source, result)
+        }
+    }
+    for source in &["<a>", "<a b=1 />"] {
+        assert!(jsx(source).is_err());
+    }
+}
+
+#[test]
+fn jsx_is_opt_in() {
+    match esprit::script("<a/>") {
+        Err(Error::UnexpectedToken(_, _)) => {}
+        result => panic!("expected JSX to be rejected, got {:?}", result)
+    }
+    assert!(jsx("a < b > c").is_ok());
+}
@@ -0,0 +1,48 @@
+extern crate esprit;
+
+use esprit::{script, strict, module, Parser};
+
+fn annex_b(source: &str) -> bool {
+    let mut parser = Parser::from_source(source);
+    parser.annex_b = true;
+    parser.script(false).is_ok()
+}
+
+#[test]
+fn legacy_literals_and_comments_in_sloppy_scripts() {
+    assert!(script("x = 010").is_ok());
+    assert!(script("x = '\\101'").is_ok());
+    assert!(script("x = 1 <!-- comment\n").is_ok());
+    assert!(script("x = 1\n--> comment\n").is_ok());
+    assert!(script("'\\101'; 'use strict';").is_err());
+    assert!(strict("x = 010").is_err());
+    assert!(strict("x = '\\101'").is_err());
+    assert!(module("x = 010").is_err());
+    assert!(module("<!-- comment\n").is_err());
+}
+
+#[test]
+fn syntactic_extensions_need_annex_b() {
+    assert!(script("if (x) function f() {}").is_err());
+    assert!(script("l: function f() {}").is_err());
+    assert!(script("for (var x = 1 in o);").is_err());
+    assert!(annex_b("if (x) function f() {}"));
+    assert!(annex_b("l: function f() {}"));
+    assert!(annex_b("for (var x = 1 in o);"));
+    assert!(!annex_b("'use strict'; if (x) function f() {}"));
+}
+
+#[test]
+fn legacy_declarations_in_sloppy_scripts() {
+    assert!(script("try {} catch (e) { var e; }").is_ok());
+    assert!(script("{ function f() {} function f() {} }").is_ok());
+    assert!(script("switch (x) { case 1: function f() {} default: function f() {} }").is_ok());
+    assert!(script("try {} catch ([e]) { var e; }").is_err());
+    assert!(script("{ async function f() {} function f() {} }").is_err());
+    assert!(script("{ function* f() {} function f() {} }").is_err());
+    assert!(script("function g() { 'use strict'; { function f() {} function f() {} } }").is_err());
+    assert!(strict("try {} catch (e) { var e; }").is_err());
+    assert!(strict("{ function f() {} function f() {} }").is_err());
+    assert!(module("try {} catch (e) { var e; }").is_err());
+    assert!(module("{ function f() {} function f() {} }").is_err());
+}
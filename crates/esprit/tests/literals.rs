@@ -0,0 +1,73 @@
+extern crate esprit;
+extern crate easter;
+extern crate joker;
+
+use esprit::script;
+use easter::expr::Expr;
+use easter::obj::{Prop, PropKey};
+use easter::stmt::{Stmt, StmtListItem};
+use joker::track::Untrack;
+
+fn expression(source: &str) -> Expr {
+    match script(source).map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(Stmt::Expr(_, expr, _)))) => expr,
+        result => panic!("expected an expression statement in {:?}, got {:?}", source, result)
+    }
+}
+
+// Without their locations, literals and names compare by value, whatever
+// their spelling.
+fn untracked(source: &str) -> Expr {
+    let mut expr = expression(source);
+    expr.untrack();
+    expr
+}
+
+fn raw(expr: &Expr) -> &str {
+    match *expr {
+        Expr::Number(_, ref literal) => literal.raw.as_ref().unwrap(),
+        Expr::BigInt(_, ref literal) => literal.raw.as_ref().unwrap(),
+        Expr::String(_, ref literal) => literal.source.as_ref().unwrap(),
+        Expr::RegExp(_, ref literal) => literal.raw.as_ref().unwrap(),
+        ref expr => panic!("expected a literal, got {:?}", expr)
+    }
+}
+
+#[test]
+fn raw_literals() {
+    assert_eq!(raw(&expression("0XfF")), "0XfF");
+    assert_eq!(raw(&expression("1_000.5e1")), "1_000.5e1");
+    assert_eq!(raw(&expression("0x1_0n")), "0x1_0n");
+    assert_eq!(raw(&expression("('\\x41\\\n')")), "'\\x41\\\n'");
+    assert_eq!(raw(&expression("/[/]a\\//gi")), "/[/]a\\//gi");
+    assert_eq!(untracked("0xff"), untracked("255"));
+    assert_eq!(untracked("('\\x41')"), untracked("(\"A\")"));
+}
+
+#[test]
+fn raw_names() {
+    match expression("({ \\u0061: 1, b\\u{63}() {}, d: 2 })") {
+        Expr::Obj(_, ref props) => {
+            let keys: Vec<(&str, &str)> = props.iter().map(|prop| match *prop {
+                Prop::Regular(_, PropKey::Id(_, ref name), _)
+              | Prop::Method(PropKey::Id(_, ref name), _) => (name.as_ref(), name.raw()),
+                ref prop => panic!("expected a named property, got {:?}", prop)
+            }).collect();
+            assert_eq!(keys, vec![("a", "\\u0061"), ("bc", "b\\u{63}"), ("d", "d")]);
+        }
+        expr => panic!("expected an object, got {:?}", expr)
+    }
+    match expression("a.\\u0062.c") {
+        Expr::Dot(_, ref object, ref key) => {
+            assert_eq!((key.value.as_ref(), key.value.raw()), ("c", "c"));
+            match **object {
+                Expr::Dot(_, _, ref key) => {
+                    assert_eq!((key.value.as_ref(), key.value.raw()), ("b", "\\u0062"));
+                }
+                ref expr => panic!("expected a member expression, got {:?}", expr)
+            }
+        }
+        expr => panic!("expected a member expression, got {:?}", expr)
+    }
+    assert_eq!(untracked("a.\\u0062"), untracked("a.b"));
+}
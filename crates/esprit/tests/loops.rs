@@ -0,0 +1,109 @@
+extern crate esprit;
+extern crate easter;
+
+use esprit::script;
+use esprit::error::Error;
+use easter::cover;
+use easter::decl::Dtor;
+use easter::id::Id;
+use easter::patt::{CompoundPatt, Patt};
+use easter::stmt::{ForInHead, ForOfHead, Stmt, StmtListItem};
+
+fn statement(source: &str) -> Stmt {
+    match script(source).map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(stmt))) => stmt,
+        result => panic!("expected a statement in {:?}, got {:?}", source, result)
+    }
+}
+
+fn names(patt: &Patt<Id>) -> Vec<&str> {
+    patt.bound_ids().into_iter().map(|id| id.name.as_ref()).collect()
+}
+
+fn is_unexpected_token<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::UnexpectedToken(_, _)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn declarations_in_for_heads() {
+    match statement("for (var [a, , ...b] of c);") {
+        Stmt::ForOf(_, ref head, _, _) => match **head {
+            ForOfHead::Var(_, ref patt) => assert_eq!(names(patt), vec!["a", "b"]),
+            ref head => panic!("expected a var head, got {:?}", head)
+        },
+        stmt => panic!("expected a for-of loop, got {:?}", stmt)
+    }
+    match statement("for (let { a, b: [c = 1], ...d } in e);") {
+        Stmt::ForIn(_, ref head, _, _) => match **head {
+            ForInHead::Let(_, ref patt) => assert_eq!(names(patt), vec!["a", "c", "d"]),
+            ref head => panic!("expected a let head, got {:?}", head)
+        },
+        stmt => panic!("expected a for-in loop, got {:?}", stmt)
+    }
+    match statement("for (const [a = 1] of b);") {
+        Stmt::ForOf(_, ref head, _, _) => match **head {
+            ForOfHead::Const(_, ref patt) => assert_eq!(names(patt), vec!["a"]),
+            ref head => panic!("expected a const head, got {:?}", head)
+        },
+        stmt => panic!("expected a for-of loop, got {:?}", stmt)
+    }
+}
+
+#[test]
+fn assignment_patterns_in_for_heads() {
+    match statement("for ([a.b, c[0] = 1, ...d] of e);") {
+        Stmt::ForOf(_, ref head, _, _) => match **head {
+            ForOfHead::Patt(Patt::Compound(CompoundPatt::Arr(_, ref elements, Some(_)))) => assert_eq!(elements.len(), 2),
+            ref head => panic!("expected an array pattern head, got {:?}", head)
+        },
+        stmt => panic!("expected a for-of loop, got {:?}", stmt)
+    }
+    match statement("for ({ a = 1, b: c.d } in e);") {
+        Stmt::ForIn(_, ref head, _, _) => match **head {
+            ForInHead::Patt(Patt::Compound(CompoundPatt::Obj(_, ref props, None))) => assert_eq!(props.len(), 2),
+            ref head => panic!("expected an object pattern head, got {:?}", head)
+        },
+        stmt => panic!("expected a for-in loop, got {:?}", stmt)
+    }
+    assert!(script("for ([a, b] = [1, 2]; ;);").is_ok());
+}
+
+#[test]
+fn destructuring_declarators() {
+    match statement("var [a, ...b] = c, { d, ...e } = f, g;") {
+        Stmt::Var(_, ref dtors, _) => {
+            let names: Vec<Vec<&str>> = dtors.iter()
+                .map(|dtor| dtor.bound_ids().into_iter().map(|id| id.name.as_ref()).collect())
+                .collect();
+            assert_eq!(names, vec![vec!["a", "b"], vec!["d", "e"], vec!["g"]]);
+            match dtors[0] {
+                Dtor::Compound(_, CompoundPatt::Arr(_, _, Some(_)), _, _) => {}
+                ref dtor => panic!("expected an array pattern declarator, got {:?}", dtor)
+            }
+        }
+        stmt => panic!("expected a var statement, got {:?}", stmt)
+    }
+    assert!(script("function f(...[a, b]) {} function g(...{ length }) {}").is_ok());
+}
+
+#[test]
+fn destructuring_errors() {
+    assert!(is_unexpected_token(script("var [a];")));
+    assert!(is_unexpected_token(script("let { a };")));
+    assert!(is_unexpected_token(script("for (var [a] = 1 in b);")));
+    assert!(is_unexpected_token(script("for (let [a] = 1 of b);")));
+    assert!(is_unexpected_token(script("for (let [a], b of c);")));
+    assert!(is_unexpected_token(script("function f(...[a] = 1) {}")));
+    assert!(is_unexpected_token(script("function f(...[a], b) {}")));
+    match script("for ([a] = 1 of b);") {
+        Err(Error::InvalidLHS(_, cover::Error::InvalidAssignTarget(_))) => {}
+        result => panic!("expected an invalid assignment target, got {:?}", result)
+    }
+    match script("for (let [a, a] of b);") {
+        Err(Error::DuplicateDeclaration(_, ref name)) => assert_eq!(name, "a"),
+        result => panic!("expected a duplicate declaration, got {:?}", result)
+    }
+}
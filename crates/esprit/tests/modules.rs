@@ -0,0 +1,258 @@
+extern crate esprit;
+extern crate easter;
+
+use esprit::{script, module, program, Program};
+use esprit::error::{Check, Error};
+use easter::cover;
+use easter::decl::{Export, Import};
+use easter::expr::Expr;
+use easter::stmt::{ModItem, Stmt, StmtListItem};
+
+fn is_invalid_target<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::InvalidLHS(_, cover::Error::InvalidAssignTarget(_))) => true,
+        _ => false
+    }
+}
+
+fn import_declaration(source: &str) -> Import {
+    match module(source).map(|module| module.items.into_iter().next()) {
+        Ok(Some(ModItem::Import(import))) => import,
+        result => panic!("expected an import declaration in {:?}, got {:?}", source, result)
+    }
+}
+
+fn is_unexpected_token<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::UnexpectedToken(_, _)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn import_declarations() {
+    match import_declaration("import 'm';") {
+        Import::ForEffect(_, ref source, _) => assert_eq!(source.value, "m"),
+        import => panic!("expected an import for effect, got {:?}", import)
+    }
+    match import_declaration("import a, { b, c as d, default as e, if as f } from 'm';") {
+        Import::Named(_, Some(ref default), ref specs, ref source, _) => {
+            assert_eq!(default.name.as_ref(), "a");
+            let specs: Vec<(&str, &str)> = specs.iter()
+                .map(|spec| (spec.imported.value.as_ref(), spec.local.name.as_ref()))
+                .collect();
+            assert_eq!(specs, vec![("b", "b"), ("c", "d"), ("default", "e"), ("if", "f")]);
+            assert_eq!(source.value, "m");
+        }
+        import => panic!("expected a named import, got {:?}", import)
+    }
+    match import_declaration("import {} from 'm';") {
+        Import::Named(_, None, ref specs, _, _) => assert!(specs.is_empty()),
+        import => panic!("expected a named import, got {:?}", import)
+    }
+    match import_declaration("import * as ns from 'm';") {
+        Import::Namespace(_, None, ref ns, _, _) => assert_eq!(ns.name.as_ref(), "ns"),
+        import => panic!("expected a namespace import, got {:?}", import)
+    }
+    match import_declaration("import a, * as ns from 'm';") {
+        Import::Namespace(_, Some(ref default), ref ns, _, _) => {
+            assert_eq!((default.name.as_ref(), ns.name.as_ref()), ("a", "ns"));
+        }
+        import => panic!("expected a namespace import, got {:?}", import)
+    }
+}
+
+#[test]
+fn import_errors() {
+    assert!(is_unexpected_token(module("import * from 'm';")));
+    assert!(is_unexpected_token(module("import a, * as b, { c } from 'm';")));
+    assert!(is_unexpected_token(module("import { default } from 'm';")));
+    assert!(is_unexpected_token(module("import { a as 1 } from 'm';")));
+    assert!(is_unexpected_token(module("import { a } 'm';")));
+    assert!(is_unexpected_token(script("import a from 'm';")));
+    match module("import { a, a } from 'm';") {
+        Err(Error::DuplicateDeclaration(_, ref name)) => assert_eq!(name, "a"),
+        result => panic!("expected a duplicate declaration, got {:?}", result)
+    }
+    match module("import a from 'm'; let a;") {
+        Err(Error::DuplicateDeclaration(_, ref name)) => assert_eq!(name, "a"),
+        result => panic!("expected a duplicate declaration, got {:?}", result)
+    }
+}
+
+#[test]
+fn export_namespace_from() {
+    for &(source, expected) in &[("export * as ns from 'm';", "ns"), ("export * as default from 'm';", "default")] {
+        match module(source).map(|module| module.items.into_iter().next()) {
+            Ok(Some(ModItem::Export(Export::NamespaceFrom(_, ref name, ref source, _)))) => {
+                assert_eq!((name.value.as_ref(), &source.value[..]), (expected, "m"));
+            }
+            result => panic!("expected a namespace export, got {:?}", result)
+        }
+    }
+    match module("export * from 'm';").map(|module| module.items.into_iter().next()) {
+        Ok(Some(ModItem::Export(Export::AllFrom(_, _, _)))) => {}
+        result => panic!("expected a star export, got {:?}", result)
+    }
+    match module("export * as a from 'm'; export var a;") {
+        Err(Error::DuplicateExport(_, ref name)) => assert_eq!(name, "a"),
+        result => panic!("expected a duplicate export, got {:?}", result)
+    }
+    assert!(is_unexpected_token(module("export * as ns;")));
+    assert!(is_unexpected_token(module("export * as 1 from 'm';")));
+}
+
+fn exports(source: &str) -> Vec<Export> {
+    match module(source) {
+        Ok(module) => module.items.into_iter().filter_map(|item| match item {
+            ModItem::Export(export) => Some(export),
+            _ => None
+        }).collect(),
+        Err(error) => panic!("expected a module in {:?}, got {:?}", source, error)
+    }
+}
+
+fn exported_names(source: &str) -> Vec<String> {
+    exports(source).iter()
+        .flat_map(|export| export.exported_names())
+        .map(|(name, _)| String::from(name))
+        .collect()
+}
+
+#[test]
+fn export_declarations() {
+    assert_eq!(exported_names("export var a, [b] = c; export let d; export const e = 1;"), vec!["a", "b", "d", "e"]);
+    assert_eq!(exported_names("export function f() {} export async function g() {} export class C {}"), vec!["f", "g", "C"]);
+    assert_eq!(exported_names("var a, b; export { a, b as c, a as default };"), vec!["a", "c", "default"]);
+    assert_eq!(exported_names("export { a as b, default, if as c } from 'm';"), vec!["b", "default", "c"]);
+    assert!(exported_names("export * from 'm';").is_empty());
+    match exports("export default function () {}")[..] {
+        [Export::DefaultFun(_, ref fun)] => assert!(fun.body.items.is_empty()),
+        ref exports => panic!("expected a default function, got {:?}", exports)
+    }
+    match exports("export default class {}")[..] {
+        [Export::DefaultClass(_, ref class)] => assert!(class.id.is_none()),
+        ref exports => panic!("expected a default class, got {:?}", exports)
+    }
+    match exports("export default (function () {});")[..] {
+        [Export::DefaultExpr(_, _, _)] => {}
+        ref exports => panic!("expected a default expression, got {:?}", exports)
+    }
+}
+
+#[test]
+fn duplicate_exports() {
+    for &(source, name) in &[("export default 1; export default 2;", "default"),
+                             ("export var a; export { a };", "a"),
+                             ("var a, c; export { a as b, c as b };", "b"),
+                             ("export function f() {} export { g as f } from 'm';", "f")] {
+        match module(source) {
+            Err(Error::DuplicateExport(_, ref duplicate)) => assert_eq!(duplicate, name),
+            result => panic!("expected a duplicate export in {:?}, got {:?}", source, result)
+        }
+    }
+}
+
+#[test]
+fn exported_bindings_must_be_declared() {
+    assert!(module("var a; export { a };").is_ok());
+    assert!(module("export { a }; let a;").is_ok());
+    assert!(module("import a from 'm'; export { a as b };").is_ok());
+    assert!(module("function f() {} export { f };").is_ok());
+    assert!(module("export { a } from 'm';").is_ok());
+    for source in &["export { a };", "function f() { var a; } export { a };", "{ let a; } export { a };"] {
+        match module(source) {
+            Err(Error::UndeclaredExport(_, ref name)) => assert_eq!(name, "a"),
+            result => panic!("expected an undeclared export in {:?}, got {:?}", source, result)
+        }
+    }
+    assert!(is_unexpected_token(module("export { if };")));
+}
+
+fn assigned_value(source: &str) -> Expr {
+    match module(source).map(|module| module.items.into_iter().next()) {
+        Ok(Some(ModItem::StmtListItem(StmtListItem::Stmt(Stmt::Expr(_, Expr::Assign(_, _, value), _))))) => *value,
+        result => panic!("expected an assignment in {:?}, got {:?}", source, result)
+    }
+}
+
+#[test]
+fn dynamic_import() {
+    match assigned_value("x = import('m')") {
+        Expr::Import(_, ref specifier) => match **specifier {
+            Expr::String(_, ref lit) => assert_eq!(lit.value, "m"),
+            ref expr => panic!("expected a string specifier, got {:?}", expr)
+        },
+        expr => panic!("expected an import call, got {:?}", expr)
+    }
+    match assigned_value("x = import(a + b).then(f)") {
+        Expr::Call(_, ref callee, _) => match **callee {
+            Expr::Dot(_, ref object, _) => match **object {
+                Expr::Import(_, ref specifier) => match **specifier {
+                    Expr::Binop(_, _, _, _) => {}
+                    ref expr => panic!("expected a computed specifier, got {:?}", expr)
+                },
+                ref expr => panic!("expected an import call, got {:?}", expr)
+            },
+            ref expr => panic!("expected a member expression, got {:?}", expr)
+        },
+        expr => panic!("expected a call, got {:?}", expr)
+    }
+    assert!(script("x = import('m')").is_ok());
+    assert!(is_unexpected_token(script("x = import()")));
+    assert!(is_unexpected_token(script("x = import('a', 'b', 'c')")));
+    assert!(is_unexpected_token(script("new import('m')")));
+}
+
+fn is_import_meta_outside_module<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::ImportMetaOutsideModule(_)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn import_meta() {
+    match assigned_value("x = import.meta.url") {
+        Expr::Dot(_, ref object, ref key) => {
+            assert_eq!(key.value.as_ref(), "url");
+            match **object {
+                Expr::ImportMeta(_) => {}
+                ref expr => panic!("expected import.meta, got {:?}", expr)
+            }
+        }
+        expr => panic!("expected a member expression, got {:?}", expr)
+    }
+    assert!(is_import_meta_outside_module(script("x = import.meta.url")));
+    assert!(is_import_meta_outside_module(script("function f() { import.meta }")));
+    assert!(is_unexpected_token(module("x = import.foo")));
+}
+
+#[test]
+fn import_meta_is_not_a_target() {
+    assert!(module("import.meta.url = 1").is_ok());
+    assert!(is_invalid_target(module("import.meta = 1")));
+    assert!(is_invalid_target(module("import.meta += 1")));
+    assert!(is_invalid_target(module("import.meta++")));
+    assert!(is_invalid_target(module("--import.meta")));
+    assert!(is_invalid_target(module("for (import.meta of x);")));
+}
+
+#[test]
+fn program_defers_the_goal() {
+    match program("x = import.meta").unwrap() {
+        Program::Ambiguous(ref checks, _) => match checks[..] {
+            [Check::Script(Error::ImportMetaOutsideModule(_))] => {}
+            ref checks => panic!("expected an import.meta check, got {:?}", checks)
+        },
+        Program::Module(_) => { panic!("expected an ambiguous program"); }
+    }
+    match program("x = import('m')").unwrap() {
+        Program::Ambiguous(checks, _) => { assert!(checks.is_empty()); }
+        Program::Module(_) => { panic!("expected an ambiguous program"); }
+    }
+    match program("import x from 'm'; x = import.meta").unwrap() {
+        Program::Module(_) => {}
+        Program::Ambiguous(_, _) => { panic!("expected a module"); }
+    }
+}
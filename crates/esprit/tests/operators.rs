@@ -0,0 +1,122 @@
+extern crate esprit;
+extern crate easter;
+extern crate joker;
+
+use esprit::script;
+use esprit::error::Error;
+use easter::expr::Expr;
+use easter::punc::{AssopTag, BinopTag, LogopTag};
+use easter::stmt::{Stmt, StmtListItem};
+use easter::cover;
+use joker::track::Untrack;
+
+fn expression(source: &str) -> Expr {
+    match script(source).map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(Stmt::Expr(_, expr, _)))) => expr,
+        result => panic!("expected an expression statement in {:?}, got {:?}", source, result)
+    }
+}
+
+// Parentheses leave no trace in the tree, so an expression can be compared
+// with an explicitly grouped version of itself.
+fn same_grouping(source: &str, grouped: &str) -> bool {
+    let mut expr = expression(source);
+    let mut grouped = expression(grouped);
+    expr.untrack();
+    grouped.untrack();
+    expr == grouped
+}
+
+fn assignment_tag(source: &str) -> AssopTag {
+    match expression(source) {
+        Expr::BinAssign(_, op, _, _) => op.tag,
+        expr => panic!("expected a compound assignment in {:?}, got {:?}", source, expr)
+    }
+}
+
+#[test]
+fn exponentiation() {
+    match expression("a ** b") {
+        Expr::Binop(_, ref op, _, _) => assert!(op.tag == BinopTag::Exp),
+        expr => panic!("expected a binary expression, got {:?}", expr)
+    }
+    assert!(same_grouping("a ** b ** c", "a ** (b ** c)"));
+    assert!(same_grouping("a * b ** c", "a * (b ** c)"));
+    assert!(assignment_tag("a **= b") == AssopTag::ExpEq);
+    assert!(script("(-a) ** b; ++a ** b; a-- ** b").is_ok());
+    for source in &["-a ** b", "typeof a ** b", "!a ** b", "delete a ** b"] {
+        match script(source) {
+            Err(Error::UnaryBeforeExponent(_)) => {}
+            result => panic!("expected a unary operand error in {:?}, got {:?}", source, result)
+        }
+    }
+}
+
+#[test]
+fn optional_chains() {
+    match expression("a?.b.c") {
+        Expr::Chain(_, ref link) => match **link {
+            Expr::Dot(_, ref object, ref key) => {
+                assert_eq!(key.value.as_ref(), "c");
+                match **object {
+                    Expr::OptionalDot(_, _, ref key) => assert_eq!(key.value.as_ref(), "b"),
+                    ref expr => panic!("expected an optional member, got {:?}", expr)
+                }
+            }
+            ref expr => panic!("expected a member expression, got {:?}", expr)
+        },
+        expr => panic!("expected a chain, got {:?}", expr)
+    }
+    match expression("a?.[b]?.(c)") {
+        Expr::Chain(_, ref link) => match **link {
+            Expr::OptionalCall(_, ref callee, ref args) => {
+                assert_eq!(args.len(), 1);
+                match **callee {
+                    Expr::OptionalBrack(_, _, _) => {}
+                    ref expr => panic!("expected an optional computed member, got {:?}", expr)
+                }
+            }
+            ref expr => panic!("expected an optional call, got {:?}", expr)
+        },
+        expr => panic!("expected a chain, got {:?}", expr)
+    }
+    match script("a?.b = 1") {
+        Err(Error::InvalidLHS(_, cover::Error::InvalidAssignTarget(_))) => {}
+        result => panic!("expected an invalid assignment target, got {:?}", result)
+    }
+    for source in &["new a?.b", "a?.`x`", "a?.b`x`"] {
+        match script(source) {
+            Err(Error::IllegalOptionalChain(_)) => {}
+            result => panic!("expected an illegal optional chain in {:?}, got {:?}", source, result)
+        }
+    }
+}
+
+#[test]
+fn nullish_coalescing() {
+    match expression("a ?? b") {
+        Expr::Logop(_, ref op, _, _) => assert!(op.tag == LogopTag::Coalesce),
+        expr => panic!("expected a logical expression, got {:?}", expr)
+    }
+    assert!(same_grouping("a ?? b ?? c", "(a ?? b) ?? c"));
+    assert!(script("(a ?? b) || c; a ?? (b && c); a?.b ?? c").is_ok());
+    for source in &["a ?? b || c", "a || b ?? c", "a && b ?? c", "a ?? b && c"] {
+        match script(source) {
+            Err(Error::MixedCoalesce(_)) => {}
+            result => panic!("expected a mixed coalescing error in {:?}, got {:?}", source, result)
+        }
+    }
+}
+
+#[test]
+fn logical_assignment() {
+    assert!(assignment_tag("a &&= b") == AssopTag::AndEq);
+    assert!(assignment_tag("a ||= b") == AssopTag::OrEq);
+    assert!(assignment_tag("a ??= b") == AssopTag::CoalesceEq);
+    for source in &["a + b ??= c", "a?.b ||= c", "f() &&= c"] {
+        match script(source) {
+            Err(Error::InvalidLHS(_, _)) => {}
+            result => panic!("expected an invalid assignment target in {:?}, got {:?}", source, result)
+        }
+    }
+}
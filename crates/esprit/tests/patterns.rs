@@ -0,0 +1,100 @@
+extern crate esprit;
+extern crate easter;
+
+use esprit::{script, strict, module};
+use esprit::error::Error;
+use easter::cover;
+
+fn cover_error(source: &str) -> cover::Error {
+    match script(source) {
+        Err(Error::InvalidLHS(_, err)) => err,
+        result => panic!("expected an invalid target in {:?}, got {:?}", source, result)
+    }
+}
+
+#[test]
+fn object_rest_in_assignment() {
+    assert!(script("({...a} = o)").is_ok());
+    assert!(script("({a, ...b.c} = o)").is_ok());
+    assert!(script("x = {...a,}").is_ok());
+    assert!(script("x = {...a, b}").is_ok());
+    assert!(script("({...a,} = obj)").is_err());
+    assert!(script("({...{a}} = obj)").is_err());
+    assert!(script("({...[a]} = o)").is_err());
+    assert!(script("({...a, b} = o)").is_err());
+    assert!(script("for ({...a} of x);").is_ok());
+    assert!(script("for ({...a,} of x);").is_err());
+    assert!(script("for ({...{a}} of x);").is_err());
+}
+
+#[test]
+fn object_rest_in_binding() {
+    assert!(script("let {a, ...b} = o").is_ok());
+    assert!(script("let {...a,} = o").is_err());
+    assert!(script("let {...{a}} = o").is_err());
+    assert!(script("let {...[a]} = o").is_err());
+    assert!(script("function f({...a,}) {}").is_err());
+}
+
+#[test]
+fn assignment_targets() {
+    assert!(script("[a = 1, [b] = [], {c: d = 2}, ...e] = o").is_ok());
+    assert!(script("({a: {b} = {}} = o)").is_ok());
+    assert!(script("({a: 1} = o)").is_err());
+    assert!(script("[a + 1] = o").is_err());
+}
+
+#[test]
+fn for_in_of_targets() {
+    assert!(script("for ([a = 1, ...b] of c);").is_ok());
+    assert!(script("for ({a: [b = 1]} in c);").is_ok());
+    assert!(script("for ([a.b, c[0]] of d);").is_ok());
+    match cover_error("for (x = 1 in y);") { cover::Error::InvalidAssignTarget(_) => {} err => panic!("{:?}", err) }
+    match cover_error("for (x = 1 of y);") { cover::Error::InvalidAssignTarget(_) => {} err => panic!("{:?}", err) }
+    match cover_error("for ([a] = 1 of c);") { cover::Error::InvalidAssignTarget(_) => {} err => panic!("{:?}", err) }
+    match cover_error("for ([...a = 1] of b);") { cover::Error::InvalidAssignTarget(_) => {} err => panic!("{:?}", err) }
+    match cover_error("for ([...a,] in c);") { cover::Error::TrailingCommaAfterRest(_) => {} err => panic!("{:?}", err) }
+}
+
+#[test]
+fn array_rest_in_assignment() {
+    assert!(script("x = [...a,]").is_ok());
+    assert!(script("[a, ...b] = c").is_ok());
+    match cover_error("[...a,] = c") { cover::Error::TrailingCommaAfterRest(_) => {} err => panic!("{:?}", err) }
+    match cover_error("[...a = 1] = b") { cover::Error::InvalidAssignTarget(_) => {} err => panic!("{:?}", err) }
+    match cover_error("[...a, b] = c") { cover::Error::InvalidAssignTarget(_) => {} err => panic!("{:?}", err) }
+}
+
+fn is_duplicate_proto<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::DuplicateProto(_)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn duplicate_proto() {
+    for source in &["({__proto__: 1, __proto__: 2})", "x = [{__proto__: 1, \"__proto__\": 2}]"] {
+        assert!(is_duplicate_proto(script(source)), "{}", source);
+        assert!(is_duplicate_proto(strict(source)), "{}", source);
+        assert!(is_duplicate_proto(module(source)), "{}", source);
+    }
+    assert!(is_duplicate_proto(script("({x: {__proto__: 1, __proto__: 2}})")));
+    assert!(is_duplicate_proto(script("f({__proto__: 1, __proto__: 2})")));
+    assert!(is_duplicate_proto(script("async({__proto__: 1, __proto__: 2})")));
+    assert!(is_duplicate_proto(script("[x + {__proto__: 1, __proto__: 2}] = c")));
+    assert!(script("({__proto__: 1, __proto__() {}, ['__proto__']: 2})").is_ok());
+}
+
+#[test]
+fn duplicate_proto_in_patterns() {
+    assert!(script("({__proto__: a, __proto__: b} = c)").is_ok());
+    assert!(script("[{__proto__: a, __proto__: b}] = c").is_ok());
+    assert!(script("({x: {__proto__: a, __proto__: b}} = c)").is_ok());
+    assert!(script("[...{__proto__: a, __proto__: b}] = c").is_ok());
+    assert!(script("[{__proto__: a, __proto__: b} = d] = c").is_ok());
+    assert!(script("({__proto__: a, __proto__: b}) => 1").is_ok());
+    assert!(script("async ({__proto__: a, __proto__: b}) => 1").is_ok());
+    assert!(script("for ([{__proto__: a, __proto__: b}] of c);").is_ok());
+    assert!(module("({__proto__: a, __proto__: b} = c)").is_ok());
+}
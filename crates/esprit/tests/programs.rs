@@ -0,0 +1,70 @@
+extern crate esprit;
+extern crate easter;
+extern crate joker;
+
+use esprit::{script, module};
+use esprit::error::Error;
+use easter::expr::Expr;
+use easter::stmt::{ModItem, Stmt, StmtListItem};
+use joker::word::Atom;
+
+fn is_illegal_hash<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::LexError(joker::error::Error::IllegalChar('#'))) => true,
+        _ => false
+    }
+}
+
+fn is_contextual_await<T>(result: Result<T, Error>) -> bool {
+    match result {
+        Err(Error::ContextualKeyword(_, Atom::Await)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn hashbang() {
+    let body = script("#!/usr/bin/env node\nx").unwrap();
+    match body.hashbang {
+        Some(ref hashbang) => {
+            assert_eq!(hashbang.text, "/usr/bin/env node");
+            assert!(hashbang.location.is_some());
+        }
+        None => panic!("expected a hashbang")
+    }
+    match body.items[..] {
+        [StmtListItem::Stmt(Stmt::Expr(_, Expr::Id(ref id), _))] => assert_eq!(id.name.as_ref(), "x"),
+        ref items => panic!("expected a single expression statement, got {:?}", items)
+    }
+    let body = module("#!/usr/bin/env node\nexport {}").unwrap();
+    assert_eq!(body.hashbang.map(|hashbang| hashbang.text), Some(String::from("/usr/bin/env node")));
+    assert!(script("x").unwrap().hashbang.is_none());
+    assert!(is_illegal_hash(script(" #!/usr/bin/env node")));
+    assert!(is_illegal_hash(script("\n#!/usr/bin/env node")));
+    assert!(is_illegal_hash(script("x\n#!/usr/bin/env node")));
+}
+
+#[test]
+fn top_level_await() {
+    match module("await x;").map(|module| module.items.into_iter().next()) {
+        Ok(Some(ModItem::StmtListItem(StmtListItem::Stmt(Stmt::Expr(_, Expr::Await(_, ref arg), _))))) => match **arg {
+            Expr::Id(ref id) => assert_eq!(id.name.as_ref(), "x"),
+            ref expr => panic!("expected an identifier operand, got {:?}", expr)
+        },
+        result => panic!("expected an await expression, got {:?}", result)
+    }
+    match module("for await (x of y);").map(|module| module.items.into_iter().next()) {
+        Ok(Some(ModItem::StmtListItem(StmtListItem::Stmt(Stmt::ForAwait(_, _, _, _))))) => {}
+        result => panic!("expected a for-await loop, got {:?}", result)
+    }
+    assert!(is_contextual_await(module("let await;")));
+    assert!(is_contextual_await(module("function f() { await x; }")));
+    match script("await x;") {
+        Err(Error::FailedASI(_)) => {}
+        result => panic!("expected await to be an identifier in a script, got {:?}", result)
+    }
+    match script("await;").map(|script| script.items.into_iter().next()) {
+        Ok(Some(StmtListItem::Stmt(Stmt::Expr(_, Expr::Id(ref id), _)))) => assert_eq!(id.name.as_ref(), "await"),
+        result => panic!("expected an identifier, got {:?}", result)
+    }
+}
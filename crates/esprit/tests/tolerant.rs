@@ -0,0 +1,132 @@
+extern crate esprit;
+extern crate easter;
+extern crate joker;
+
+use esprit::{tolerant_script, tolerant_module};
+use esprit::error::Error;
+use easter::stmt::{Stmt, StmtListItem};
+use easter::decl::Decl;
+use easter::class::ClassMember;
+use joker::error::Error as LexError;
+
+fn is_error(item: &StmtListItem) -> bool {
+    match *item {
+        StmtListItem::Stmt(Stmt::Error(_)) => true,
+        _ => false
+    }
+}
+
+fn class_members(source: &str) -> (Vec<ClassMember>, Vec<Error>) {
+    let (mut script, errors) = tolerant_script(source).unwrap();
+    match script.items.remove(0) {
+        StmtListItem::Decl(Decl::Class(class)) => (class.body, errors),
+        item => { panic!("expected a class, got {:?}", item); }
+    }
+}
+
+#[test]
+fn statement_errors() {
+    let (script, errors) = tolerant_script("a = 1; b = ; c = 2;").unwrap();
+    assert_eq!(script.items.len(), 3);
+    assert!(!is_error(&script.items[0]));
+    assert!(is_error(&script.items[1]));
+    assert!(!is_error(&script.items[2]));
+    assert_eq!(errors.len(), 1);
+    match errors[0] {
+        Error::UnexpectedToken(_, _) => {}
+        ref error => { panic!("unexpected error {:?}", error); }
+    }
+}
+
+#[test]
+fn lex_errors() {
+    let (script, errors) = tolerant_script("a = 1; b = # c; d = 2;").unwrap();
+    assert_eq!(script.items.len(), 3);
+    assert!(is_error(&script.items[1]));
+    assert_eq!(errors, vec![Error::LexError(LexError::IllegalChar('#'))]);
+
+    // An unterminated literal ends at the end of its line.
+    let (script, errors) = tolerant_script("x = \"abc\ny = 1;\nz = /d\nw = 2;").unwrap();
+    assert_eq!(script.items.len(), 4);
+    assert!(is_error(&script.items[0]));
+    assert!(!is_error(&script.items[1]));
+    assert!(is_error(&script.items[2]));
+    assert!(!is_error(&script.items[3]));
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn unterminated_regexp_at_end() {
+    let (script, errors) = tolerant_script("x = /").unwrap();
+    assert_eq!(script.items.len(), 1);
+    assert!(is_error(&script.items[0]));
+    assert_eq!(errors, vec![Error::LexError(LexError::UnterminatedRegExp(None))]);
+
+    let (script, errors) = tolerant_script("/").unwrap();
+    assert!(script.items.iter().all(is_error));
+    assert_eq!(errors, vec![Error::LexError(LexError::UnterminatedRegExp(None))]);
+
+    let (script, errors) = tolerant_script("x = /a/;\ny = /[b").unwrap();
+    assert_eq!(script.items.len(), 2);
+    assert!(!is_error(&script.items[0]));
+    assert!(is_error(&script.items[1]));
+    assert_eq!(errors, vec![Error::LexError(LexError::UnterminatedRegExp(None))]);
+}
+
+#[test]
+fn errors_in_blocks() {
+    let (script, errors) = tolerant_script("function f() { x = ; y = 1; } z = 2;").unwrap();
+    assert_eq!(script.items.len(), 2);
+    match script.items[0] {
+        StmtListItem::Decl(Decl::Fun(ref fun)) => {
+            assert_eq!(fun.body.items.len(), 2);
+            assert!(is_error(&fun.body.items[0]));
+            assert!(!is_error(&fun.body.items[1]));
+        }
+        ref item => { panic!("expected a function, got {:?}", item); }
+    }
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn errors_in_class_bodies() {
+    let (body, errors) = class_members("class A { x = ; y = 1 }");
+    assert_eq!(body.len(), 2);
+    match body[0] { ClassMember::Error(_) => {} ref member => { panic!("expected an error, got {:?}", member); } }
+    match body[1] { ClassMember::Field(_) => {} ref member => { panic!("expected a field, got {:?}", member); } }
+    assert_eq!(errors.len(), 1);
+
+    // A member without a `;` ends at the next line.
+    let (body, errors) = class_members("class A {\n  x = )\n  m() {}\n}\nz = 2;");
+    assert_eq!(body.len(), 2);
+    match body[1] { ClassMember::Method(_) => {} ref member => { panic!("expected a method, got {:?}", member); } }
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn unbalanced_braces() {
+    // A block left open at the end of the input keeps its statements.
+    let (script, errors) = tolerant_script("if (x) { a = 1;\nb = 2;").unwrap();
+    assert_eq!(script.items.len(), 1);
+    match script.items[0] {
+        StmtListItem::Stmt(Stmt::If(_, _, ref body, _)) => match **body {
+            Stmt::Block(ref block) => { assert_eq!(block.items.len(), 2); }
+            ref stmt => { panic!("expected a block, got {:?}", stmt); }
+        },
+        ref item => { panic!("expected an if statement, got {:?}", item); }
+    }
+    assert_eq!(errors.len(), 1);
+
+    // Skipping an unclosed `{` stops at the `}` of the enclosing block.
+    let (script, errors) = tolerant_script("function f() {\n  if x {\n  y = 1;\n}\nz = 2;").unwrap();
+    assert_eq!(script.items.len(), 2);
+    assert!(!is_error(&script.items[1]));
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn module_errors() {
+    let (module, errors) = tolerant_module("import a from 'a';\nexport let = 1;\nexport const b = 2;").unwrap();
+    assert_eq!(module.items.len(), 3);
+    assert_eq!(errors.len(), 1);
+}
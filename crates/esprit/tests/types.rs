@@ -0,0 +1,300 @@
+extern crate esprit;
+extern crate easter;
+
+use esprit::Parser;
+use esprit::error::Error;
+use esprit::result::Result;
+use easter::class::ClassMember;
+use easter::decl::{Decl, Dtor};
+use easter::expr::Expr;
+use easter::fun::Fun;
+use easter::stmt::{Script, Stmt, StmtListItem};
+use easter::ty::{Type, TypeMember, TypeName};
+
+fn typed(s: &str) -> Result<Script> {
+    let mut parser = Parser::from_source(s);
+    parser.types = true;
+    parser.script(false)
+}
+
+fn item(source: &str) -> StmtListItem {
+    match typed(source).map(|script| script.items.into_iter().next()) {
+        Ok(Some(item)) => item,
+        result => panic!("expected an item in {:?}, got {:?}", source, result)
+    }
+}
+
+fn declaration(source: &str) -> Decl {
+    match item(source) {
+        StmtListItem::Decl(decl) => decl,
+        item => panic!("expected a declaration in {:?}, got {:?}", source, item)
+    }
+}
+
+fn expression(source: &str) -> Expr {
+    match item(source) {
+        StmtListItem::Stmt(Stmt::Expr(_, expr, _)) => expr,
+        item => panic!("expected an expression statement in {:?}, got {:?}", source, item)
+    }
+}
+
+// The right-hand side of an assignment statement.
+fn assigned(source: &str) -> Expr {
+    match expression(source) {
+        Expr::Assign(_, _, value) => *value,
+        expr => panic!("expected an assignment in {:?}, got {:?}", source, expr)
+    }
+}
+
+fn arrow(expr: &Expr) -> &Fun {
+    match *expr {
+        Expr::Fun(ref fun) => fun,
+        ref expr => panic!("expected an arrow function, got {:?}", expr)
+    }
+}
+
+// The name of a type reference without a qualifier.
+fn ref_name(ty: &Type) -> &str {
+    match *ty {
+        Type::Ref(_, TypeName::Id(ref id), _) => id.name.as_ref(),
+        ref ty => panic!("expected a type reference, got {:?}", ty)
+    }
+}
+
+fn is_unexpected_token<T>(result: Result<T>) -> bool {
+    match result {
+        Err(Error::UnexpectedToken(_, _)) => true,
+        _ => false
+    }
+}
+
+#[test]
+fn annotations() {
+    let fun = match declaration("function f(a: number, b?: string, ...c: T[]): void {}") {
+        Decl::Fun(fun) => fun,
+        decl => panic!("expected a function, got {:?}", decl)
+    };
+    let annots: Vec<(bool, &Type)> = fun.params.types.iter().map(|annot| match *annot {
+        Some(ref annot) => (annot.optional, annot.ty.as_ref().unwrap()),
+        None => panic!("expected every parameter to be annotated")
+    }).collect();
+    match annots[..] {
+        [(false, a), (true, b), (false, &Type::Array(_, ref c))] => {
+            assert_eq!((ref_name(a), ref_name(b), ref_name(c)), ("number", "string", "T"));
+        }
+        ref annots => panic!("unexpected parameter annotations {:?}", annots)
+    }
+    match fun.ret {
+        Some(Type::Void(_)) => {}
+        ret => panic!("expected a void return type, got {:?}", ret)
+    }
+    match declaration("let b: { x: T, y?: U }, c = 1;") {
+        Decl::Let(_, ref dtors, _) => match dtors[..] {
+            [Dtor::Simple(_, _, Some(Type::Object(_, ref members)), None), Dtor::Simple(_, _, None, Some(_))] => {
+                assert_eq!(members.len(), 2);
+            }
+            ref dtors => panic!("unexpected declarators {:?}", dtors)
+        },
+        decl => panic!("expected a let declaration, got {:?}", decl)
+    }
+    let value = assigned("f = (a, b): T => a;");
+    let fun = arrow(&value);
+    assert_eq!(fun.params.types, vec![None, None]);
+    assert_eq!(fun.ret.as_ref().map(ref_name), Some("T"));
+    match esprit::script("var a: number = 1;") {
+        Err(Error::FailedASI(_)) => {}
+        result => panic!("expected annotations to be rejected, got {:?}", result)
+    }
+}
+
+#[test]
+fn declarations() {
+    match declaration("interface A<T> extends B<T>, C { [k: string]: T; (): void; readonly x?: number; f(a: T): U; }") {
+        Decl::Interface(ref interface) => {
+            assert_eq!(interface.type_params.len(), 1);
+            let extends: Vec<&str> = interface.extends.iter().map(ref_name).collect();
+            assert_eq!(extends, vec!["B", "C"]);
+            match interface.body[..] {
+                [TypeMember::Index(_, _, _, _), TypeMember::Call(_), TypeMember::Prop(ref prop), TypeMember::Method(_, _, false, ref method)] => {
+                    assert!(prop.readonly && prop.optional);
+                    assert_eq!(method.params.len(), 1);
+                }
+                ref members => panic!("unexpected interface members {:?}", members)
+            }
+        }
+        decl => panic!("expected an interface, got {:?}", decl)
+    }
+    match declaration("type A = B | C<D> & E;") {
+        Decl::TypeAlias(ref alias) => match alias.ty {
+            Type::Union(_, ref types) => match types[..] {
+                [ref b, Type::Intersection(_, ref types)] => {
+                    assert_eq!(ref_name(b), "B");
+                    match types[0] {
+                        Type::Ref(_, _, ref args) => assert_eq!(args.len(), 1),
+                        ref ty => panic!("expected a generic type, got {:?}", ty)
+                    }
+                }
+                ref types => panic!("unexpected union members {:?}", types)
+            },
+            ref ty => panic!("expected a union, got {:?}", ty)
+        },
+        decl => panic!("expected a type alias, got {:?}", decl)
+    }
+    match declaration("type F<T> = (a: T) => T;") {
+        Decl::TypeAlias(ref alias) => match alias.ty {
+            Type::Fun(_, ref fun) => assert_eq!(fun.ret.as_ref().map(ref_name), Some("T")),
+            ref ty => panic!("expected a function type, got {:?}", ty)
+        },
+        decl => panic!("expected a type alias, got {:?}", decl)
+    }
+    assert!(is_unexpected_token(typed("type A = ;")));
+    assert!(is_unexpected_token(typed("interface A { x: }")));
+}
+
+#[test]
+fn generics() {
+    match declaration("function f<T, U extends T = T>(a: T): U {}") {
+        Decl::Fun(ref fun) => match fun.type_params[..] {
+            [ref t, ref u] => {
+                assert_eq!((t.id.name.as_ref(), u.id.name.as_ref()), ("T", "U"));
+                assert!(t.constraint.is_none() && u.constraint.is_some() && u.default.is_some());
+            }
+            ref params => panic!("unexpected type parameters {:?}", params)
+        },
+        decl => panic!("expected a function, got {:?}", decl)
+    }
+    for source in &["f<T>(a);", "new f<T>(a);"] {
+        match expression(source) {
+            Expr::Call(_, ref callee, _) | Expr::New(_, ref callee, _) => match **callee {
+                Expr::TypeArgs(_, _, ref args) => assert_eq!(args.len(), 1),
+                ref expr => panic!("expected type arguments in {:?}, got {:?}", source, expr)
+            },
+            expr => panic!("expected a call in {:?}, got {:?}", source, expr)
+        }
+    }
+    assert_eq!(arrow(&assigned("f = <T>(a: T) => a;")).type_params.len(), 1);
+    assert!(arrow(&assigned("f = async <T>(a: T): T => a;")).is_async);
+    assert!(typed("async<T>(a);").is_ok());
+    match expression("a < b > c;") {
+        Expr::Binop(_, _, ref left, _) => match **left {
+            Expr::Binop(_, _, _, _) => {}
+            ref expr => panic!("expected a comparison, got {:?}", expr)
+        },
+        expr => panic!("expected a comparison, got {:?}", expr)
+    }
+    assert!(is_unexpected_token(typed("function f<>() {}")));
+}
+
+#[test]
+fn assertions() {
+    match assigned("x = a as T;") {
+        Expr::As(_, _, ref ty) => assert_eq!(ref_name(ty), "T"),
+        expr => panic!("expected a type assertion, got {:?}", expr)
+    }
+    match assigned("x = a!.b;") {
+        Expr::Dot(_, ref object, _) => match **object {
+            Expr::NonNull(_, _) => {}
+            ref expr => panic!("expected a non-null assertion, got {:?}", expr)
+        },
+        expr => panic!("expected a member expression, got {:?}", expr)
+    }
+    match assigned("x = a!;") {
+        Expr::NonNull(_, _) => {}
+        expr => panic!("expected a non-null assertion, got {:?}", expr)
+    }
+    match assigned("x = [1, 2] as const;") {
+        Expr::As(_, ref expr, Type::Const(_)) => match **expr {
+            Expr::Arr(_, _) => {}
+            ref expr => panic!("expected an array, got {:?}", expr)
+        },
+        expr => panic!("expected a const assertion, got {:?}", expr)
+    }
+    match assigned("x = a as const as T;") {
+        Expr::As(_, ref expr, ref ty) => {
+            assert_eq!(ref_name(ty), "T");
+            match **expr {
+                Expr::As(_, _, Type::Const(_)) => {}
+                ref expr => panic!("expected a const assertion, got {:?}", expr)
+            }
+        }
+        expr => panic!("expected a type assertion, got {:?}", expr)
+    }
+    assert!(is_unexpected_token(typed("let x: const;")));
+    match esprit::script("x = a as T;") {
+        Err(Error::FailedASI(_)) => {}
+        result => panic!("expected assertions to be rejected, got {:?}", result)
+    }
+}
+
+#[test]
+fn conditional_types() {
+    match declaration("type A<T> = T | U extends string ? 'a' : T extends number ? 1 : never;") {
+        Decl::TypeAlias(ref alias) => match alias.ty {
+            Type::Conditional(_, ref check, ref extends, ref cons, ref alt) => {
+                match **check {
+                    Type::Union(_, ref types) => assert_eq!(types.len(), 2),
+                    ref ty => panic!("expected a union check type, got {:?}", ty)
+                }
+                assert_eq!(ref_name(extends), "string");
+                match (&**cons, &**alt) {
+                    (&Type::String(_, _), &Type::Conditional(_, _, _, _, ref never)) => assert_eq!(ref_name(never), "never"),
+                    types => panic!("unexpected branches {:?}", types)
+                }
+            }
+            ref ty => panic!("expected a conditional type, got {:?}", ty)
+        },
+        decl => panic!("expected a type alias, got {:?}", decl)
+    }
+    match declaration("function f<T extends A extends B ? C : D>() {}") {
+        Decl::Fun(ref fun) => match fun.type_params[0].constraint {
+            Some(Type::Conditional(_, _, _, _, _)) => {}
+            ref ty => panic!("expected a conditional constraint, got {:?}", ty)
+        },
+        decl => panic!("expected a function, got {:?}", decl)
+    }
+    assert!(typed("type A = B extends (x: C) => D ? E : F;").is_ok());
+    assert!(is_unexpected_token(typed("type A = B extends C ? D;")));
+    assert!(is_unexpected_token(typed("let x: A\nextends B ? C : D;")));
+}
+
+#[test]
+fn conditional_consequent() {
+    match assigned("x = a ? (b) : c => d;") {
+        Expr::Cond(_, _, ref cons, ref alt) => {
+            match **cons {
+                Expr::Id(_) => {}
+                ref expr => panic!("expected a parenthesized consequent, got {:?}", expr)
+            }
+            assert!(arrow(alt).ret.is_none());
+        }
+        expr => panic!("expected a conditional, got {:?}", expr)
+    }
+    assert!(typed("x = a ? (b) : c;").is_ok());
+    match assigned("x = a ? (b): T => d : c;") {
+        Expr::Cond(_, _, ref cons, _) => assert_eq!(arrow(cons).ret.as_ref().map(ref_name), Some("T")),
+        expr => panic!("expected a conditional, got {:?}", expr)
+    }
+}
+
+#[test]
+fn classes() {
+    match declaration("class A<T> extends B<T> implements C, D<T> { x: number; y?: string = 'a'; static z: T; }") {
+        Decl::Class(ref class) => {
+            assert_eq!(class.type_params.len(), 1);
+            let implements: Vec<&str> = class.implements.iter().map(ref_name).collect();
+            assert_eq!(implements, vec!["C", "D"]);
+            let optional: Vec<bool> = class.body.iter().map(|member| match *member {
+                ClassMember::Field(ref field) => field.annot.as_ref().unwrap().optional,
+                ref member => panic!("expected a field, got {:?}", member)
+            }).collect();
+            assert_eq!(optional, vec![false, true, false]);
+        }
+        decl => panic!("expected a class, got {:?}", decl)
+    }
+    assert!(typed("x = class<T> implements C {};").is_ok());
+    match esprit::script("class A { x: number; }") {
+        Err(Error::FailedASI(_)) => {}
+        result => panic!("expected field annotations to be rejected, got {:?}", result)
+    }
+    assert!(is_unexpected_token(esprit::script("class A implements C {}")));
+}
@@ -0,0 +1,88 @@
+use easter::class::{Class, ClassMember, Field, Method, MethodKind};
+use easter::stmt::Script;
+use easter::fun::FunctionKind;
+use unjson::ty::Object;
+use unjson::{ExtractField, Unjson};
+
+use tag::{Tag, TagOf};
+use result::{Map, Result};
+use error::{Error, string_error, node_type_error};
+use node::ExtractNode;
+use obj::IntoObj;
+use fun::IntoFun;
+
+pub trait IntoClass {
+    fn into_class(self) -> Result<Class>;
+    fn into_class_member(self) -> Result<ClassMember>;
+}
+
+impl IntoClass for Object {
+    fn into_class(mut self) -> Result<Class> {
+        let id = self.extract_id_opt("id")?;
+        let extends = self.extract_expr_opt("superClass")?.map(Box::new);
+        let mut body = self.extract_object("body")?;
+        let tag = body.tag()?;
+        if tag != Tag::ClassBody {
+            return node_type_error("class body", tag);
+        }
+        let list = body.extract_array("body")?;
+        let objs = list.map(|v| v.into_object().map_err(Error::Json))?;
+        Ok(Class {
+            location: None,
+            decorators: vec![],
+            id: id,
+            type_params: vec![],
+            extends: extends,
+            implements: vec![],
+            body: objs.map(|o| o.into_class_member())?
+        })
+    }
+
+    fn into_class_member(mut self) -> Result<ClassMember> {
+        let tag = self.tag()?;
+        match tag {
+            Tag::MethodDefinition => {}
+            Tag::PropertyDefinition => {
+                let computed = self.computed();
+                let key = self.extract_object("key")?.into_prop_key(computed)?;
+                return Ok(ClassMember::Field(Field {
+                    location: None,
+                    decorators: vec![],
+                    is_static: self.extract_bool("static")?,
+                    key: key,
+                    annot: None,
+                    value: self.extract_expr_opt("value")?
+                }));
+            }
+            Tag::StaticBlock => {
+                return Ok(ClassMember::StaticBlock(None, Script {
+                    location: None,
+                    hashbang: None,
+                    dirs: vec![],
+                    items: self.extract_stmt_list("body")?
+                }));
+            }
+            _ => { return node_type_error("class member", tag); }
+        }
+        let computed = self.computed();
+        let key = self.extract_object("key")?.into_prop_key(computed)?;
+        let is_static = self.extract_bool("static")?;
+        let kind = self.extract_string("kind")?;
+        let kind = match &kind[..] {
+            "constructor" => MethodKind::Constructor,
+            "method"      => MethodKind::Method,
+            "get"         => MethodKind::Get,
+            "set"         => MethodKind::Set,
+            _ => { return string_error("'constructor', 'method', 'get', or 'set'", kind); }
+        };
+        let fun = self.extract_object("value")?.into_fun(FunctionKind::Anonymous)?;
+        Ok(ClassMember::Method(Method {
+            location: None,
+            decorators: vec![],
+            is_static: is_static,
+            kind: kind,
+            key: key,
+            fun: fun
+        }))
+    }
+}
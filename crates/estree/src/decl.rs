@@ -26,13 +26,13 @@ impl IntoConst for Vec<Dtor> {
     fn into_const(self) -> Result<Vec<ConstDtor>> {
         self.into_iter().map(|dtor| {
             Ok(match dtor {
-                Dtor::Simple(_, id, Some(expr)) => {
+                Dtor::Simple(_, id, _, Some(expr)) => {
                     ConstDtor::from_simple_init(id, expr)
                 }
-                Dtor::Simple(_, id, None) => {
+                Dtor::Simple(_, id, _, None) => {
                     return Err(Error::UninitializedPattern(Patt::Simple(id)));
                 }
-                Dtor::Compound(_, compound, expr) => {
+                Dtor::Compound(_, compound, _, expr) => {
                     ConstDtor::from_compound_init(compound, expr)
                 }
             })
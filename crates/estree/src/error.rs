@@ -41,7 +41,7 @@ impl Display for Error {
                 let ty = match *patt {
                     Patt::Assign(_, _, _) => "assignment",
                     Patt::Compound(CompoundPatt::Arr(_, _, _)) => "array",
-                    Patt::Compound(CompoundPatt::Obj(_, _)) => "object",
+                    Patt::Compound(CompoundPatt::Obj(_, _, _)) => "object",
                     Patt::Simple(_) => "constant"
                 };
                 fmt.write_fmt(format_args!("uninitialized {} pattern in declarator", ty))
@@ -1,27 +1,35 @@
+use std::borrow::Cow;
+
 use serde_json::value::Value;
-use easter::expr::{Expr, ExprListItem, Assign};
+use easter::expr::{Expr, ExprListItem, Assign, Template, TemplateElement};
 use easter::fun::{FunctionKind};
 use easter::obj::DotKey;
 use easter::id::IdExt;
 use easter::punc::{Unop, Binop, Assop, Logop};
 use unjson::ty::{Object, TyOf};
 use unjson::{ExtractField, Unjson};
-use joker::token::RegExpLiteral;
+use joker::token::{RegExpLiteral, BigIntLiteral};
 
 use tag::{Tag, TagOf};
 use id::IntoId;
 use patt::IntoPatt;
-use result::Result;
-use error::{string_error, node_type_error, type_error};
+use result::{Result, Map};
+use error::{Error, string_error, node_type_error, type_error};
 use node::ExtractNode;
 use fun::IntoFun;
+use class::IntoClass;
+use obj::IntoObj;
 use lit::{IntoStringLiteral, IntoNumberLiteral};
+use jsx::IntoJSX;
 
 pub trait IntoExpr {
     fn into_expr(self) -> Result<Expr>;
     fn into_assignable_expr(self) -> Result<Expr>;
     fn into_expr_list_item(self) -> Result<ExprListItem>;
     fn into_lit(self) -> Result<Expr>;
+    fn into_template(self) -> Result<Template>;
+    fn into_template_element(self) -> Result<TemplateElement>;
+    fn optional(&self) -> bool;
 }
 
 impl IntoExpr for Object {
@@ -36,9 +44,13 @@ impl IntoExpr for Object {
                     Ok(op) => op,
                     Err(_) => { return string_error("binary operator", str); }
                 };
-                let left = self.extract_expr("left")?;
+                let left = self.extract_object("left")?;
                 let right = self.extract_expr("right")?;
-                Expr::Binop(None, op, Box::new(left), Box::new(right))
+                if left.tag()? == Tag::PrivateIdentifier {
+                    Expr::PrivateIn(None, left.into_private_name()?, Box::new(right))
+                } else {
+                    Expr::Binop(None, op, Box::new(left.into_expr()?), Box::new(right))
+                }
             }
             Tag::AssignmentExpression => {
                 let str = self.extract_string("operator")?;
@@ -93,20 +105,55 @@ impl IntoExpr for Object {
                 }
             }
             Tag::MemberExpression => {
-                let obj = Box::new(self.extract_expr("object")?);
+                let obj = self.extract_object("object")?;
+                let is_super = obj.tag()? == Tag::Super;
+                let optional = self.optional();
                 if self.extract_bool("computed")? {
                     let prop = Box::new(self.extract_expr("property")?);
-                    Expr::Brack(None, obj, prop)
+                    if is_super {
+                        Expr::SuperBrack(None, prop)
+                    } else if optional {
+                        Expr::OptionalBrack(None, Box::new(obj.into_expr()?), prop)
+                    } else {
+                        Expr::Brack(None, Box::new(obj.into_expr()?), prop)
+                    }
                 } else {
-                    let id = self.extract_object("property")?.into_id()?;
-                    let key = DotKey { location: None, value: id.name.into_string() };
-                    Expr::Dot(None, obj, key)
+                    let prop = self.extract_object("property")?;
+                    if prop.tag()? == Tag::PrivateIdentifier {
+                        let key = prop.into_private_name()?;
+                        return Ok(if optional {
+                            Expr::OptionalPrivateDot(None, Box::new(obj.into_expr()?), key)
+                        } else {
+                            Expr::PrivateDot(None, Box::new(obj.into_expr()?), key)
+                        });
+                    }
+                    let id = prop.into_id()?;
+                    let key = DotKey { location: None, value: id.name };
+                    if is_super {
+                        Expr::SuperDot(None, key)
+                    } else if optional {
+                        Expr::OptionalDot(None, Box::new(obj.into_expr()?), key)
+                    } else {
+                        Expr::Dot(None, Box::new(obj.into_expr()?), key)
+                    }
                 }
             }
             Tag::CallExpression => {
-                let callee = Box::new(self.extract_expr("callee")?);
-                let args = self.extract_expr_list("arguments")?;
-                Expr::Call(None, callee, args)
+                let callee = self.extract_object("callee")?;
+                let mut args = self.extract_expr_list("arguments")?;
+                match callee.tag()? {
+                    Tag::Super => Expr::SuperCall(None, args),
+                    // Older versions of Esprima represent `import(x)` as a call.
+                    Tag::Import => match args.pop() {
+                        Some(ExprListItem::Expr(source)) if args.is_empty() => Expr::Import(None, Box::new(source)),
+                        _ => { return node_type_error("import call with a single argument", Tag::CallExpression); }
+                    },
+                    _ if self.optional() => Expr::OptionalCall(None, Box::new(callee.into_expr()?), args),
+                    _ => Expr::Call(None, Box::new(callee.into_expr()?), args)
+                }
+            }
+            Tag::ChainExpression => {
+                Expr::Chain(None, Box::new(self.extract_expr("expression")?))
             }
             Tag::NewExpression => {
                 let callee = Box::new(self.extract_expr("callee")?);
@@ -143,13 +190,35 @@ impl IntoExpr for Object {
                 let alt = Box::new(self.extract_expr("alternate")?);
                 Expr::Cond(None, test, cons, alt)
             }
+            Tag::ClassExpression => Expr::Class(self.into_class()?),
+            Tag::TemplateLiteral => Expr::Template(self.into_template()?),
+            Tag::TaggedTemplateExpression => {
+                let tag = Box::new(self.extract_expr("tag")?);
+                let quasi = self.extract_object("quasi")?.into_template()?;
+                Expr::TaggedTemplate(None, tag, quasi)
+            }
+            Tag::JSXElement => Expr::JSXElement(self.into_jsx_element()?),
+            Tag::JSXFragment => Expr::JSXFragment(self.into_jsx_fragment()?),
             Tag::ThisExpression => Expr::This(None),
+            Tag::AwaitExpression => {
+                let arg = self.extract_expr("argument")?;
+                Expr::Await(None, Box::new(arg))
+            }
+            Tag::YieldExpression => {
+                if self.extract_bool("delegate")? {
+                    Expr::YieldDelegate(None, Box::new(self.extract_expr("argument")?))
+                } else {
+                    Expr::Yield(None, self.extract_expr_opt("argument")?.map(Box::new))
+                }
+            }
+            Tag::ImportExpression => Expr::Import(None, Box::new(self.extract_expr("source")?)),
             Tag::MetaProperty => {
                 let meta = self.extract_id("meta")?.name;
                 let prop = self.extract_id("property")?.name;
                 match (meta.as_ref(), prop.as_ref()) {
                     ("new", "target") => Expr::NewTarget(None),
-                    (meta, prop) => { return string_error("new.target", format!("{}.{}", meta, prop)); }
+                    ("import", "meta") => Expr::ImportMeta(None),
+                    (meta, prop) => { return string_error("new.target or import.meta", format!("{}.{}", meta, prop)); }
                 }
             }
             _ => { return node_type_error("expression", tag); }
@@ -168,30 +237,70 @@ impl IntoExpr for Object {
     fn into_expr_list_item(mut self) -> Result<ExprListItem> {
         match self.tag()? {
             Tag::SpreadElement => {
-                Ok(ExprListItem::Spread(None, self.extract_expr("argument")?))
+                Ok(ExprListItem::Spread(None, self.extract_expr("argument")?, false))
             }
             _ => self.into_expr().map(ExprListItem::Expr)
         }
     }
 
     fn into_lit(mut self) -> Result<Expr> {
+        let raw = if self.contains_key("raw") { Some(Cow::Owned(self.extract_string("raw")?)) } else { None };
+        // The value of a BigInt literal may be null where BigInts are unsupported.
+        if self.contains_key("bigint") {
+            let value = self.extract_string("bigint")?;
+            return Ok(Expr::BigInt(None, BigIntLiteral { source: None, raw: raw, value: value }));
+        }
         let json = self.extract_field("value")?;
         Ok(match json {
             Value::Null if !self.contains_key("regex") => Expr::Null(None),
             Value::Bool(true) => Expr::True(None),
             Value::Bool(false) => Expr::False(None),
-            Value::String(value) => Expr::String(None, value.into_string_literal()),
-            Value::Number(val) => Expr::Number(None, val.into_number_literal()),
+            Value::String(value) => {
+                let mut literal = value.into_string_literal();
+                literal.source = raw;
+                Expr::String(None, literal)
+            }
+            Value::Number(val) => {
+                let mut literal = val.into_number_literal();
+                literal.raw = raw;
+                Expr::Number(None, literal)
+            }
             Value::Null | Value::Object(_) => {
                 let mut regex = self.extract_object("regex")?;
                 let pattern = regex.extract_string("pattern")?;
                 let flags = regex.extract_string("flags")?;
                 Expr::RegExp(None, RegExpLiteral {
-                    pattern: pattern,
-                    flags: flags.chars().collect()
+                    pattern: Cow::Owned(pattern),
+                    flags: Cow::Owned(flags),
+                    raw: raw
                 })
             }
             _ => { return type_error("null, number, boolean, string, or object", json.ty()); }
         })
     }
+
+    fn into_template(mut self) -> Result<Template> {
+        let quasis = self.extract_array("quasis")?;
+        let quasis = quasis.map(|v| v.into_object().map_err(Error::Json))?;
+        let quasis = quasis.map(|o| o.into_template_element())?;
+        let exprs = self.extract_exprs("expressions")?;
+        Ok(Template { location: None, quasis: quasis, exprs: exprs })
+    }
+
+    fn into_template_element(mut self) -> Result<TemplateElement> {
+        match self.tag()? {
+            Tag::TemplateElement => {
+                let mut value = self.extract_object("value")?;
+                let cooked = value.extract_string_opt("cooked")?;
+                let raw = value.extract_string("raw")?;
+                Ok(TemplateElement { location: None, cooked: cooked, raw: raw })
+            }
+            tag => node_type_error("template element", tag)
+        }
+    }
+
+    // Only the links of an optional chain carry the flag.
+    fn optional(&self) -> bool {
+        self.get("optional").and_then(|b| b.as_bool()) == Some(true)
+    }
 }
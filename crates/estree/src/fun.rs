@@ -23,6 +23,7 @@ impl IntoFun for Object {
             (FunctionKind::Anonymous, Some(true)) => FunctionKind::AnonymousGenerator,
             (r @ _, _) => r
         };
+        let is_async = self.extract_bool_opt("async")?.unwrap_or(false);
 
         let params = self.extract_params("params")?;
         let mut obj = self.extract_object("body")?;
@@ -30,13 +31,17 @@ impl IntoFun for Object {
         Ok(Fun {
             location: None,
             kind: kind,
+            is_async: is_async,
+            type_params: vec![],
             params: params,
+            ret: None,
             body: body,
             body_expr: None
         })
     }
 
     fn into_arrow_function(mut self) -> Result<Fun> {
+        let is_async = self.extract_bool_opt("async")?.unwrap_or(false);
         let params = self.extract_params("params")?;
         let mut obj = self.extract_object("body")?;
         match obj.tag()? {
@@ -45,7 +50,10 @@ impl IntoFun for Object {
                 Ok(Fun {
                     location: None,
                     kind: FunctionKind::Arrow,
+                    is_async: is_async,
+                    type_params: vec![],
                     params: params,
+                    ret: None,
                     body: body,
                     body_expr: None
                 })
@@ -55,7 +63,10 @@ impl IntoFun for Object {
                 Ok(Fun {
                     location: None,
                     kind: FunctionKind::Arrow,
+                    is_async: is_async,
+                    type_params: vec![],
                     params: params,
+                    ret: None,
                     body: empty_script(),
                     body_expr: Some(Box::new(expr))
                 })
@@ -0,0 +1,142 @@
+use easter::jsx::{JSXAttr, JSXAttrValue, JSXChild, JSXElement, JSXFragment, JSXId, JSXName};
+use joker::token::JSXText;
+use unjson::ty::Object;
+use unjson::{ExtractField, Unjson};
+
+use tag::{Tag, TagOf};
+use result::{Result, Map};
+use error::{Error, node_type_error};
+use node::ExtractNode;
+use expr::IntoExpr;
+use lit::IntoStringLiteral;
+
+pub trait IntoJSX {
+    fn into_jsx_element(self) -> Result<JSXElement>;
+    fn into_jsx_fragment(self) -> Result<JSXFragment>;
+    fn into_jsx_name(self) -> Result<JSXName>;
+    fn into_jsx_id(self) -> Result<JSXId>;
+    fn into_jsx_attr(self) -> Result<JSXAttr>;
+    fn into_jsx_attr_value(self) -> Result<JSXAttrValue>;
+    fn into_jsx_child(self) -> Result<JSXChild>;
+    fn extract_jsx_children(&mut self, &'static str) -> Result<Vec<JSXChild>>;
+}
+
+impl IntoJSX for Object {
+    fn into_jsx_element(mut self) -> Result<JSXElement> {
+        let tag = self.tag()?;
+        if tag != Tag::JSXElement {
+            return node_type_error("JSX element", tag);
+        }
+        let mut opening = self.extract_object("openingElement")?;
+        let tag = opening.tag()?;
+        if tag != Tag::JSXOpeningElement {
+            return node_type_error("JSX opening element", tag);
+        }
+        let name = opening.extract_object("name")?.into_jsx_name()?;
+        let attrs = opening.extract_array("attributes")?;
+        let attrs = attrs.map(|v| v.into_object().map_err(Error::Json))?;
+        let children = if opening.extract_bool("selfClosing")? {
+            None
+        } else {
+            Some(self.extract_jsx_children("children")?)
+        };
+        Ok(JSXElement {
+            location: None,
+            name: name,
+            attrs: attrs.map(|o| o.into_jsx_attr())?,
+            children: children
+        })
+    }
+
+    fn into_jsx_fragment(mut self) -> Result<JSXFragment> {
+        let tag = self.tag()?;
+        if tag != Tag::JSXFragment {
+            return node_type_error("JSX fragment", tag);
+        }
+        Ok(JSXFragment {
+            location: None,
+            children: self.extract_jsx_children("children")?
+        })
+    }
+
+    fn into_jsx_name(mut self) -> Result<JSXName> {
+        let tag = self.tag()?;
+        Ok(match tag {
+            Tag::JSXIdentifier => JSXName::Id(self.into_jsx_id()?),
+            Tag::JSXNamespacedName => {
+                let namespace = self.extract_object("namespace")?.into_jsx_id()?;
+                let name = self.extract_object("name")?.into_jsx_id()?;
+                JSXName::Namespaced(None, namespace, name)
+            }
+            Tag::JSXMemberExpression => {
+                let object = self.extract_object("object")?.into_jsx_name()?;
+                let property = self.extract_object("property")?.into_jsx_id()?;
+                JSXName::Member(None, Box::new(object), property)
+            }
+            _ => { return node_type_error("JSX name", tag); }
+        })
+    }
+
+    fn into_jsx_id(mut self) -> Result<JSXId> {
+        let tag = self.tag()?;
+        if tag != Tag::JSXIdentifier {
+            return node_type_error("JSX identifier", tag);
+        }
+        Ok(JSXId { location: None, name: self.extract_string("name")? })
+    }
+
+    fn into_jsx_attr(mut self) -> Result<JSXAttr> {
+        let tag = self.tag()?;
+        Ok(match tag {
+            Tag::JSXAttribute => {
+                let name = self.extract_object("name")?.into_jsx_name()?;
+                let value = match self.extract_object_opt("value")? {
+                    Some(value) => Some(value.into_jsx_attr_value()?),
+                    None => None
+                };
+                JSXAttr::Regular(None, name, value)
+            }
+            Tag::JSXSpreadAttribute => JSXAttr::Spread(None, self.extract_expr("argument")?),
+            _ => { return node_type_error("JSX attribute", tag); }
+        })
+    }
+
+    fn into_jsx_attr_value(mut self) -> Result<JSXAttrValue> {
+        let tag = self.tag()?;
+        Ok(match tag {
+            Tag::Literal => JSXAttrValue::String(None, self.extract_string("value")?.into_string_literal()),
+            Tag::JSXExpressionContainer => JSXAttrValue::Expr(None, self.extract_expr("expression")?),
+            Tag::JSXElement => JSXAttrValue::Element(self.into_jsx_element()?),
+            Tag::JSXFragment => JSXAttrValue::Fragment(self.into_jsx_fragment()?),
+            _ => { return node_type_error("JSX attribute value", tag); }
+        })
+    }
+
+    fn into_jsx_child(mut self) -> Result<JSXChild> {
+        let tag = self.tag()?;
+        Ok(match tag {
+            Tag::JSXText => {
+                let value = self.extract_string("value")?;
+                let raw = self.extract_string("raw")?;
+                JSXChild::Text(None, JSXText { value: value, raw: raw })
+            }
+            Tag::JSXExpressionContainer => {
+                let expr = self.extract_object("expression")?;
+                match expr.tag()? {
+                    Tag::JSXEmptyExpression => JSXChild::Expr(None, None),
+                    _ => JSXChild::Expr(None, Some(expr.into_expr()?))
+                }
+            }
+            Tag::JSXSpreadChild => JSXChild::Spread(None, self.extract_expr("expression")?),
+            Tag::JSXElement => JSXChild::Element(self.into_jsx_element()?),
+            Tag::JSXFragment => JSXChild::Fragment(self.into_jsx_fragment()?),
+            _ => { return node_type_error("JSX child", tag); }
+        })
+    }
+
+    fn extract_jsx_children(&mut self, name: &'static str) -> Result<Vec<JSXChild>> {
+        let list = self.extract_array(name)?;
+        let objs = list.map(|v| v.into_object().map_err(Error::Json))?;
+        objs.map(|o| o.into_jsx_child())
+    }
+}
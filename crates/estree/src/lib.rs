@@ -15,14 +15,17 @@ mod fun;
 mod patt;
 mod obj;
 mod decl;
+mod class;
+mod module;
 mod prog;
 mod lit;
+mod jsx;
 
 use serde::de::Error;
 use serde::de::{Deserialize, Deserializer};
 use easter::stmt::Script;
 use unjson::ty::Object;
-pub use prog::IntoScript;
+pub use prog::{IntoScript, IntoModule};
 
 pub struct ESTreeScript(Script);
 
@@ -1,25 +1,26 @@
+use std::borrow::Cow;
 use serde_json::Number;
 use joker::token::{StringLiteral, NumberLiteral, NumberSource};
 
 pub trait IntoStringLiteral {
-    fn into_string_literal(self) -> StringLiteral;
+    fn into_string_literal(self) -> StringLiteral<'static>;
 }
 
 impl IntoStringLiteral for String {
-    fn into_string_literal(self) -> StringLiteral {
+    fn into_string_literal(self) -> StringLiteral<'static> {
         StringLiteral {
             source: None,
-            value: self
+            value: Cow::Owned(self)
         }
     }
 }
 
 pub trait IntoNumberLiteral {
-    fn into_number_literal(self) -> NumberLiteral;
+    fn into_number_literal(self) -> NumberLiteral<'static>;
 }
 
 impl IntoNumberLiteral for Number {
-    fn into_number_literal(self) -> NumberLiteral {
+    fn into_number_literal(self) -> NumberLiteral<'static> {
         // By definition of Serde_json, numbers are either f64, u64 or i64.
         // Unfortunately, we cannot pattern match on that.
         let value = if self.is_f64() {
@@ -34,33 +35,37 @@ impl IntoNumberLiteral for Number {
 
         NumberLiteral {
             source: Some(NumberSource::DecimalInt(self.to_string(), None)),
+            raw: None,
             value
         }
     }
 }
 
 impl IntoNumberLiteral for i64 {
-    fn into_number_literal(self) -> NumberLiteral {
+    fn into_number_literal(self) -> NumberLiteral<'static> {
         NumberLiteral {
             source: Some(NumberSource::DecimalInt(self.to_string(), None)),
+            raw: None,
             value: self as f64
         }
     }
 }
 
 impl IntoNumberLiteral for u64 {
-    fn into_number_literal(self) -> NumberLiteral {
+    fn into_number_literal(self) -> NumberLiteral<'static> {
         NumberLiteral {
             source: Some(NumberSource::DecimalInt(self.to_string(), None)),
+            raw: None,
             value: self as f64
         }
     }
 }
 
 impl IntoNumberLiteral for f64 {
-    fn into_number_literal(self) -> NumberLiteral {
+    fn into_number_literal(self) -> NumberLiteral<'static> {
         NumberLiteral {
             source: None,
+            raw: None,
             value: self
         }
     }
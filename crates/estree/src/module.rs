@@ -0,0 +1,132 @@
+use easter::decl::{Import, ImportSpec, Export, ExportSpec};
+use easter::obj::DotKey;
+use easter::punc::Semi;
+use easter::stmt::{ModItem, StmtListItem, Stmt};
+use easter::fun::FunctionKind;
+use unjson::ty::Object;
+use unjson::{ExtractField, Unjson};
+
+use tag::{Tag, TagOf};
+use error::{Error, node_type_error};
+use expr::IntoExpr;
+use fun::IntoFun;
+use class::IntoClass;
+use result::{Map, Result};
+use node::ExtractNode;
+use stmt::IntoStmt;
+use id::IntoId;
+use lit::IntoStringLiteral;
+
+pub trait IntoModItem {
+    fn into_mod_item(self) -> Result<ModItem>;
+    fn into_import(self) -> Result<Import>;
+    fn into_export(self) -> Result<Export>;
+}
+
+fn into_dot_key(obj: Object) -> Result<DotKey> {
+    Ok(DotKey {
+        location: None,
+        value: obj.into_id()?.name
+    })
+}
+
+impl IntoModItem for Object {
+    fn into_mod_item(self) -> Result<ModItem> {
+        Ok(match self.tag()? {
+            Tag::ImportDeclaration => ModItem::Import(self.into_import()?),
+            Tag::ExportNamedDeclaration
+          | Tag::ExportDefaultDeclaration
+          | Tag::ExportAllDeclaration => ModItem::Export(self.into_export()?),
+            _ => ModItem::StmtListItem(self.into_stmt_list_item()?)
+        })
+    }
+
+    fn into_import(mut self) -> Result<Import> {
+        let source = self.extract_object("source")?.extract_string("value")?.into_string_literal();
+        let list = self.extract_array("specifiers")?;
+        let objs = list.map(|v| v.into_object().map_err(Error::Json))?;
+        if objs.is_empty() {
+            return Ok(Import::ForEffect(None, source, Semi::Explicit(None)));
+        }
+
+        let mut default = None;
+        let mut specs = Vec::new();
+        for mut obj in objs {
+            match obj.tag()? {
+                Tag::ImportDefaultSpecifier => {
+                    default = Some(obj.extract_id("local")?);
+                }
+                Tag::ImportNamespaceSpecifier => {
+                    let ns = obj.extract_id("local")?;
+                    return Ok(Import::Namespace(None, default, ns, source, Semi::Explicit(None)));
+                }
+                Tag::ImportSpecifier => {
+                    specs.push(ImportSpec {
+                        location: None,
+                        imported: into_dot_key(obj.extract_object("imported")?)?,
+                        local: obj.extract_id("local")?
+                    });
+                }
+                tag => { return node_type_error("import specifier", tag); }
+            }
+        }
+        Ok(Import::Named(None, default, specs, source, Semi::Explicit(None)))
+    }
+
+    fn into_export(mut self) -> Result<Export> {
+        Ok(match self.tag()? {
+            Tag::ExportAllDeclaration => {
+                let source = self.extract_object("source")?.extract_string("value")?.into_string_literal();
+                // Older ESTree producers leave out `exported` altogether.
+                let exported = if self.contains_key("exported") {
+                    self.extract_object_opt("exported")?
+                } else {
+                    None
+                };
+                match exported {
+                    Some(exported) => Export::NamespaceFrom(None, into_dot_key(exported)?, source, Semi::Explicit(None)),
+                    None => Export::AllFrom(None, source, Semi::Explicit(None))
+                }
+            }
+            Tag::ExportDefaultDeclaration => {
+                let mut decl = self.extract_object("declaration")?;
+                match decl.tag()? {
+                    Tag::FunctionDeclaration => {
+                        let kind = match decl.extract_id_opt("id")? {
+                            Some(id) => FunctionKind::Named(id),
+                            None => FunctionKind::Anonymous
+                        };
+                        Export::DefaultFun(None, decl.into_fun(kind)?)
+                    }
+                    Tag::ClassDeclaration => Export::DefaultClass(None, decl.into_class()?),
+                    _ => Export::DefaultExpr(None, decl.into_expr()?, Semi::Explicit(None))
+                }
+            }
+            tag => {
+                if let Some(decl) = self.extract_object_opt("declaration")? {
+                    return Ok(match decl.into_stmt_list_item()? {
+                        StmtListItem::Decl(decl) => Export::Decl(decl),
+                        StmtListItem::Stmt(Stmt::Var(_, dtors, semi)) => Export::Var(None, dtors, semi),
+                        _ => { return node_type_error("declaration", tag); }
+                    });
+                }
+                let list = self.extract_array("specifiers")?;
+                let objs = list.map(|v| v.into_object().map_err(Error::Json))?;
+                let specs = objs.map(|mut obj| {
+                    Ok(ExportSpec {
+                        location: None,
+                        local: into_dot_key(obj.extract_object("local")?)?,
+                        exported: into_dot_key(obj.extract_object("exported")?)?
+                    })
+                })?;
+                match self.extract_object_opt("source")? {
+                    Some(mut source) => {
+                        let source = source.extract_string("value")?.into_string_literal();
+                        Export::ListFrom(None, specs, source, Semi::Explicit(None))
+                    }
+                    None => Export::List(None, specs, Semi::Explicit(None))
+                }
+            }
+        })
+    }
+}
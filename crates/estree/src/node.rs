@@ -2,7 +2,7 @@ use unjson::ty::Object;
 use unjson::{ExtractField, Unjson};
 use easter::id::Id;
 use easter::expr::{Expr, ExprListItem};
-use easter::stmt::{Stmt, Block, StmtListItem, Case, Catch, Script};
+use easter::stmt::{Stmt, Block, StmtListItem, ModItem, Case, Catch, Script, Module};
 use easter::patt::{Patt, RestPatt};
 use easter::obj::Prop;
 use easter::decl::Dtor;
@@ -17,6 +17,7 @@ use expr::IntoExpr;
 use patt::IntoPatt;
 use obj::IntoObj;
 use decl::IntoDecl;
+use module::IntoModItem;
 
 pub trait ExtractNode {
     fn extract_id(&mut self, &'static str) -> Result<Id>;
@@ -39,6 +40,7 @@ pub trait ExtractNode {
     fn extract_case_list(&mut self, &'static str) -> Result<Vec<Case>>;
     fn extract_catch_opt(&mut self, &'static str) -> Result<Option<Catch>>;
     fn extract_script(&mut self, &'static str) -> Result<Script>;
+    fn extract_module(&mut self, &'static str) -> Result<Module>;
 }
 
 fn split_prefix<T, F>(v: &mut Vec<T>, mut p: F) -> Vec<T>
@@ -154,7 +156,8 @@ impl ExtractNode for Object {
         Ok(Params {
             location: None,
             list: objs.map(|o| o.into_patt())?,
-            rest: rest
+            rest: rest,
+            types: vec![]
         })
     }
 
@@ -191,9 +194,25 @@ impl ExtractNode for Object {
                          .collect();
         Ok(Script {
             location: None,
+            hashbang: None,
             dirs: prolog,
             items: items
         })
     }
 
+    fn extract_module(&mut self, name: &'static str) -> Result<Module> {
+        let list = self.extract_array(name)?;
+        let objs = list.map(|v| v.into_object().map_err(Error::Json))?;
+        let mut list: Vec<ModItem> = objs.map(|o| o.into_mod_item())?;
+        let items = split_prefix(&mut list, |s| s.is_directive());
+        let prolog = list.iter()
+                         .filter_map(|s| s.to_directive())
+                         .collect();
+        Ok(Module {
+            location: None,
+            hashbang: None,
+            dirs: prolog,
+            items: items
+        })
+    }
 }
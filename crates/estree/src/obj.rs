@@ -1,5 +1,5 @@
 use easter::fun::{FunctionKind};
-use easter::obj::{Prop, PropKey, PropVal};
+use easter::obj::{PrivateName, Prop, PropKey, PropVal};
 use easter::expr::Expr;
 use unjson::ty::{Object, Ty};
 use unjson::ExtractField;
@@ -7,7 +7,7 @@ use unjson::ExtractField;
 use tag::{Tag, TagOf};
 use id::IntoId;
 use result::Result;
-use error::{type_error, array_error};
+use error::{type_error, array_error, node_type_error};
 use node::ExtractNode;
 use expr::IntoExpr;
 use fun::IntoFun;
@@ -15,11 +15,15 @@ use fun::IntoFun;
 pub trait IntoObj {
     fn into_prop(self) -> Result<Prop>;
     fn into_prop_key(self, Option<bool>) -> Result<PropKey>;
+    fn into_private_name(self) -> Result<PrivateName>;
     fn computed(&self) -> Option<bool>;
 }
 
 impl IntoObj for Object {
     fn into_prop(mut self) -> Result<Prop> {
+        if self.tag()? == Tag::SpreadElement {
+            return Ok(Prop::Spread(None, self.extract_expr("argument")?, false));
+        }
         let key = self.extract_object("key")?;
         let computed = self.computed();
         let mut val = self.extract_object("value")?;
@@ -50,16 +54,25 @@ impl IntoObj for Object {
         Ok(Prop::Regular(None, key.into_prop_key(computed)?, val))
     }
 
+    fn into_private_name(mut self) -> Result<PrivateName> {
+        let tag = self.tag()?;
+        if tag != Tag::PrivateIdentifier {
+            return node_type_error("private identifier", tag);
+        }
+        Ok(PrivateName { location: None, name: self.extract_string("name")? })
+    }
+
     fn into_prop_key(self, computed: Option<bool>) -> Result<PropKey> {
         match self.tag()? {
             Tag::Identifier => {
                 let id = self.into_id()?;
                 match computed {
                     Some(true) => Ok(PropKey::Computed(None, Expr::Id(id))),
-                    Some(false) => Ok(PropKey::Id(None, id.name.into_string())),
-                    _ => Ok(PropKey::Id(None, id.name.into_string()))
+                    Some(false) => Ok(PropKey::Id(None, id.name)),
+                    _ => Ok(PropKey::Id(None, id.name))
                 }
             }
+            Tag::PrivateIdentifier => Ok(PropKey::Private(self.into_private_name()?)),
             Tag::Literal => {
                 match self.into_lit()? {
                     Expr::Number(_, lit) => Ok(PropKey::Number(None, lit)),
@@ -1,3 +1,4 @@
+use easter::expr::Expr;
 use easter::id::{Id, IdExt};
 use easter::patt::{CompoundPatt, Patt, PropPatt, RestPatt};
 use unjson::ty::Object;
@@ -10,9 +11,11 @@ use result::{Map, Result};
 use tag::{Tag, TagOf};
 
 use node::ExtractNode;
+use expr::IntoExpr;
 
 pub trait IntoPatt {
     fn into_patt(self) -> Result<Patt<Id>>;
+    fn into_assign_patt(self) -> Result<Patt<Expr>>;
 }
 
 impl IntoPatt for Object {
@@ -20,7 +23,7 @@ impl IntoPatt for Object {
         let tag = self.tag()?;
         match tag {
             Tag::AssignmentPattern => {
-                let left = self.extract_id("left")?;
+                let left = Box::new(self.extract_patt("left")?);
                 let right = Box::new(self.extract_expr("right")?);
                 Ok(Patt::Assign(None, left, right))
             }
@@ -56,17 +59,17 @@ impl IntoPatt for Object {
                 let list = self.extract_array("properties")?;
                 let mut objs = list.map(|v| Ok(v.into_object().map_err(Error::Json)?))?;
 
-                // let mut rest = None;
-                // if let Some(mut last) = objs.pop() {
-                //     if last.tag()? == Tag::RestElement {
-                //         rest = Some(RestPatt {
-                //             location: None,
-                //             patt: last.extract_patt("argument")?
-                //         });
-                //     } else {
-                //         objs.push(last));
-                //     }
-                // }
+                let mut rest = None;
+                if let Some(mut last) = objs.pop() {
+                    if last.tag()? == Tag::RestElement {
+                        rest = Some(RestPatt {
+                            location: None,
+                            patt: last.extract_patt("argument")?
+                        });
+                    } else {
+                        objs.push(last);
+                    }
+                }
 
                 let patt_elements = objs.map(|mut e| match e.extract_bool("shorthand")? {
                     true => {
@@ -89,11 +92,90 @@ impl IntoPatt for Object {
                     }
                 })?;
 
-                // let rest = rest.map(Box::new);
+                let rest = rest.map(Box::new);
 
-                Ok(Patt::Compound(CompoundPatt::Obj(None, patt_elements)))
+                Ok(Patt::Compound(CompoundPatt::Obj(None, patt_elements, rest)))
             }
             _ => self.into_id().map(|id| id.into_patt()),
         }
     }
+
+    // The target of a destructuring assignment or of a for-in/of loop head,
+    // whose leaves are assignable expressions rather than identifiers.
+    fn into_assign_patt(mut self) -> Result<Patt<Expr>> {
+        let tag = self.tag()?;
+        match tag {
+            Tag::AssignmentPattern => {
+                let left = Box::new(self.extract_object("left")?.into_assign_patt()?);
+                let right = Box::new(self.extract_expr("right")?);
+                Ok(Patt::Assign(None, left, right))
+            }
+            Tag::ArrayPattern => {
+                let list = self.extract_array("elements")?;
+                let mut objs = list.map(|v| match v.is_null() {
+                    true => Ok(None),
+                    false => Ok(Some(v.into_object().map_err(Error::Json)?)),
+                })?;
+
+                let mut rest = None;
+                if let Some(Some(mut last)) = objs.pop() {
+                    if last.tag()? == Tag::RestElement {
+                        rest = Some(RestPatt {
+                            location: None,
+                            patt: last.extract_object("argument")?.into_assign_patt()?,
+                        });
+                    } else {
+                        objs.push(Some(last));
+                    }
+                }
+
+                let patt_elements = objs.map(|e| match e {
+                    None => Ok(None),
+                    Some(o) => Ok(Some(o.into_assign_patt()?)),
+                })?;
+
+                Ok(Patt::Compound(CompoundPatt::Arr(None, patt_elements, rest.map(Box::new))))
+            }
+            Tag::ObjectPattern => {
+                let list = self.extract_array("properties")?;
+                let mut objs = list.map(|v| Ok(v.into_object().map_err(Error::Json)?))?;
+
+                let mut rest = None;
+                if let Some(mut last) = objs.pop() {
+                    if last.tag()? == Tag::RestElement {
+                        rest = Some(RestPatt {
+                            location: None,
+                            patt: last.extract_object("argument")?.into_assign_patt()?,
+                        });
+                    } else {
+                        objs.push(last);
+                    }
+                }
+
+                let patt_elements = objs.map(|mut e| match e.extract_bool("shorthand")? {
+                    true => {
+                        let prop_key = e.extract_id("key")?;
+                        let value_obj = e.extract_object("value")?;
+                        match value_obj.tag()? {
+                            Tag::AssignmentPattern => Ok(PropPatt::Shorthand(
+                                None,
+                                prop_key,
+                                Some(value_obj.into_patt()?),
+                            )),
+                            _ => Ok(PropPatt::Shorthand(None, prop_key, None)),
+                        }
+                    }
+                    false => {
+                        let computed = e.computed();
+                        let prop_key = e.extract_object("key")?.into_prop_key(computed)?;
+                        let prop_value = e.extract_object("value")?.into_assign_patt()?;
+                        Ok(PropPatt::Regular(None, prop_key, prop_value))
+                    }
+                })?;
+
+                Ok(Patt::Compound(CompoundPatt::Obj(None, patt_elements, rest.map(Box::new))))
+            }
+            _ => self.into_expr()?.into_assignable().map(Patt::Simple).map_err(|_| Error::InvalidLHS("left")),
+        }
+    }
 }
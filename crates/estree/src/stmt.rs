@@ -10,6 +10,8 @@ use tag::{Tag, TagOf};
 use decl::IntoConst;
 use expr::IntoExpr;
 use fun::IntoFun;
+use class::IntoClass;
+use patt::IntoPatt;
 use error::{Error, string_error, array_error, node_type_error};
 use result::Result;
 use node::ExtractNode;
@@ -65,13 +67,7 @@ impl IntoForInHead for Object {
                     (_, _, _) => { return string_error("var or let", kind); }
                 }
             }
-            _ => {
-                let expr = self.into_expr()?;
-                match expr.into_simple_or_compound_pattern() {
-                    Ok(patt) => ForInHead::Patt(patt),
-                    _ => { return Err(Error::InvalidLHS("left")); }
-                }
-            }
+            _ => ForInHead::Patt(self.into_assign_patt()?)
         })
     }
 }
@@ -98,13 +94,7 @@ impl IntoForOfHead for Object {
                     _ => { return string_error("var or let", kind); }
                 }
             },
-            _ => {
-                let expr = self.into_expr()?;
-                match expr.into_simple_or_compound_pattern() {
-                    Ok(patt) => ForOfHead::Patt(patt),
-                    _ => { return Err(Error::InvalidLHS("left")); }
-                }
-            }
+            _ => ForOfHead::Patt(self.into_assign_patt()?)
         })
     }
 }
@@ -121,12 +111,18 @@ fn into_stmt_list_item(mut this: Object, allow_decl: bool) -> Result<StmtListIte
     let tag = this.tag()?;
     Ok(StmtListItem::Stmt(match tag {
         Tag::FunctionDeclaration => {
+            let id = this.extract_id("id")?;
+            let fun = this.into_fun(FunctionKind::Named(id))?;
+            if !allow_decl {
+                return Ok(StmtListItem::Stmt(Stmt::Fun(fun)));
+            }
+            return Ok(StmtListItem::Decl(Decl::Fun(fun)));
+        }
+        Tag::ClassDeclaration => {
             if !allow_decl {
                 return node_type_error("statement", tag);
             }
-            let id = this.extract_id("id")?;
-            let decl_fn = Decl::Fun(this.into_fun(FunctionKind::Named(id))?);
-            return Ok(StmtListItem::Decl(decl_fn));
+            return Ok(StmtListItem::Decl(Decl::Class(this.into_class()?)));
         }
         Tag::VariableDeclaration => {
             let dtors = this.extract_dtor_list("declarations")?;
@@ -189,7 +185,11 @@ fn into_stmt_list_item(mut this: Object, allow_decl: bool) -> Result<StmtListIte
             let left = this.extract_object("left")?.into_for_of_head()?;
             let right = this.extract_expr("right")?;
             let body = this.extract_stmt("body")?;
-            Stmt::ForOf(None, Box::new(left), right, Box::new(body))
+            if this.extract_bool_opt("await")?.unwrap_or(false) {
+                Stmt::ForAwait(None, Box::new(left), right, Box::new(body))
+            } else {
+                Stmt::ForOf(None, Box::new(left), right, Box::new(body))
+            }
         }
         Tag::BlockStatement => {
             Stmt::Block(this.into_block()?)
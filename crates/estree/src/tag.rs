@@ -10,6 +10,7 @@ use error::Error;
 #[derive(Debug, PartialEq, Clone, Copy)]
 pub enum Tag {
     Identifier,
+    PrivateIdentifier,
     Literal,
 
     BinaryExpression,
@@ -19,6 +20,7 @@ pub enum Tag {
     UpdateExpression,
     MemberExpression,
     CallExpression,
+    ChainExpression,
     NewExpression,
     ArrayExpression,
     FunctionExpression,
@@ -27,13 +29,39 @@ pub enum Tag {
     ObjectExpression,
     ConditionalExpression,
     ThisExpression,
+    YieldExpression,
+    AwaitExpression,
     MetaProperty,
+    ImportExpression,
+    Import,
+    Super,
+    ClassExpression,
+    TemplateLiteral,
+    TaggedTemplateExpression,
 
+    TemplateElement,
+    Property,
     SpreadElement,
     RestElement,
 
     FunctionDeclaration,
     VariableDeclaration,
+    ClassDeclaration,
+
+    ClassBody,
+    MethodDefinition,
+    PropertyDefinition,
+    StaticBlock,
+
+    ImportDeclaration,
+    ImportSpecifier,
+    ImportDefaultSpecifier,
+    ImportNamespaceSpecifier,
+
+    ExportNamedDeclaration,
+    ExportDefaultDeclaration,
+    ExportAllDeclaration,
+    ExportSpecifier,
 
     EmptyStatement,
     ExpressionStatement,
@@ -56,7 +84,20 @@ pub enum Tag {
 
     AssignmentPattern,
     ObjectPattern,
-    ArrayPattern
+    ArrayPattern,
+
+    JSXElement,
+    JSXFragment,
+    JSXOpeningElement,
+    JSXIdentifier,
+    JSXNamespacedName,
+    JSXMemberExpression,
+    JSXAttribute,
+    JSXSpreadAttribute,
+    JSXExpressionContainer,
+    JSXEmptyExpression,
+    JSXSpreadChild,
+    JSXText
 }
 
 impl Display for Tag {
@@ -71,6 +112,7 @@ impl FromStr for Tag {
     fn from_str(s: &str) -> Result<Tag> {
         Ok(match s {
             "Identifier"            => Tag::Identifier,
+            "PrivateIdentifier"     => Tag::PrivateIdentifier,
             "Literal"               => Tag::Literal,
             "BinaryExpression"      => Tag::BinaryExpression,
             "AssignmentExpression"  => Tag::AssignmentExpression,
@@ -79,6 +121,7 @@ impl FromStr for Tag {
             "UpdateExpression"      => Tag::UpdateExpression,
             "MemberExpression"      => Tag::MemberExpression,
             "CallExpression"        => Tag::CallExpression,
+            "ChainExpression"       => Tag::ChainExpression,
             "NewExpression"         => Tag::NewExpression,
             "ArrayExpression"       => Tag::ArrayExpression,
             "FunctionExpression"    => Tag::FunctionExpression,
@@ -87,9 +130,32 @@ impl FromStr for Tag {
             "ObjectExpression"      => Tag::ObjectExpression,
             "ConditionalExpression" => Tag::ConditionalExpression,
             "ThisExpression"        => Tag::ThisExpression,
+            "YieldExpression"       => Tag::YieldExpression,
+            "AwaitExpression"       => Tag::AwaitExpression,
             "MetaProperty"          => Tag::MetaProperty,
+            "ImportExpression"      => Tag::ImportExpression,
+            "Import"                => Tag::Import,
+            "Super"                 => Tag::Super,
+            "ClassExpression"       => Tag::ClassExpression,
+            "TemplateLiteral"       => Tag::TemplateLiteral,
+            "TaggedTemplateExpression" => Tag::TaggedTemplateExpression,
+            "TemplateElement"       => Tag::TemplateElement,
             "FunctionDeclaration"   => Tag::FunctionDeclaration,
             "VariableDeclaration"   => Tag::VariableDeclaration,
+            "ClassDeclaration"      => Tag::ClassDeclaration,
+            "ClassBody"             => Tag::ClassBody,
+            "MethodDefinition"      => Tag::MethodDefinition,
+            "PropertyDefinition"    => Tag::PropertyDefinition,
+            "StaticBlock"           => Tag::StaticBlock,
+            "ImportDeclaration"     => Tag::ImportDeclaration,
+            "ImportSpecifier"       => Tag::ImportSpecifier,
+            "ImportDefaultSpecifier" => Tag::ImportDefaultSpecifier,
+            "ImportNamespaceSpecifier" => Tag::ImportNamespaceSpecifier,
+            "ExportNamedDeclaration" => Tag::ExportNamedDeclaration,
+            "ExportDefaultDeclaration" => Tag::ExportDefaultDeclaration,
+            "ExportAllDeclaration"  => Tag::ExportAllDeclaration,
+            "ExportSpecifier"       => Tag::ExportSpecifier,
+            "Property"              => Tag::Property,
             "SpreadElement"         => Tag::SpreadElement,
             "RestElement"           => Tag::RestElement,
             "EmptyStatement"        => Tag::EmptyStatement,
@@ -113,6 +179,18 @@ impl FromStr for Tag {
             "AssignmentPattern"     => Tag::AssignmentPattern,
             "ObjectPattern"         => Tag::ObjectPattern,
             "ArrayPattern"          => Tag::ArrayPattern,
+            "JSXElement"            => Tag::JSXElement,
+            "JSXFragment"           => Tag::JSXFragment,
+            "JSXOpeningElement"     => Tag::JSXOpeningElement,
+            "JSXIdentifier"         => Tag::JSXIdentifier,
+            "JSXNamespacedName"     => Tag::JSXNamespacedName,
+            "JSXMemberExpression"   => Tag::JSXMemberExpression,
+            "JSXAttribute"          => Tag::JSXAttribute,
+            "JSXSpreadAttribute"    => Tag::JSXSpreadAttribute,
+            "JSXExpressionContainer" => Tag::JSXExpressionContainer,
+            "JSXEmptyExpression"    => Tag::JSXEmptyExpression,
+            "JSXSpreadChild"        => Tag::JSXSpreadChild,
+            "JSXText"               => Tag::JSXText,
             _ => { return Err(Error::InvalidTypeTag(String::from(s))); }
         })
     }
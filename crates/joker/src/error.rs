@@ -10,6 +10,7 @@ pub enum Error {
     UnterminatedRegExp(Option<char>),
     MissingExponent(Option<char>),
     UnterminatedString(Option<char>),
+    UnterminatedTemplate,
     MissingBinaryDigits,
     MissingOctalDigits,
     MissingHexDigits,
@@ -18,6 +19,10 @@ pub enum Error {
     IllegalUnicode(u32),
     IdAfterNumber(char),
     DigitAfterNumber(char),
+    MisplacedSeparator,
+    IllegalRegExpFlag(char),
+    DuplicateRegExpFlag(char),
+    ConflictingRegExpFlags,
     ReservedWordWithEscapes(Reserved)
 }
 
@@ -25,7 +30,9 @@ impl Display for Error {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         match *self {
             Error::IllegalChar(ref ch)  |
-            Error::InvalidDigit(ref ch) => fmt.write_fmt(format_args!("{}: {:?}", self.description(), *ch)),
+            Error::InvalidDigit(ref ch) |
+            Error::IllegalRegExpFlag(ref ch) |
+            Error::DuplicateRegExpFlag(ref ch) => fmt.write_fmt(format_args!("{}: {:?}", self.description(), *ch)),
             Error::ReservedWordWithEscapes(ref word) => fmt.write_fmt(format_args!("{}: {:?}", self.description(), word)),
             Error::IllegalUnicode(ref u) => fmt.write_fmt(format_args!("{}: \\u{{{:04x}}}", self.description(), u)),
             _ => fmt.write_str(self.description()),
@@ -41,6 +48,7 @@ impl StdError for Error {
             Error::UnterminatedRegExp(_) => "unterminated regexp literal",
             Error::MissingExponent(_) => "missing exponent",
             Error::UnterminatedString(_) => "unterminated string",
+            Error::UnterminatedTemplate => "unterminated template literal",
             Error::MissingBinaryDigits => "missing binary digits",
             Error::MissingOctalDigits => "missing octal digits",
             Error::MissingHexDigits => "missing hex digits",
@@ -49,6 +57,10 @@ impl StdError for Error {
             Error::IllegalUnicode(_) => "illegal code unit",
             Error::IdAfterNumber(_) => "identifier starts immediately after numeric literal",
             Error::DigitAfterNumber(_) => "numeric literal starts immediately after previous numeric literal",
+            Error::MisplacedSeparator => "numeric separator must appear between two digits",
+            Error::IllegalRegExpFlag(_) => "illegal regexp flag",
+            Error::DuplicateRegExpFlag(_) => "duplicate regexp flag",
+            Error::ConflictingRegExpFlags => "regexp flags 'u' and 'v' are mutually exclusive",
             Error::ReservedWordWithEscapes(_) => "reserved word with escapes",
         }
     }
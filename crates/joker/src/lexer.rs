@@ -1,14 +1,17 @@
 use std::char;
 
 use track::*;
-use token::{Token, TokenData, Exp, CharCase, Sign, NumberSource, Radix, StringLiteral, RegExpLiteral};
+use token::{Comment, CommentKind, Token, TokenData, Exp, CharCase, Sign, NumberSource, Radix, StringLiteral, RegExpLiteral, TemplatePart, JSXText};
 use word::{Map as WordMap, Word};
 
 use char::ESCharExt;
 use reader::Reader;
+use entities;
 use error::Error;
 use result::Result;
 use std::collections::VecDeque;
+use std::mem::replace;
+use std::borrow::Cow;
 
 fn add_digits(digits: Vec<u32>, radix: u32) -> u32 {
     let mut place = 1;
@@ -20,48 +23,92 @@ fn add_digits(digits: Vec<u32>, radix: u32) -> u32 {
     sum
 }
 
+fn decode_char_ref(name: &str) -> Option<char> {
+    if name.starts_with("#x") || name.starts_with("#X") {
+        return u32::from_str_radix(&name[2..], 16).ok().and_then(char::from_u32);
+    }
+    if name.starts_with('#') {
+        return name[1..].parse().ok().and_then(char::from_u32);
+    }
+    entities::lookup(name)
+}
+
+// Pushes a character onto the values of a template part that no longer
+// borrow the source.
+fn push_copied(raw: &mut Option<String>, cooked: &mut Option<Option<String>>, ch: char) {
+    if let Some(ref mut raw) = *raw {
+        raw.push(ch);
+    }
+    if let Some(Some(ref mut cooked)) = *cooked {
+        cooked.push(ch);
+    }
+}
+
 struct SpanTracker {
     start: Posn
 }
 
 impl SpanTracker {
-    fn end(&self, lexer: &Lexer, value: TokenData) -> Token {
+    fn end<'a>(&self, lexer: &Lexer<'a>, value: TokenData<'a>) -> Token<'a> {
         let end = lexer.posn();
         Token::new(self.start, end, value)
     }
 }
 
-pub struct Lexer {
-    reader: Reader,
-    lookahead: VecDeque<Token>,
+// JSX is lexed differently inside of tags, where names may contain `-` and
+// strings have no escapes, and between tags, where everything up to the next
+// `<` or `{` is text. The parser switches modes as it enters and leaves JSX.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Normal,
+    JSXTag,
+    JSXChild
+}
+
+pub struct Lexer<'a> {
+    reader: Reader<'a>,
+    lookahead: VecDeque<Token<'a>>,
     wordmap: WordMap,
-    empty_line: bool
+    empty_line: bool,
+    mode: Mode,
+    html_comments: bool,
+    html_comment: Option<Span>,
+    hashbang: Option<(Span, &'a str)>,
+    comments: Option<Vec<Comment<'a>>>
 }
 
-impl Lexer {
+impl<'a> Lexer<'a> {
     // constructor
 
-    pub fn new<I>(chars: I) -> Lexer where I: Iterator<Item=char>  {
+    pub fn new(source: &'a str) -> Lexer<'a> {
         Lexer {
-            reader: Reader::new(chars),
+            reader: Reader::new(source),
             lookahead: VecDeque::with_capacity(2),
             wordmap: WordMap::new(),
-            empty_line: true
+            empty_line: true,
+            mode: Mode::Normal,
+            html_comments: false,
+            html_comment: None,
+            hashbang: None,
+            comments: None
         }
     }
 
     // public methods
 
+    // Seeking is only done to backtrack within ordinary code, so it also
+    // leaves any JSX mode.
     pub fn seek(&mut self, index: usize, posn: Posn) {
+        self.mode = Mode::Normal;
         // println!("lookahead Clear {:?}", self.lookahead);
         self.lookahead.clear();
         // println!("lookahead Cleared {:?}", self.lookahead);
 
-        self.reader.seek(index, posn);
+        self.rewind(index, posn);
    }
 
 
-    pub fn peek_token(&mut self, operator: bool) -> Result<&Token> {
+    pub fn peek_token(&mut self, operator: bool) -> Result<&Token<'a>> {
         if self.lookahead.is_empty() {
             let token = self.read_next_token(operator)?;
             self.lookahead.push_front(token);
@@ -69,7 +116,7 @@ impl Lexer {
         Ok(self.lookahead.front().unwrap())
     }
 
-    pub fn repeek_token(&mut self) -> &Token {
+    pub fn repeek_token(&mut self) -> &Token<'a> {
         self.lookahead.front().unwrap()
     }
 
@@ -78,11 +125,11 @@ impl Lexer {
         Ok(())
     }
 
-    pub fn reread_token(&mut self) -> Token {
+    pub fn reread_token(&mut self) -> Token<'a> {
         self.lookahead.pop_front().unwrap()
     }
 
-    pub fn read_token(&mut self, operator: bool) -> Result<Token> {
+    pub fn read_token(&mut self, operator: bool) -> Result<Token<'a>> {
         // println!("lookahead {:?}", self.lookahead);
         match self.lookahead.pop_front() {
             Some(token) => {
@@ -96,7 +143,71 @@ impl Lexer {
         }
     }
 
-    pub fn unread_token(&mut self, token: Token) {
+    // Re-lex the `}` that closes a template substitution as the template
+    // middle or tail that it begins.
+    pub fn read_template_continuation(&mut self) -> Result<Token<'a>> {
+        let rbrace = self.lookahead.pop_front().unwrap();
+        debug_assert!(rbrace.value == TokenData::RBrace);
+        self.lookahead.clear();
+        self.rewind(rbrace.location.end.offset as usize, rbrace.location.end);
+        let span = SpanTracker { start: rbrace.location.start };
+        let mut result = self.read_template_chars(span, false)?;
+        result.newline = rbrace.newline;
+        Ok(result)
+    }
+
+    pub fn mode(&self) -> Mode { self.mode }
+
+    // Any lookahead was lexed in the old mode, so it is discarded and lexed
+    // again from the start of the first token.
+    pub fn set_mode(&mut self, mode: Mode) {
+        if let Some(token) = self.lookahead.pop_front() {
+            self.lookahead.clear();
+            self.rewind(token.location.start.offset as usize, token.location.start);
+        }
+        self.mode = mode;
+    }
+
+    // HTML-like comments (`<!--` and `-->` at the start of a line) are an
+    // Annex B extension that is only recognized in scripts.
+    pub fn set_html_comments(&mut self, enabled: bool) {
+        self.html_comments = enabled;
+    }
+
+    // The location of the first HTML-like comment skipped so far, if any.
+    pub fn html_comment(&self) -> Option<Span> { self.html_comment }
+
+    // Comments are collected as they are skipped, including those before
+    // tokens that are still in the lookahead buffer.
+    pub fn set_collect_comments(&mut self, enabled: bool) {
+        self.comments = if enabled { Some(Vec::new()) } else { None };
+    }
+
+    // The comments collected since the last call.
+    pub fn take_comments(&mut self) -> Vec<Comment<'a>> {
+        match self.comments {
+            Some(ref mut comments) => replace(comments, Vec::new()),
+            None => Vec::new()
+        }
+    }
+
+    // The location and text (without the leading `#!`) of the hashbang
+    // comment at the start of the input, once it has been skipped.
+    pub fn hashbang(&self) -> Option<(Span, &'a str)> {
+        self.hashbang
+    }
+
+    // Skips the character that the last attempt to lex a token failed at, so
+    // that a parser recovering from the error always makes progress.
+    pub fn skip_char(&mut self) {
+        debug_assert!(self.lookahead.is_empty(), "Lookahead buffer is not empty");
+        self.mode = Mode::Normal;
+        if self.reader.peek(0).is_some() {
+            self.skip();
+        }
+    }
+
+    pub fn unread_token(&mut self, token: Token<'a>) {
         debug_assert!(self.lookahead.len() < self.lookahead.capacity(), "Lookahead buffer is full");
         // let token_len = token.location.end.column - token.location.start.column;
         // let index = self.index() - token_len as usize;
@@ -109,18 +220,28 @@ impl Lexer {
 
     pub fn posn(&self) -> Posn { self.reader.curr_posn() }
 
+    // The source text of a location that has already been read.
+    pub fn text(&self, location: Span) -> &'a str {
+        self.reader.text(location.start.offset as usize, location.end.offset as usize)
+    }
+
     pub fn index(&self) -> usize { self.reader.curr_index() }
 
     pub fn seek_index(&self) -> usize {
         // println!("lookahead {:?}", self.lookahead);
         // @cleanup Should we use offset everywhere here.
-        match self.lookahead.len() {
-            1 => self.index() - self.lookahead.front().unwrap().len(),
-            2 => {
-                let first_len = self.lookahead[0].location.start.offset as usize;
-                first_len
-            }
-            _ => self.reader.curr_index()
+        match self.lookahead.front() {
+            Some(token) => token.location.start.offset as usize,
+            None => self.reader.curr_index()
+        }
+    }
+
+    // The position matching `seek_index`, i.e. the start of the first
+    // lookahead token if there is one.
+    pub fn seek_posn(&self) -> Posn {
+        match self.lookahead.front() {
+            Some(token) => token.location.start,
+            None => self.posn()
         }
     }
 
@@ -221,6 +342,20 @@ impl Lexer {
         }
     }
 
+    // Values that match the source text from `start` borrow it until they
+    // first differ from it, when the text so far is copied.
+    fn copy_source<'b>(&self, value: &'b mut Option<String>, start: usize) -> &'b mut String {
+        let index = self.index();
+        value.get_or_insert_with(|| self.reader.text(start, index).to_string())
+    }
+
+    fn source_value(&self, value: Option<String>, start: usize, end: usize) -> Cow<'a, str> {
+        match value {
+            Some(value) => Cow::Owned(value),
+            None => Cow::Borrowed(self.reader.text(start, end))
+        }
+    }
+
     // lexical grammar
 
     fn skip_newlines(&mut self) {
@@ -244,6 +379,39 @@ impl Lexer {
         s.push(self.read());
     }
 
+    // Moves the reader, forgetting any comments that will be skipped again.
+    fn rewind(&mut self, index: usize, posn: Posn) {
+        if let Some(ref mut comments) = self.comments {
+            while comments.last().map_or(false, |comment| comment.location.start.offset as usize >= index) {
+                comments.pop();
+            }
+        }
+        self.reader.seek(index, posn);
+    }
+
+    // The delimiters of a comment are not part of its text.
+    fn collect_comment(&mut self, kind: CommentKind, start: Posn, open: usize, close: usize) {
+        if let Some(ref mut comments) = self.comments {
+            let end = self.reader.curr_posn();
+            comments.push(Comment {
+                location: Span { start: start, end: end },
+                kind: kind,
+                text: self.reader.text(start.offset as usize + open, end.offset as usize - close),
+                newline: false
+            });
+        }
+    }
+
+    // Marks the last comment as followed by a newline, if it was skipped
+    // since the given count of collected comments.
+    fn collect_newline(&mut self, since: usize) {
+        if let Some(ref mut comments) = self.comments {
+            if comments.len() > since {
+                comments.last_mut().unwrap().newline = true;
+            }
+        }
+    }
+
     fn skip_whitespace(&mut self) {
         self.skip_while(&|ch| ch.is_es_whitespace());
     }
@@ -255,6 +423,29 @@ impl Lexer {
         self.skip_until(&|ch| ch.is_es_newline());
     }
 
+    fn skip_html_comment(&mut self, kind: CommentKind, prefix_len: usize) {
+        let start = self.posn();
+        self.skip_line_comment(prefix_len);
+        if self.html_comment.is_none() {
+            self.html_comment = Some(Span { start: start, end: self.posn() });
+        }
+        self.collect_comment(kind, start, prefix_len, 0);
+    }
+
+    fn read_hashbang(&mut self) {
+        let start = self.posn();
+        self.skip2();
+        while let Some(ch) = self.peek() {
+            if ch.is_es_newline() {
+                break;
+            }
+            self.reread(ch);
+        }
+        let text = self.reader.text(start.offset as usize + 2, self.index());
+        self.hashbang = Some((Span { start: start, end: self.posn() }, text));
+        self.collect_comment(CommentKind::Hashbang, start, 2, 0);
+    }
+
     fn skip_block_comment(&mut self) -> Result<bool> {
         self.skip2();
         let mut found_newline = false;
@@ -274,57 +465,107 @@ impl Lexer {
         Ok(found_newline)
     }
 
-    fn read_regexp(&mut self) -> Result<Token> {
+    // The pattern is the source text between the slashes, so the regexp
+    // is only read to find where it ends.
+    fn read_regexp(&mut self) -> Result<Token<'a>> {
         let span = self.start();
-        let mut s = String::new();
         self.reread('/');
-        self.read_until_with(&|ch| ch == '/', &mut |this| { this.read_regexp_char(&mut s) })?;
+        let start = self.index();
+        self.read_until_with(&|ch| ch == '/', &mut |this| { this.read_regexp_char() })?;
+        if self.peek().is_none() {
+            return Err(Error::UnterminatedRegExp(None));
+        }
+        let pattern = self.reader.text(start, self.index());
         self.reread('/');
-        let flags = self.read_word_parts()?;
+        let flags = self.read_regexp_flags()?;
         Ok(span.end(self, TokenData::RegExp(RegExpLiteral {
-            pattern: s,
-            flags: flags.text.chars().collect()
+            pattern: Cow::Borrowed(pattern),
+            flags: flags,
+            raw: None
         })))
     }
 
-    fn read_regexp_char(&mut self, s: &mut String) -> Result<()> {
+    fn read_regexp_flags(&mut self) -> Result<Cow<'a, str>> {
+        let word = self.read_word_parts()?;
+        if word.had_escape() {
+            return Err(Error::IllegalRegExpFlag('\\'));
+        }
+        for (i, ch) in word.text.char_indices() {
+            match ch {
+                'd' | 'g' | 'i' | 'm' | 's' | 'u' | 'v' | 'y' => {}
+                _ => { return Err(Error::IllegalRegExpFlag(ch)); }
+            }
+            if word.text[..i].contains(ch) {
+                return Err(Error::DuplicateRegExpFlag(ch));
+            }
+        }
+        if word.text.contains('u') && word.text.contains('v') {
+            return Err(Error::ConflictingRegExpFlags);
+        }
+        Ok(word.text)
+    }
+
+    fn read_regexp_char(&mut self) -> Result<()> {
         match self.peek() {
-            Some('\\') => self.read_regexp_backslash(s),
-            Some('[') => self.read_regexp_class(s),
+            Some('\\') => self.read_regexp_backslash(),
+            Some('[') => self.read_regexp_class(),
             Some(ch) if ch.is_es_newline() => Err(Error::UnterminatedRegExp(Some(ch))),
-            Some(ch) => { s.push(self.reread(ch)); Ok(()) }
+            Some(_) => { self.skip(); Ok(()) }
             None => Err(Error::UnterminatedRegExp(None))
         }
     }
 
-    fn read_regexp_backslash(&mut self, s: &mut String) -> Result<()> {
-        s.push(self.reread('\\'));
+    fn read_regexp_backslash(&mut self) -> Result<()> {
+        self.reread('\\');
         match self.peek() {
             Some(ch) if ch.is_es_newline() => Err(Error::UnterminatedRegExp(Some(ch))),
-            Some(ch) => { s.push(self.reread(ch)); Ok(()) }
+            Some(_) => { self.skip(); Ok(()) }
             None => Err(Error::UnterminatedRegExp(None))
         }
     }
 
-    fn read_regexp_class(&mut self, s: &mut String) -> Result<()> {
-        s.push(self.reread('['));
-        self.read_until_with(&|ch| ch == ']', &mut |this| { this.read_regexp_class_char(s) })?;
-        s.push(self.reread(']'));
+    fn read_regexp_class(&mut self) -> Result<()> {
+        self.reread('[');
+        self.read_until_with(&|ch| ch == ']', &mut |this| { this.read_regexp_class_char() })?;
+        if self.peek().is_none() {
+            return Err(Error::UnterminatedRegExp(None));
+        }
+        self.reread(']');
         Ok(())
     }
 
-    fn read_regexp_class_char(&mut self, s: &mut String) -> Result<()> {
+    fn read_regexp_class_char(&mut self) -> Result<()> {
         match self.peek() {
-            Some('\\') => self.read_regexp_backslash(s),
-            Some(ch) => { s.push(self.reread(ch)); Ok(()) }
+            Some('\\') => self.read_regexp_backslash(),
+            Some(_) => { self.skip(); Ok(()) }
             None => Err(Error::UnterminatedRegExp(None))
         }
     }
 
-    fn read_decimal_digits(&mut self) -> String {
+    fn read_decimal_digits(&mut self) -> Result<String> {
         let mut s = String::new();
-        self.read_into_until(&mut s, &|ch| !ch.is_es_dec_digit());
-        s
+        self.read_separated_into(&mut s, &|ch| ch.is_es_dec_digit())?;
+        Ok(s)
+    }
+
+    // Reads a run of digits that may contain numeric separators. Callers
+    // start at a digit, so it's enough to check that each separator is
+    // followed by a digit. The separators are dropped from the result.
+    fn read_separated_into<F>(&mut self, s: &mut String, pred: &F) -> Result<()>
+      where F: Fn(char) -> bool
+    {
+        loop {
+            match self.peek() {
+                Some('_') => {
+                    if !self.reader.peek(1).map_or(false, |ch| pred(ch)) {
+                        return Err(Error::MisplacedSeparator);
+                    }
+                    self.skip();
+                }
+                Some(ch) if pred(ch) => { s.push(self.reread(ch)); }
+                _ => { return Ok(()); }
+            }
+        }
     }
 
     fn read_exp_part(&mut self) -> Result<Option<Exp>> {
@@ -340,11 +581,12 @@ impl Lexer {
             _ => None
         };
         match self.peek() {
+            Some('_') => { return Err(Error::MisplacedSeparator); }
             Some(ch) if !ch.is_es_dec_digit() => return Err(Error::MissingExponent(Some(ch))),
             None => { return Err(Error::MissingExponent(None)); }
             _ => ()
         }
-        Ok(Some(Exp { e: e, sign: sign, value: self.read_decimal_digits() }))
+        Ok(Some(Exp { e: e, sign: sign, value: self.read_decimal_digits()? }))
     }
 
     fn read_decimal_int(&mut self) -> String {
@@ -353,9 +595,9 @@ impl Lexer {
         s
     }
 
-    fn read_radix_int<F, G>(&mut self, radix: u32, pred: &F, cons: &G, missing_digits: Error) -> Result<Token>
+    fn read_radix_int<F, G>(&mut self, radix: u32, pred: &F, cons: &G, missing_digits: Error) -> Result<Token<'a>>
       where F: Fn(char) -> bool,
-            G: Fn(CharCase, String) -> TokenData
+            G: Fn(CharCase, String) -> NumberSource
     {
         debug_assert!(self.peek() == Some('0'));
         debug_assert!(self.reader.peek(1).map_or(false, |ch| ch.is_alphabetic()));
@@ -367,30 +609,39 @@ impl Lexer {
         } else {
             CharCase::UpperCase
         };
+        if self.peek() == Some('_') {
+            return Err(Error::MisplacedSeparator);
+        }
         self.read_digit_into(&mut s, radix, pred, missing_digits)?;
-        self.read_into_until(&mut s, &|ch| !pred(ch));
-        Ok(span.end(self, cons(flag, s)))
+        self.read_separated_into(&mut s, pred)?;
+        let source = cons(flag, s);
+        let value = if self.matches('n') {
+            source.into_bigint_token_data()
+        } else {
+            source.into_token_data()
+        };
+        Ok(span.end(self, value))
     }
 
-    fn read_hex_int(&mut self) -> Result<Token> {
+    fn read_hex_int(&mut self) -> Result<Token<'a>> {
         self.read_radix_int(16, &|ch| ch.is_es_hex_digit(), &|cc, s| {
-            NumberSource::RadixInt(Radix::Hex(cc), s).into_token_data()
+            NumberSource::RadixInt(Radix::Hex(cc), s)
         }, Error::MissingHexDigits)
     }
 
-    fn read_oct_int(&mut self) -> Result<Token> {
+    fn read_oct_int(&mut self) -> Result<Token<'a>> {
         self.read_radix_int(8, &|ch| ch.is_es_oct_digit(), &|cc, s| {
-            NumberSource::RadixInt(Radix::Oct(Some(cc)), s).into_token_data()
+            NumberSource::RadixInt(Radix::Oct(Some(cc)), s)
         }, Error::MissingOctalDigits)
     }
 
-    fn read_bin_int(&mut self) -> Result<Token> {
+    fn read_bin_int(&mut self) -> Result<Token<'a>> {
         self.read_radix_int(2, &|ch| ch.is_es_bin_digit(), &|cc, s| {
-            NumberSource::RadixInt(Radix::Bin(cc), s).into_token_data()
+            NumberSource::RadixInt(Radix::Bin(cc), s)
         }, Error::MissingBinaryDigits)
     }
 
-    fn read_number(&mut self) -> Result<Token> {
+    fn read_number(&mut self) -> Result<Token<'a>> {
         let result = match self.peek2() {
             (Some('0'), Some('x')) | (Some('0'), Some('X')) => self.read_hex_int(),
             (Some('0'), Some('o')) | (Some('0'), Some('O')) => self.read_oct_int(),
@@ -398,20 +649,29 @@ impl Lexer {
             (Some('.'), _) => {
                 let span = self.start();
                 self.skip();
-                let frac = self.read_decimal_digits();
+                let frac = self.read_decimal_digits()?;
                 let exp = self.read_exp_part()?;
                 Ok(span.end(self, NumberSource::Float(None, Some(frac), exp).into_token_data()))
             }
             (Some(ch), _) => {
                 debug_assert!(ch.is_es_dec_digit());
                 let span = self.start();
-                let s = self.read_decimal_int();
+                // Numeric separators and BigInt suffixes are not allowed in
+                // legacy octal or decimal literals with a leading zero.
+                let s = if ch == '0' {
+                    self.read_decimal_int()
+                } else {
+                    self.read_decimal_digits()?
+                };
                 let value = if ch == '0' && s.len() > 1 && s.chars().skip(1).all(|ch| ch.is_es_oct_digit()) {
-                    NumberSource::RadixInt(Radix::Oct(None), s)
+                    NumberSource::RadixInt(Radix::Oct(None), s).into_token_data()
+                } else if (ch != '0' || s.len() == 1) && self.matches('n') {
+                    NumberSource::DecimalInt(s, None).into_bigint_token_data()
                 } else {
                     let (dot, frac) = if self.matches('.') {
                         (true, Some(match self.peek() {
-                            Some(ch) if ch.is_es_dec_digit() => self.read_decimal_digits(),
+                            Some('_') => { return Err(Error::MisplacedSeparator); }
+                            Some(ch) if ch.is_es_dec_digit() => self.read_decimal_digits()?,
                             _ => String::from("")
                         }))
                     } else {
@@ -419,16 +679,17 @@ impl Lexer {
                     };
                     let exp = self.read_exp_part()?;
                     if dot {
-                        NumberSource::Float(Some(s), frac, exp)
+                        NumberSource::Float(Some(s), frac, exp).into_token_data()
                     } else {
-                        NumberSource::DecimalInt(s, exp)
+                        NumberSource::DecimalInt(s, exp).into_token_data()
                     }
                 };
-                Ok(span.end(self, value.into_token_data()))
+                Ok(span.end(self, value))
             }
             (None, _) => { panic!("read_number() called at EOF"); }
         }?;
         match self.peek() {
+            Some('_') => { return Err(Error::MisplacedSeparator); }
             Some(ch) if ch.is_es_identifier_start() => { return Err(Error::IdAfterNumber(ch)); }
             Some(ch) if ch.is_es_dec_digit() => { return Err(Error::DigitAfterNumber(ch)); }
             _ => {}
@@ -436,40 +697,219 @@ impl Lexer {
         Ok(result)
     }
 
-    fn read_string(&mut self) -> Result<Token> {
+    fn read_string(&mut self) -> Result<Token<'a>> {
         debug_assert!(self.peek().is_some());
         let span = self.start();
-        let mut source = String::new();
-        let mut value = String::new();
         let quote = self.read();
-        source.push(quote);
-        loop {
-            self.read_into2_until(&mut source, &mut value, &|ch| {
+        let start = self.index();
+        let mut value = None;
+        let end = loop {
+            let pred = |ch: char| {
                 ch == quote ||
                 ch == '\\' ||
                 ch.is_es_newline()
-            });
+            };
+            match value {
+                Some(ref mut value) => { self.read_into_until(value, &pred); }
+                None => { self.skip_until(&pred); }
+            }
             match self.peek() {
                 Some('\\') => {
-                    self.read_string_escape(&mut source, &mut value)?;
+                    let value = self.copy_source(&mut value, start);
+                    self.read_string_escape(value)?;
                 }
                 Some(ch) if ch.is_es_newline() => {
                     return Err(Error::UnterminatedString(Some(ch)));
                 }
                 Some(_) => {
-                    source.push(quote);
+                    let end = self.index();
                     self.skip();
-                    break;
+                    break end;
                 }
                 None => return Err(Error::UnterminatedString(None))
             }
-        }
+        };
         Ok(span.end(self, TokenData::String(StringLiteral {
-            source: Some(source),
-            value: value
+            source: None,
+            value: self.source_value(value, start, end)
         })))
     }
 
+    fn read_template(&mut self) -> Result<Token<'a>> {
+        let span = self.start();
+        self.reread('`');
+        self.read_template_chars(span, true)
+    }
+
+    fn read_template_chars(&mut self, span: SpanTracker, head: bool) -> Result<Token<'a>> {
+        let start = self.index();
+        // The raw value only differs from the source at a carriage return,
+        // and the cooked value at an escape as well. The cooked value is
+        // `None` after an escape that's only legal in a tagged template.
+        let mut raw = None;
+        let mut cooked = Some(None);
+        loop {
+            match self.peek2() {
+                (Some('`'), _) => {
+                    let part = self.template_part(raw, cooked, start);
+                    self.skip();
+                    return Ok(span.end(self, if head {
+                        TokenData::Template(part)
+                    } else {
+                        TokenData::TemplateTail(part)
+                    }));
+                }
+                (Some('$'), Some('{')) => {
+                    let part = self.template_part(raw, cooked, start);
+                    self.skip2();
+                    return Ok(span.end(self, if head {
+                        TokenData::TemplateHead(part)
+                    } else {
+                        TokenData::TemplateMiddle(part)
+                    }));
+                }
+                (Some('\\'), next) => {
+                    if next == Some('\r') {
+                        self.copy_source(&mut raw, start);
+                    }
+                    if let Some(ref mut cooked) = cooked {
+                        self.copy_source(cooked, start);
+                    }
+                    self.reread('\\');
+                    let mut escape_raw = String::new();
+                    let escape = self.read_template_escape(&mut escape_raw)?;
+                    if let Some(ref mut raw) = raw {
+                        raw.push('\\');
+                        raw.push_str(&escape_raw);
+                    }
+                    cooked = match (cooked, escape) {
+                        (Some(Some(mut s)), Some(ch)) => {
+                            if let Some(ch) = ch {
+                                s.push(ch);
+                            }
+                            Some(Some(s))
+                        }
+                        _ => None
+                    };
+                }
+                (Some(ch), _) if ch.is_es_newline() => {
+                    if ch == '\r' {
+                        self.copy_source(&mut raw, start);
+                        if let Some(ref mut cooked) = cooked {
+                            self.copy_source(cooked, start);
+                        }
+                    }
+                    let ch = self.read_template_newline();
+                    push_copied(&mut raw, &mut cooked, ch);
+                }
+                (Some(ch), _) => {
+                    self.reread(ch);
+                    push_copied(&mut raw, &mut cooked, ch);
+                }
+                (None, _) => { return Err(Error::UnterminatedTemplate); }
+            }
+        }
+    }
+
+    fn template_part(&self, raw: Option<String>, cooked: Option<Option<String>>, start: usize) -> TemplatePart<'a> {
+        let end = self.index();
+        TemplatePart {
+            cooked: cooked.map(|cooked| self.source_value(cooked, start, end)),
+            raw: self.source_value(raw, start, end),
+        }
+    }
+
+    // CR and CRLF are normalized to LF in both the cooked and raw values.
+    fn read_template_newline(&mut self) -> char {
+        match self.read() {
+            '\r' => {
+                self.matches('\n');
+                '\n'
+            }
+            ch => ch
+        }
+    }
+
+    // Reads the rest of an escape sequence in a template into `raw`, returning
+    // the character it contributes to the cooked value (none for a line
+    // continuation), or `None` if the escape is only legal in a tagged
+    // template.
+    fn read_template_escape(&mut self, raw: &mut String) -> Result<Option<Option<char>>> {
+        Ok(match self.peek2() {
+            (Some(ch), _) if ch.is_es_newline() => {
+                raw.push(self.read_template_newline());
+                Some(None)
+            }
+            (Some('0'), next) if !next.map_or(false, |ch| ch.is_es_dec_digit()) => {
+                raw.push(self.reread('0'));
+                Some(Some('\u{0}'))
+            }
+            (Some(ch), _) if ch.is_es_dec_digit() => {
+                raw.push(self.reread(ch));
+                None
+            }
+            (Some('x'), _) => {
+                raw.push(self.reread('x'));
+                let mut code = 0;
+                for _ in 0..2 {
+                    match self.peek() {
+                        Some(ch) if ch.is_es_hex_digit() => {
+                            raw.push(self.reread(ch));
+                            code = (code << 4) + ch.to_digit(16).unwrap();
+                        }
+                        _ => { return Ok(None); }
+                    }
+                }
+                Some(char::from_u32(code))
+            }
+            (Some('u'), _) => {
+                raw.push(self.reread('u'));
+                self.read_template_unicode_escape(raw)
+            }
+            (Some(ch), _) => {
+                raw.push(self.reread(ch));
+                Some(Some(ch.unescape()))
+            }
+            (None, _) => { return Err(Error::UnterminatedTemplate); }
+        })
+    }
+
+    fn read_template_unicode_escape(&mut self, raw: &mut String) -> Option<Option<char>> {
+        let mut code: u32 = 0;
+        if self.matches('{') {
+            raw.push('{');
+            let mut digits = 0;
+            loop {
+                match self.peek() {
+                    Some('}') if digits > 0 => {
+                        raw.push(self.reread('}'));
+                        break;
+                    }
+                    Some(ch) if ch.is_es_hex_digit() => {
+                        raw.push(self.reread(ch));
+                        code = (code << 4) + ch.to_digit(16).unwrap();
+                        digits += 1;
+                        if code > 0x10ffff {
+                            return None;
+                        }
+                    }
+                    _ => { return None; }
+                }
+            }
+        } else {
+            for _ in 0..4 {
+                match self.peek() {
+                    Some(ch) if ch.is_es_hex_digit() => {
+                        raw.push(self.reread(ch));
+                        code = (code << 4) + ch.to_digit(16).unwrap();
+                    }
+                    _ => { return None; }
+                }
+            }
+        }
+        Some(Some(char::from_u32(code).unwrap_or('?')))
+    }
+
     fn read_unicode_escape_seq(&mut self, s: &mut String) -> Result<u32> {
         if self.matches('{') {
             s.push('{');
@@ -492,8 +932,12 @@ impl Lexer {
         }
     }
 
-    fn read_string_escape(&mut self, source: &mut String, value: &mut String) -> Result<()> {
-        source.push(self.reread('\\'));
+    fn read_string_escape(&mut self, value: &mut String) -> Result<()> {
+        // The source of the literal is taken from the input afterwards, so
+        // the text of the escape is only read into a scratch buffer.
+        let mut source = String::new();
+        let source = &mut source;
+        self.reread('\\');
         match self.peek() {
             Some(ch) if ch.is_es_oct_digit() => {
                 let mut code = 0;
@@ -554,18 +998,34 @@ impl Lexer {
         self.read_digit_into(s, 16, &|ch| ch.is_es_hex_digit(), Error::MissingHexDigits)
     }
 
-    fn read_word_parts(&mut self) -> Result<Word> {
+    // Words without escapes borrow their text from the source.
+    fn read_word_parts(&mut self) -> Result<Word<'a>> {
+        let start = self.index();
         let mut s = Word::new();
         self.read_until_with(&|ch| ch != '\\' && !ch.is_es_identifier_continue(), &mut |this| {
             match this.read() {
-                '\\' => this.read_word_escape(&mut s),
-                ch => { s.text.push(ch); Ok(()) }
+                '\\' => {
+                    if !s.had_escape() {
+                        s.text = Cow::Owned(this.reader.text(start, this.index() - 1).to_string());
+                    }
+                    this.read_word_escape(&mut s)
+                }
+                ch => {
+                    if s.had_escape() {
+                        s.text.to_mut().push(ch);
+                    }
+                    Ok(())
+                }
             }
         })?;
+        s.raw = self.reader.text(start, self.index());
+        if !s.had_escape() {
+            s.text = Cow::Borrowed(s.raw);
+        }
         Ok(s)
     }
 
-    fn read_word(&mut self) -> Result<Token> {
+    fn read_word(&mut self) -> Result<Token<'a>> {
         debug_assert!(self.peek().map_or(false, |ch| ch == '\\' || ch.is_es_identifier_start()));
         let span = self.start();
         let s = self.read_word_parts()?;
@@ -573,7 +1033,116 @@ impl Lexer {
         Ok(span.end(self, self.wordmap.tokenize(s)?))
     }
 
-    fn read_word_escape(&mut self, s: &mut Word) -> Result<()> {
+    fn read_private_name(&mut self) -> Result<Token<'a>> {
+        let span = self.start();
+        self.skip();
+        let s = self.read_word_parts()?;
+        debug_assert!(s.text.len() > 0);
+        Ok(span.end(self, TokenData::PrivateName(s.text)))
+    }
+
+    fn read_jsx_tag_token(&mut self, pair: (Option<char>, Option<char>)) -> Result<Token<'a>> {
+        match pair {
+            (Some('<'), _)                               => Ok(self.read_punc(TokenData::LAngle)),
+            (Some('>'), _)                               => Ok(self.read_punc(TokenData::RAngle)),
+            (Some('/'), _)                               => Ok(self.read_punc(TokenData::Slash)),
+            (Some('='), _)                               => Ok(self.read_punc(TokenData::Assign)),
+            (Some('{'), _)                               => Ok(self.read_punc(TokenData::LBrace)),
+            (Some('}'), _)                               => Ok(self.read_punc(TokenData::RBrace)),
+            (Some(':'), _)                               => Ok(self.read_punc(TokenData::Colon)),
+            (Some('.'), _)                               => Ok(self.read_punc(TokenData::Dot)),
+            (Some('"'), _) | (Some('\''), _)             => self.read_jsx_string(),
+            (Some(ch), _) if ch.is_es_identifier_start() => self.read_jsx_name(),
+            (Some(ch), _)                                => Err(Error::IllegalChar(ch)),
+            (None, _)                                    => {
+                let here = self.posn();
+                Ok(Token::new(here, here, TokenData::EOF))
+            }
+        }
+    }
+
+    fn read_jsx_name(&mut self) -> Result<Token<'a>> {
+        let span = self.start();
+        let start = self.index();
+        self.skip_until(&|ch| ch != '-' && !ch.is_es_identifier_continue());
+        let name = self.reader.text(start, self.index());
+        Ok(span.end(self, TokenData::JSXName(Cow::Borrowed(name))))
+    }
+
+    // JSX strings may span lines and have no escapes, but they may contain
+    // character references.
+    fn read_jsx_string(&mut self) -> Result<Token<'a>> {
+        let span = self.start();
+        let mut source = String::new();
+        let mut value = String::new();
+        let quote = self.read();
+        self.read_jsx_text_into(&mut source, &mut value, &|ch| ch == quote);
+        match self.peek() {
+            Some(_) => { self.reread(quote); }
+            None => { return Err(Error::UnterminatedString(None)); }
+        }
+        Ok(span.end(self, TokenData::String(StringLiteral {
+            source: None,
+            value: Cow::Owned(value)
+        })))
+    }
+
+    fn read_jsx_child(&mut self) -> Result<Token<'a>> {
+        match self.peek() {
+            Some('<') => Ok(self.read_punc(TokenData::LAngle)),
+            Some('{') => Ok(self.read_punc(TokenData::LBrace)),
+            // These must be written as `{'>'}` or `&gt;` and so on.
+            Some('>') | Some('}') => Err(Error::IllegalChar(self.read())),
+            Some(_) => {
+                let span = self.start();
+                let mut raw = String::new();
+                let mut value = String::new();
+                self.read_jsx_text_into(&mut raw, &mut value, &|ch| {
+                    ch == '<' || ch == '{' || ch == '>' || ch == '}'
+                });
+                Ok(span.end(self, TokenData::JSXText(JSXText {
+                    value: value,
+                    raw: raw
+                })))
+            }
+            None => {
+                let here = self.posn();
+                Ok(Token::new(here, here, TokenData::EOF))
+            }
+        }
+    }
+
+    fn read_jsx_text_into<F>(&mut self, raw: &mut String, value: &mut String, pred: &F)
+      where F: Fn(char) -> bool
+    {
+        loop {
+            self.read_into2_until(raw, value, &|ch| ch == '&' || pred(ch));
+            if self.peek() != Some('&') {
+                return;
+            }
+            raw.push(self.reread('&'));
+            let mut name = String::new();
+            while name.len() < 10 {
+                match self.peek() {
+                    Some(ch) if ch.is_ascii_alphanumeric() || ch == '#' => { name.push(self.reread(ch)); }
+                    _ => { break; }
+                }
+            }
+            raw.push_str(&name);
+            match (self.peek(), decode_char_ref(&name)) {
+                (Some(';'), Some(ch)) => {
+                    raw.push(self.reread(';'));
+                    value.push(ch);
+                }
+                _ => {
+                    value.push('&');
+                    value.push_str(&name);
+                }
+            }
+        }
+    }
+
+    fn read_word_escape(&mut self, s: &mut Word<'a>) -> Result<()> {
         s.set_had_escape();
         match self.peek() {
             Some('u') => { self.reread('u'); }
@@ -582,40 +1151,45 @@ impl Lexer {
         let mut dummy = String::with_capacity(4);
         let code_point = self.read_unicode_escape_seq(&mut dummy)?;
         match char::from_u32(code_point) {
-            Some(ch) => { s.text.push(ch); Ok(()) }
+            Some(ch) => { s.text.to_mut().push(ch); Ok(()) }
             None => Err(Error::IllegalUnicode(code_point))
         }
     }
 
-    fn read_punc(&mut self, value: TokenData) -> Token {
+    fn read_punc(&mut self, value: TokenData<'a>) -> Token<'a> {
         let span = self.start();
         self.skip();
         span.end(self, value)
     }
 
-    fn read_punc2(&mut self, value: TokenData) -> Token {
+    fn read_punc2(&mut self, value: TokenData<'a>) -> Token<'a> {
         let span = self.start();
         self.skip2();
         span.end(self, value)
     }
 
-    fn read_punc3(&mut self, value: TokenData) -> Token {
+    fn read_punc3(&mut self, value: TokenData<'a>) -> Token<'a> {
         let span = self.start();
         self.skip2();
         self.skip();
         span.end(self, value)
     }
 
-    fn read_punc2_3(&mut self, ch: char, value2: TokenData, value3: TokenData) -> Token {
+    fn read_punc2_3(&mut self, ch: char, value2: TokenData<'a>, value3: TokenData<'a>) -> Token<'a> {
         let span = self.start();
         self.skip2();
         let value = if self.matches(ch) { value3 } else { value2 };
         span.end(self, value)
     }
 
-    fn read_next_token(&mut self, operator: bool) -> Result<Token> {
+    fn read_next_token(&mut self, operator: bool) -> Result<Token<'a>> {
+        if self.mode == Mode::JSXChild {
+            return self.read_jsx_child();
+        }
+
         let mut pair;
         let mut found_newline = false;
+        let collected = self.comments.as_ref().map_or(0, |comments| comments.len());
 
         // Skip whitespace and comments.
         loop {
@@ -626,21 +1200,33 @@ impl Lexer {
                     self.skip_newlines();
                     found_newline = true;
                     self.empty_line = true;
+                    self.collect_newline(collected);
+                }
+                (Some('/'), Some('/')) => {
+                    let start = self.posn();
+                    self.skip_line_comment(2);
+                    self.collect_comment(CommentKind::Line, start, 2, 0);
                 }
-                (Some('/'), Some('/')) => { self.skip_line_comment(2); }
+                // A hashbang is only a comment at the very start of the input.
+                (Some('#'), Some('!')) if self.posn().offset == 0 => { self.read_hashbang(); }
                 (Some('/'), Some('*')) => {
-                    found_newline = self.skip_block_comment()? || found_newline;
+                    let start = self.posn();
+                    if self.skip_block_comment()? {
+                        found_newline = true;
+                        self.empty_line = true;
+                    }
+                    self.collect_comment(CommentKind::Block, start, 2, 2);
                 }
-                (Some('<'), Some('!')) => {
+                (Some('<'), Some('!')) if self.html_comments && self.mode == Mode::Normal => {
                     if self.reader.peek(2) == Some('-') && self.reader.peek(3) == Some('-') {
-                        self.skip_line_comment(4);
+                        self.skip_html_comment(CommentKind::HTMLOpen, 4);
                     } else {
                         break;
                     }
                 }
-                (Some('-'), Some('-')) => {
+                (Some('-'), Some('-')) if self.html_comments && self.mode == Mode::Normal => {
                     if self.empty_line && self.reader.peek(2) == Some('>') {
-                        self.skip_line_comment(3);
+                        self.skip_html_comment(CommentKind::HTMLClose, 3);
                     } else {
                         break;
                     }
@@ -652,6 +1238,7 @@ impl Lexer {
         self.empty_line = false;
 
         let mut result = match pair {
+            _ if self.mode == Mode::JSXTag               => self.read_jsx_tag_token(pair),
             (Some('/'), _) if !operator                  => self.read_regexp(),
             (Some('/'), Some('='))                       => {
                 Ok(self.read_punc2(TokenData::SlashAssign))
@@ -708,6 +1295,9 @@ impl Lexer {
                 Ok(self.read_punc2(TokenData::MinusAssign))
             }
             (Some('-'), _)                               => Ok(self.read_punc(TokenData::Minus)),
+            (Some('*'), Some('*'))                       => {
+                Ok(self.read_punc2_3('=', TokenData::StarStar, TokenData::StarStarAssign))
+            }
             (Some('*'), Some('='))                       => {
                 Ok(self.read_punc2(TokenData::StarAssign))
             }
@@ -721,29 +1311,41 @@ impl Lexer {
             }
             (Some('^'), _)                               => Ok(self.read_punc(TokenData::BitXor)),
             (Some('&'), Some('&'))                       => {
-                Ok(self.read_punc2(TokenData::LogicalAnd))
+                Ok(self.read_punc2_3('=', TokenData::LogicalAnd, TokenData::LogicalAndAssign))
             }
             (Some('&'), Some('='))                       => {
                 Ok(self.read_punc2(TokenData::BitAndAssign))
             }
             (Some('&'), _)                               => Ok(self.read_punc(TokenData::BitAnd)),
             (Some('|'), Some('|'))                       => {
-                Ok(self.read_punc2(TokenData::LogicalOr))
+                Ok(self.read_punc2_3('=', TokenData::LogicalOr, TokenData::LogicalOrAssign))
             }
             (Some('|'), Some('='))                       => {
                 Ok(self.read_punc2(TokenData::BitOrAssign))
             }
             (Some('|'), _)                               => Ok(self.read_punc(TokenData::BitOr)),
             (Some('~'), _)                               => Ok(self.read_punc(TokenData::Tilde)),
+            (Some('@'), _)                               => Ok(self.read_punc(TokenData::At)),
             (Some('!'), Some('='))                       => {
                 Ok(self.read_punc2_3('=', TokenData::NEq, TokenData::StrictNEq))
             }
             (Some('!'), _)                               => Ok(self.read_punc(TokenData::Bang)),
+            (Some('?'), Some('?'))                       => {
+                Ok(self.read_punc2_3('=', TokenData::Coalesce, TokenData::CoalesceAssign))
+            }
+            // 11.7: `?.` followed by a digit is a conditional operator and a number.
+            (Some('?'), Some('.')) if !self.reader.peek(2).map_or(false, |ch| ch.is_es_dec_digit()) => {
+                Ok(self.read_punc2(TokenData::QuestionDot))
+            }
             (Some('?'), _)                               => Ok(self.read_punc(TokenData::Question)),
             (Some('"'), _) | (Some('\''), _)             => self.read_string(),
+            (Some('`'), _)                               => self.read_template(),
             (Some(ch), _) if ch.is_es_dec_digit()        => self.read_number(),
             (Some(ch), _) if ch.is_es_identifier_start() => self.read_word(),
             (Some('\\'), _)                              => self.read_word(),
+            (Some('#'), Some(ch)) if ch == '\\' || ch.is_es_identifier_start() => {
+                self.read_private_name()
+            }
             (Some(ch), _)                                => Err(Error::IllegalChar(ch)),
             (None, _)                                    => {
                 let here = self.posn();
@@ -751,22 +1353,39 @@ impl Lexer {
             }
         }?;
         result.newline = found_newline;
+        self.set_raw(&mut result);
         Ok(result)
     }
+
+    // The values of literals don't say how they were written, so they keep
+    // their source text.
+    fn set_raw(&self, token: &mut Token<'a>) {
+        let raw = match token.value {
+            TokenData::Number(ref mut literal) => &mut literal.raw,
+            TokenData::BigInt(ref mut literal) => &mut literal.raw,
+            TokenData::String(ref mut literal) => &mut literal.source,
+            TokenData::RegExp(ref mut literal) => &mut literal.raw,
+            _ => { return; }
+        };
+        *raw = Some(Cow::Borrowed(self.text(token.location)));
+    }
 }
 
 #[cfg(test)]
 mod tests {
 
     use test::{deserialize_lexer_tests, LexerTest};
-    use lexer::Lexer;
+    use lexer::{Lexer, Mode};
     use result::Result;
-    use token::{Token, TokenData};
+    use token::{Comment, CommentKind, Token, TokenData, StringLiteral, NumberLiteral, BigIntLiteral, RegExpLiteral, TemplatePart, NumberSource};
+    use word::Name;
     use std;
+    use std::borrow::Cow;
 
-    fn lex2(source: &String, operator: bool) -> Result<(Token, Token)> {
-        let chars = source.chars();
-        let mut lexer = Lexer::new(chars);
+    fn lex2(source: &String, operator: bool, mode: Mode, html_comments: bool) -> Result<(Token, Token)> {
+        let mut lexer = Lexer::new(source);
+        lexer.set_mode(mode);
+        lexer.set_html_comments(html_comments);
         Ok((lexer.read_token(operator)?, lexer.read_token(operator)?))
     }
 
@@ -794,12 +1413,133 @@ mod tests {
     #[test]
     pub fn go() {
         let tests = deserialize_lexer_tests(include_str!("../tests/unit.json"));
-        for LexerTest { source, operator, expected } in tests {
-            assert_test2(&source[..], &expected, TokenData::EOF, lex2(&source, operator));
-            assert_test2(&source[..], &expected, TokenData::EOF, lex2(&format!("{} ", source), operator));
-            assert_test2(&source[..], &expected, TokenData::EOF, lex2(&format!(" {}", source), operator));
-            assert_test2(&source[..], &expected, TokenData::EOF, lex2(&format!(" {} ", source), operator));
-            assert_test2(&source[..], &expected, TokenData::Semi, lex2(&format!("{};", source), operator));
+        for LexerTest { source, operator, mode, html_comments, expected } in tests {
+            assert_test2(&source[..], &expected, TokenData::EOF, lex2(&source, operator, mode, html_comments));
+            // Whitespace is significant between JSX tags, and `;` is not a
+            // token inside of them.
+            if mode != Mode::Normal {
+                continue;
+            }
+            assert_test2(&source[..], &expected, TokenData::EOF, lex2(&format!("{} ", source), operator, mode, html_comments));
+            assert_test2(&source[..], &expected, TokenData::Semi, lex2(&format!("{};", source), operator, mode, html_comments));
+            // A hashbang stops being a comment once anything precedes it.
+            if source.starts_with("#!") {
+                continue;
+            }
+            assert_test2(&source[..], &expected, TokenData::EOF, lex2(&format!(" {}", source), operator, mode, html_comments));
+            assert_test2(&source[..], &expected, TokenData::EOF, lex2(&format!(" {} ", source), operator, mode, html_comments));
+        }
+    }
+
+    #[test]
+    pub fn comments() {
+        let mut lexer = Lexer::new("a // one\n/* two */ b /*\n*/ <!-- three\n--> four\nc");
+        lexer.set_html_comments(true);
+        lexer.set_collect_comments(true);
+        let mut tokens = 0;
+        while lexer.read_token(false).unwrap().value != TokenData::EOF {
+            tokens += 1;
+        }
+        assert_eq!(tokens, 3);
+        let comments: Vec<(CommentKind, &str, bool)> = lexer.take_comments().into_iter()
+            .map(|Comment { kind, text, newline, .. }| (kind, text, newline))
+            .collect();
+        assert_eq!(comments, vec![
+            (CommentKind::Line, " one", true),
+            (CommentKind::Block, " two ", false),
+            (CommentKind::Block, "\n", false),
+            (CommentKind::HTMLOpen, " three", true),
+            (CommentKind::HTMLClose, " four", true)
+        ]);
+        assert!(lexer.take_comments().is_empty());
+    }
+
+    #[test]
+    pub fn byte_offsets() {
+        let mut lexer = Lexer::new("é /* ü */ ñb");
+        let first = lexer.read_token(false).unwrap();
+        let second = lexer.read_token(false).unwrap();
+        assert_eq!((first.location.start.offset, first.location.end.offset), (0, 2));
+        assert_eq!((second.location.start.offset, second.location.end.offset), (12, 15));
+        assert_eq!((second.location.start.column, second.location.end.column), (10, 12));
+        assert_eq!(lexer.text(second.location), "ñb");
+    }
+
+    #[test]
+    pub fn raw() {
+        let mut lexer = Lexer::new("'\\x41' 0XfF 1_000n /a/gi \\u0061bc");
+        let mut tokens = Vec::new();
+        loop {
+            let token = lexer.read_token(tokens.len() != 3).unwrap();
+            if token.value == TokenData::EOF {
+                break;
+            }
+            tokens.push(token.value);
+        }
+        let raws: Vec<&str> = tokens.iter().map(|value| match *value {
+            TokenData::String(StringLiteral { source: Some(ref raw), .. })
+          | TokenData::Number(NumberLiteral { raw: Some(ref raw), .. })
+          | TokenData::BigInt(BigIntLiteral { raw: Some(ref raw), .. })
+          | TokenData::RegExp(RegExpLiteral { raw: Some(ref raw), .. }) => &raw[..],
+            TokenData::Identifier(ref name) => name.raw(),
+            _ => panic!("unexpected token {:?}", value)
+        }).collect();
+        assert_eq!(raws, vec!["'\\x41'", "0XfF", "1_000n", "/a/gi", "\\u0061bc"]);
+        assert_eq!(tokens[4], TokenData::Identifier(Name::String("abc".into())));
+        assert_eq!(tokens[1], NumberSource::DecimalInt(String::from("255"), None).into_token_data());
+    }
+
+    #[test]
+    pub fn borrowed_words() {
+        let mut lexer = Lexer::new("abc \\u0061bc #d");
+        match lexer.read_token(false).unwrap().value {
+            TokenData::Identifier(Name::String(Cow::Borrowed("abc"))) => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::Identifier(Name::Escaped(Cow::Owned(ref name), Cow::Borrowed("\\u0061bc"))) if name == "abc" => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::PrivateName(Cow::Borrowed("d")) => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+    }
+
+    #[test]
+    pub fn borrowed_literals() {
+        let mut lexer = Lexer::new("'abc' 'a\\x62c' /[/]b/gi `ab` `a\\x62` `a\r\nb` `a\\\r\nb` `\\u{`");
+        match lexer.read_token(false).unwrap().value {
+            TokenData::String(StringLiteral { value: Cow::Borrowed("abc"), .. }) => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::String(StringLiteral { value: Cow::Owned(ref value), .. }) if value == "abc" => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::RegExp(RegExpLiteral { pattern: Cow::Borrowed("[/]b"), flags: Cow::Borrowed("gi"), .. }) => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::Template(TemplatePart { cooked: Some(Cow::Borrowed("ab")), raw: Cow::Borrowed("ab") }) => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::Template(TemplatePart { cooked: Some(Cow::Owned(ref cooked)), raw: Cow::Borrowed("a\\x62") }) if cooked == "ab" => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::Template(TemplatePart { cooked: Some(Cow::Owned(ref cooked)), raw: Cow::Owned(ref raw) }) if cooked == "a\nb" && raw == "a\nb" => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::Template(TemplatePart { cooked: Some(Cow::Owned(ref cooked)), raw: Cow::Owned(ref raw) }) if cooked == "ab" && raw == "a\\\nb" => {}
+            value => panic!("unexpected token {:?}", value)
+        }
+        match lexer.read_token(false).unwrap().value {
+            TokenData::Template(TemplatePart { cooked: None, raw: Cow::Borrowed("\\u{") }) => {}
+            value => panic!("unexpected token {:?}", value)
         }
     }
 
@@ -13,7 +13,10 @@ extern crate serde_json;
 pub mod word;
 pub mod token;
 pub mod lexer;
+pub mod tokenizer;
+pub mod lines;
 mod char;
+mod entities;
 mod reader;
 mod test;
 pub mod track;
@@ -21,3 +24,5 @@ pub mod error;
 pub mod result;
 
 pub use lexer::Lexer;
+pub use tokenizer::Tokenizer;
+pub use lines::LineIndex;
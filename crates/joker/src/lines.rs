@@ -0,0 +1,223 @@
+use track::{Posn, Span};
+
+// Converts between the byte offsets in `Posn`, char offsets, UTF-16 offsets
+// (used by editors and source maps) and line/column pairs. Lines end where
+// the reader ends them: at `\n`, `\r` not followed by `\n`, `\u{2028}` and
+// `\u{2029}`. Columns count chars, like `Posn::column`, except for the
+// `utf16` conversions, whose columns count UTF-16 code units.
+pub struct LineIndex<'a> {
+    source: &'a str,
+    lines: Vec<usize>, // the byte offset of the start of each line
+    chars: Vec<usize>, // the char offset of the start of each line
+    utf16: Vec<usize>  // the UTF-16 offset of the start of each line
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(source: &'a str) -> LineIndex<'a> {
+        let mut lines = vec![0];
+        let mut chars = vec![0];
+        let mut utf16 = vec![0];
+        let (mut char_count, mut utf16_count) = (0, 0);
+        let mut iter = source.char_indices().peekable();
+        while let Some((offset, ch)) = iter.next() {
+            char_count += 1;
+            utf16_count += ch.len_utf16();
+            let newline = match ch {
+                '\r' => iter.peek().map(|&(_, next)| next) != Some('\n'),
+                '\n' | '\u{2028}' | '\u{2029}' => true,
+                _ => false
+            };
+            if newline {
+                lines.push(offset + ch.len_utf8());
+                chars.push(char_count);
+                utf16.push(utf16_count);
+            }
+        }
+        LineIndex {
+            source: source,
+            lines: lines,
+            chars: chars,
+            utf16: utf16
+        }
+    }
+
+    pub fn line_count(&self) -> usize { self.lines.len() }
+
+    // byte offsets and line/column pairs
+
+    pub fn posn(&self, offset: usize) -> Posn {
+        let line = self.line(offset);
+        Posn {
+            offset: offset as u32,
+            line: line as u32,
+            column: self.source[self.lines[line]..offset].chars().count() as u32
+        }
+    }
+
+    pub fn offset(&self, line: u32, column: u32) -> Option<usize> {
+        let start = *self.lines.get(line as usize)?;
+        if column == 0 {
+            return Some(start);
+        }
+        self.source[start..self.line_end(line as usize)].char_indices()
+            .map(|(offset, ch)| start + offset + ch.len_utf8())
+            .nth(column as usize - 1)
+    }
+
+    // char offsets
+
+    pub fn char_offset(&self, offset: usize) -> usize {
+        let line = self.line(offset);
+        self.chars[line] + self.source[self.lines[line]..offset].chars().count()
+    }
+
+    pub fn offset_of_char(&self, chars: usize) -> Option<usize> {
+        let line = last_at_most(&self.chars, chars);
+        let start = self.lines[line];
+        match chars - self.chars[line] {
+            0 => Some(start),
+            n => self.source[start..].char_indices()
+                .map(|(offset, ch)| start + offset + ch.len_utf8())
+                .nth(n - 1)
+        }
+    }
+
+    // UTF-16 offsets and line/column pairs
+
+    pub fn utf16_offset(&self, offset: usize) -> usize {
+        let line = self.line(offset);
+        self.utf16[line] + utf16_len(&self.source[self.lines[line]..offset])
+    }
+
+    pub fn offset_of_utf16(&self, units: usize) -> Option<usize> {
+        let line = last_at_most(&self.utf16, units);
+        skip_utf16(self.source, self.lines[line], units - self.utf16[line])
+    }
+
+    // The line and UTF-16 column of a byte offset, as in an LSP `Position`.
+    pub fn utf16_position(&self, offset: usize) -> (u32, u32) {
+        let line = self.line(offset);
+        (line as u32, utf16_len(&self.source[self.lines[line]..offset]) as u32)
+    }
+
+    pub fn offset_of_utf16_position(&self, line: u32, column: u32) -> Option<usize> {
+        let start = *self.lines.get(line as usize)?;
+        skip_utf16(&self.source[..self.line_end(line as usize)], start, column as usize)
+    }
+
+    // The start and end of a span, as in an LSP `Range`.
+    pub fn utf16_range(&self, span: Span) -> ((u32, u32), (u32, u32)) {
+        (self.utf16_position(span.start.offset as usize), self.utf16_position(span.end.offset as usize))
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        debug_assert!(self.source.is_char_boundary(offset), "offset is not a char boundary");
+        last_at_most(&self.lines, offset)
+    }
+
+    // The end of a line, before the char that ends it. The `\r` of a `\r\n`
+    // is part of the line, as it is for the reader.
+    fn line_end(&self, line: usize) -> usize {
+        match self.lines.get(line + 1) {
+            Some(&next) => self.source[..next].char_indices().next_back().map_or(next, |(offset, _)| offset),
+            None => self.source.len()
+        }
+    }
+}
+
+// The index of the last of the ascending line starts that is at most
+// `offset`. Every line but the last ends with a terminator, so the starts
+// are distinct.
+fn last_at_most(starts: &[usize], offset: usize) -> usize {
+    match starts.binary_search(&offset) {
+        Ok(line) => line,
+        Err(next) => next - 1
+    }
+}
+
+fn utf16_len(s: &str) -> usize {
+    s.chars().map(|ch| ch.len_utf16()).sum()
+}
+
+// The byte offset `units` UTF-16 code units after `start`, if that is
+// within the source and not in the middle of a surrogate pair.
+fn skip_utf16(source: &str, start: usize, units: usize) -> Option<usize> {
+    let mut count = 0;
+    for (offset, ch) in source[start..].char_indices() {
+        if count == units {
+            return Some(start + offset);
+        }
+        if count > units {
+            return None;
+        }
+        count += ch.len_utf16();
+    }
+    if count == units { Some(source.len()) } else { None }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use lines::LineIndex;
+    use lexer::Lexer;
+    use token::TokenData;
+
+    #[test]
+    pub fn line_terminators() {
+        let source = "a\r\nb\rc\nd\u{2028}e\u{2029}f";
+        let index = LineIndex::new(source);
+        assert_eq!(index.line_count(), 6);
+        let mut lexer = Lexer::new(source);
+        loop {
+            let token = lexer.read_token(false).unwrap();
+            if token.value == TokenData::EOF {
+                break;
+            }
+            assert_eq!(index.posn(token.location.start.offset as usize), token.location.start);
+            assert_eq!(index.posn(token.location.end.offset as usize), token.location.end);
+            let start = token.location.start;
+            assert_eq!(index.offset(start.line, start.column), Some(start.offset as usize));
+        }
+        assert_eq!(index.offset(0, 2), Some(2));
+        assert_eq!(index.offset(0, 3), None);
+        assert_eq!(index.offset(1, 1), Some(4));
+        assert_eq!(index.offset(6, 0), None);
+    }
+
+    #[test]
+    pub fn utf16() {
+        let source = "é = '😀';\n😀 + x";
+        let index = LineIndex::new(source);
+        let x = source.find('x').unwrap();
+        assert_eq!(index.char_offset(x), 13);
+        assert_eq!(index.offset_of_char(13), Some(x));
+        assert_eq!(index.utf16_offset(x), 15);
+        assert_eq!(index.offset_of_utf16(15), Some(x));
+        assert_eq!(index.utf16_position(x), (1, 5));
+        assert_eq!(index.offset_of_utf16_position(1, 5), Some(x));
+        assert_eq!(index.offset_of_utf16_position(1, 1), None);
+        assert_eq!(index.offset_of_utf16_position(1, 6), Some(source.len()));
+        assert_eq!(index.offset_of_utf16_position(1, 7), None);
+        assert_eq!(index.posn(x).column, 4);
+    }
+
+    #[test]
+    pub fn offsets_on_later_lines() {
+        let source = "é\r\n😀\u{2028}a😀b\rc\n\n";
+        let index = LineIndex::new(source);
+        let (mut chars, mut units) = (0, 0);
+        for (offset, ch) in source.char_indices().chain(Some((source.len(), ' '))) {
+            assert_eq!(index.char_offset(offset), chars);
+            assert_eq!(index.offset_of_char(chars), Some(offset));
+            assert_eq!(index.utf16_offset(offset), units);
+            assert_eq!(index.offset_of_utf16(units), Some(offset));
+            chars += 1;
+            units += ch.len_utf16();
+        }
+        assert_eq!(index.offset_of_char(chars), None);
+        assert_eq!(index.offset_of_utf16(units), None);
+        let b = source.find('b').unwrap();
+        assert_eq!(index.offset_of_utf16(index.utf16_offset(b) - 1), None);
+    }
+
+}
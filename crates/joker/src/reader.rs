@@ -1,18 +1,19 @@
 use track::Posn;
 use std::collections::VecDeque;
 
-pub struct Reader {
-    chars: Vec<char>,
+// Indices are UTF-8 byte offsets into the source, like `Posn::offset`.
+pub struct Reader<'a> {
+    source: &'a str,
     ahead: VecDeque<char>,
     curr_index: usize,
     peek_index: usize,
     curr_posn: Posn
 }
 
-impl Reader {
-    pub fn new<I>(chars: I) -> Reader where I: Iterator<Item=char> {
+impl<'a> Reader<'a> {
+    pub fn new(source: &'a str) -> Reader<'a> {
         Reader {
-            chars: chars.collect(),
+            source: source,
             ahead: VecDeque::with_capacity(4),
             curr_index: 0,
             peek_index: 0,
@@ -23,13 +24,12 @@ impl Reader {
     pub fn peek(&mut self, n: usize) -> Option<char> {
         debug_assert!(n < self.ahead.capacity(), "Lookahead buffer can't hold that many items");
         for _ in self.ahead.len()..(n + 1) {
-            match self.chars.get(self.peek_index) {
+            match self.source[self.peek_index..].chars().next() {
                 Some(ch) => {
-                    // peek_index is updated after looking at the current element unlike curr_index
-                    // with updates first then read the element later. Otherwise we can't peek 0th
-                    // element
-                    self.peek_index += 1;
-                    self.ahead.push_back(*ch)
+                    // peek_index is the end of the lookahead buffer, unlike curr_index which
+                    // is the start of it.
+                    self.peek_index += ch.len_utf8();
+                    self.ahead.push_back(ch)
                 }
                 None => {
                     return None
@@ -45,6 +45,10 @@ impl Reader {
 
     pub fn peek_index(&self) -> usize { self.peek_index }
 
+    pub fn text(&self, start: usize, end: usize) -> &'a str {
+        &self.source[start..end]
+    }
+
     pub fn seek(&mut self, last_index: usize, last_posn: Posn) {
         self.curr_index = last_index;
         self.curr_posn = last_posn;
@@ -53,27 +57,26 @@ impl Reader {
     }
 }
 
-impl Iterator for Reader {
+impl<'a> Iterator for Reader<'a> {
     type Item = char;
 
     fn next(&mut self) -> Option<char> {
-        self.curr_index += 1;
-        let curr_char = self.ahead.pop_front().or_else(|| {
-            self.chars.get(self.curr_index).map(|x| *x)
-        });
+        self.peek(0)?;
+        let curr_char = self.ahead.pop_front().unwrap();
 
-        if (curr_char == Some('\r') && self.peek(0) != Some('\n')) ||
-           curr_char == Some('\n') ||
-           curr_char == Some('\u{2028}') ||
-           curr_char == Some('\u{2029}') {
+        if (curr_char == '\r' && self.peek(0) != Some('\n')) ||
+           curr_char == '\n' ||
+           curr_char == '\u{2028}' ||
+           curr_char == '\u{2029}' {
             self.curr_posn.line += 1;
             self.curr_posn.column = 0;
         } else {
             self.curr_posn.column += 1;
         }
 
-        self.curr_posn.offset += 1;
+        self.curr_index += curr_char.len_utf8();
+        self.curr_posn.offset += curr_char.len_utf8() as u32;
 
-        curr_char
+        Some(curr_char)
     }
 }
@@ -1,17 +1,21 @@
 #![cfg(test)]
 
+use std::borrow::Cow;
 use std::collections::HashSet;
 use serde::de::{Visitor, SeqVisitor, Deserialize, Deserializer, Error, Unexpected};
 use serde::de::value::SeqVisitorDeserializer;
 use serde_json::{self, Value};
 use token::*;
 use word::Name;
+use lexer::Mode;
 use std::fmt;
 
 pub struct LexerTest {
     pub source: String,
     pub operator: bool,
-    pub expected: Result<TokenData, String>
+    pub mode: Mode,
+    pub html_comments: bool,
+    pub expected: Result<TokenData<'static>, String>
 }
 
 impl Deserialize for LexerTest {
@@ -21,7 +25,7 @@ impl Deserialize for LexerTest {
             pub source: String,
             pub context: HashSet<String>,
             pub error: Option<String>,
-            pub expected: Option<TokenData>
+            pub expected: Option<TokenData<'static>>
         }
 
         let repr: Repr = Deserialize::deserialize(deserializer)?;
@@ -29,6 +33,14 @@ impl Deserialize for LexerTest {
         Ok(LexerTest {
             source: repr.source,
             operator: repr.context.contains("operator"),
+            mode: if repr.context.contains("jsx-tag") {
+                Mode::JSXTag
+            } else if repr.context.contains("jsx-child") {
+                Mode::JSXChild
+            } else {
+                Mode::Normal
+            },
+            html_comments: repr.context.contains("html-comments"),
             expected: match (repr.error, repr.expected) {
                 (Some(_), Some(_)) => {
                     return Err(D::Error::duplicate_field("error|expected"));
@@ -43,8 +55,8 @@ impl Deserialize for LexerTest {
     }
 }
 
-impl Deserialize for Name {
-    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Name, D::Error> {
+impl Deserialize for Name<'static> {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<Name<'static>, D::Error> {
         let word: String = Deserialize::deserialize(deserializer)?;
         Ok(Name::from(word))
     }
@@ -76,18 +88,18 @@ impl Deserialize for Exp {
 
 static KNOWN_TOKEN_VARIANTS: [&'static str; 4] = ["Reserved", "LBrace", "RBrace", "..."];
 
-impl Deserialize for TokenData {
-    fn deserialize<D: Deserializer>(deserializer: D) -> Result<TokenData, D::Error> {
+impl Deserialize for TokenData<'static> {
+    fn deserialize<D: Deserializer>(deserializer: D) -> Result<TokenData<'static>, D::Error> {
         struct TokenVisitor;
 
         impl Visitor for TokenVisitor {
-            type Value = TokenData;
+            type Value = TokenData<'static>;
 
             fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
                 formatter.write_str("a nonempty sequence")
             }
 
-            fn visit_seq<V: SeqVisitor>(self, mut visitor: V) -> Result<TokenData, V::Error> {
+            fn visit_seq<V: SeqVisitor>(self, mut visitor: V) -> Result<TokenData<'static>, V::Error> {
                 let ty: String = visitor.visit()?.unwrap();
 
                 let inner = SeqVisitorDeserializer::new(visitor);
@@ -118,6 +130,7 @@ impl Deserialize for TokenData {
                     "Plus"          => TokenData::Plus,
                     "Minus"         => TokenData::Minus,
                     "Star"          => TokenData::Star,
+                    "StarStar"      => TokenData::StarStar,
                     "Mod"           => TokenData::Mod,
                     "Slash"         => TokenData::Slash,
                     "Inc"           => TokenData::Inc,
@@ -133,11 +146,14 @@ impl Deserialize for TokenData {
                     "LogicalAnd"    => TokenData::LogicalAnd,
                     "LogicalOr"     => TokenData::LogicalOr,
                     "Question"      => TokenData::Question,
+                    "QuestionDot"   => TokenData::QuestionDot,
+                    "Coalesce"      => TokenData::Coalesce,
                     "Colon"         => TokenData::Colon,
                     "Assign"        => TokenData::Assign,
                     "PlusAssign"    => TokenData::PlusAssign,
                     "MinusAssign"   => TokenData::MinusAssign,
                     "StarAssign"    => TokenData::StarAssign,
+                    "StarStarAssign" => TokenData::StarStarAssign,
                     "SlashAssign"   => TokenData::SlashAssign,
                     "ModAssign"     => TokenData::ModAssign,
                     "LShiftAssign"  => TokenData::LShiftAssign,
@@ -146,7 +162,11 @@ impl Deserialize for TokenData {
                     "BitAndAssign"  => TokenData::BitAndAssign,
                     "BitOrAssign"   => TokenData::BitOrAssign,
                     "BitXorAssign"  => TokenData::BitXorAssign,
+                    "LogicalAndAssign" => TokenData::LogicalAndAssign,
+                    "LogicalOrAssign" => TokenData::LogicalOrAssign,
+                    "CoalesceAssign" => TokenData::CoalesceAssign,
                     "Arrow"         => TokenData::Arrow,
+                    "At"            => TokenData::At,
                     "EOF"           => TokenData::EOF,
                     "DecimalInt"    => {
                         let (value, exp) = Deserialize::deserialize(inner)?;
@@ -168,24 +188,55 @@ impl Deserialize for TokenData {
                         let (int, frac, exp) = Deserialize::deserialize(inner)?;
                         NumberSource::Float(int, frac, exp).into_token_data()
                     }
+                    "BigInt"        => {
+                        let (value,): (String,) = Deserialize::deserialize(inner)?;
+                        TokenData::BigInt(BigIntLiteral {
+                            source: None,
+                            raw: None,
+                            value: value
+                        })
+                    }
                     "String"        => {
                         let (value,): (String,) = Deserialize::deserialize(inner)?;
                         TokenData::String(StringLiteral {
-                            source: Some(format!("{:?}", Value::String(value.clone()))),
-                            value: value
+                            source: Some(format!("{:?}", Value::String(value.clone())).into()),
+                            value: value.into()
                         })
                     }
                     "RegExp"        => {
                         let (pattern, flags): (String, String) = Deserialize::deserialize(inner)?;
                         TokenData::RegExp(RegExpLiteral {
-                            pattern: pattern,
-                            flags: flags.chars().collect()
+                            pattern: pattern.into(),
+                            flags: flags.into(),
+                            raw: None
                         })
                     }
+                    "Template" | "TemplateHead" | "TemplateMiddle" | "TemplateTail" => {
+                        let (cooked, raw): (Option<String>, String) = Deserialize::deserialize(inner)?;
+                        let part = TemplatePart { cooked: cooked.map(Cow::from), raw: raw.into() };
+                        match &ty[..] {
+                            "Template"       => TokenData::Template(part),
+                            "TemplateHead"   => TokenData::TemplateHead(part),
+                            "TemplateMiddle" => TokenData::TemplateMiddle(part),
+                            _                => TokenData::TemplateTail(part)
+                        }
+                    }
                     "Identifier"    => {
                         let (name,) = Deserialize::deserialize(inner)?;
                         TokenData::Identifier(name)
                     },
+                    "PrivateName"   => {
+                        let (name,): (String,) = Deserialize::deserialize(inner)?;
+                        TokenData::PrivateName(name.into())
+                    },
+                    "JSXName"       => {
+                        let (name,): (String,) = Deserialize::deserialize(inner)?;
+                        TokenData::JSXName(name.into())
+                    },
+                    "JSXText"       => {
+                        let (value, raw) = Deserialize::deserialize(inner)?;
+                        TokenData::JSXText(JSXText { value: value, raw: raw })
+                    },
                     other => { return Err(V::Error::unknown_variant(other, &KNOWN_TOKEN_VARIANTS)); }
                 })
             }
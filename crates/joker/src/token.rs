@@ -1,17 +1,38 @@
+use std::borrow::Cow;
 use std::fmt;
 use std::fmt::{Debug, Formatter};
 use track::{Posn, Span, Untrack};
 use word::{Name, Reserved};
 
+// Tokens borrow the text of names, comments and whitespace from the source.
 #[derive(Debug, PartialEq, Clone)]
-pub struct Token {
+pub struct Token<'a> {
     pub location: Span,
     pub newline: bool, // was there a newline between the preceding token and this one?
-    pub value: TokenData,
+    pub value: TokenData<'a>,
 }
 
-impl Token {
-    pub fn new(start: Posn, end: Posn, value: TokenData) -> Token {
+// Comments are only collected when the lexer is asked to, since parsing
+// doesn't need them.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CommentKind {
+    Line,
+    Block,
+    HTMLOpen,  // `<!--` to the end of the line
+    HTMLClose, // `-->` at the start of a line, to the end of the line
+    Hashbang   // `#!` at the very start of the input
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Comment<'a> {
+    pub location: Span,
+    pub kind: CommentKind,
+    pub text: &'a str, // the contents, without the delimiters
+    pub newline: bool, // was there a newline between this comment and the next token or comment?
+}
+
+impl<'a> Token<'a> {
+    pub fn new(start: Posn, end: Posn, value: TokenData<'a>) -> Token<'a> {
         Token {
             location: Span {
                 start: start,
@@ -25,10 +46,18 @@ impl Token {
     pub fn len(&self) -> usize {
         (self.location.end.column - self.location.start.column) as usize
     }
+
+    pub fn into_owned(self) -> Token<'static> {
+        Token {
+            location: self.location,
+            newline: self.newline,
+            value: self.value.into_owned(),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
-pub enum TokenData {
+pub enum TokenData<'a> {
     Reserved(Reserved),
 
     // 11.7 Punctuators
@@ -53,6 +82,7 @@ pub enum TokenData {
     Plus,
     Minus,
     Star,
+    StarStar,
     Mod,
     Slash,
     Inc,
@@ -68,11 +98,14 @@ pub enum TokenData {
     LogicalAnd,
     LogicalOr,
     Question,
+    QuestionDot,
+    Coalesce,
     Colon,
     Assign,
     PlusAssign,
     MinusAssign,
     StarAssign,
+    StarStarAssign,
     SlashAssign,
     ModAssign,
     LShiftAssign,
@@ -81,33 +114,146 @@ pub enum TokenData {
     BitAndAssign,
     BitOrAssign,
     BitXorAssign,
+    LogicalAndAssign,
+    LogicalOrAssign,
+    CoalesceAssign,
     Arrow,
+    At,
+
+    Number(NumberLiteral<'a>),
+    BigInt(BigIntLiteral<'a>),
+    String(StringLiteral<'a>),
+    RegExp(RegExpLiteral<'a>),
+
+    // 11.8.6 Template Literal Lexical Components
+    Template(TemplatePart<'a>),
+    TemplateHead(TemplatePart<'a>),
+    TemplateMiddle(TemplatePart<'a>),
+    TemplateTail(TemplatePart<'a>),
+
+    Identifier(Name<'a>),
 
-    Number(NumberLiteral),
-    String(StringLiteral),
-    RegExp(RegExpLiteral),
+    // 11.7 PrivateIdentifier, without the leading `#`
+    PrivateName(Cow<'a, str>),
 
-    Identifier(Name),
+    // JSX names may contain `-`, and JSX text is only lexed between tags.
+    JSXName(Cow<'a, str>),
+    JSXText(JSXText),
+
+    // Trivia, which only a `Tokenizer` that is asked for it produces.
+    Whitespace(Cow<'a, str>),
+    Comment(CommentKind, Cow<'a, str>),
 
     EOF,
 }
 
-impl TokenData {
+fn owned(text: Cow<str>) -> Cow<'static, str> {
+    Cow::Owned(text.into_owned())
+}
+
+macro_rules! relifetime {
+    ($token:expr, [ $( $variant:ident ),* ]) => {
+        match $token {
+            $(
+                TokenData::$variant => TokenData::$variant,
+            )*
+            _ => unreachable!()
+        }
+    };
+}
+
+impl<'a> TokenData<'a> {
     pub fn is_string(&self) -> bool {
         match *self {
             TokenData::String(_) => true,
             _ => false,
         }
     }
+
+    pub fn into_owned(self) -> TokenData<'static> {
+        match self {
+            TokenData::Identifier(name) => TokenData::Identifier(name.into_owned()),
+            TokenData::PrivateName(name) => TokenData::PrivateName(owned(name)),
+            TokenData::JSXName(name) => TokenData::JSXName(owned(name)),
+            TokenData::Whitespace(text) => TokenData::Whitespace(owned(text)),
+            TokenData::Comment(kind, text) => TokenData::Comment(kind, owned(text)),
+            TokenData::Number(lit) => TokenData::Number(lit.into_owned()),
+            TokenData::BigInt(lit) => TokenData::BigInt(lit.into_owned()),
+            TokenData::String(lit) => TokenData::String(lit.into_owned()),
+            TokenData::RegExp(lit) => TokenData::RegExp(lit.into_owned()),
+            TokenData::Template(part) => TokenData::Template(part.into_owned()),
+            TokenData::TemplateHead(part) => TokenData::TemplateHead(part.into_owned()),
+            TokenData::TemplateMiddle(part) => TokenData::TemplateMiddle(part.into_owned()),
+            TokenData::TemplateTail(part) => TokenData::TemplateTail(part.into_owned()),
+            TokenData::JSXText(text) => TokenData::JSXText(text),
+            TokenData::Reserved(word) => TokenData::Reserved(word),
+            // The rest borrow nothing, so only their lifetime changes.
+            token => relifetime!(token, [
+                LBrace, RBrace, LParen, RParen, LBrack, RBrack, Dot, Ellipsis, Semi, Comma,
+                LAngle, RAngle, LEq, GEq, Eq, NEq, StrictEq, StrictNEq,
+                Plus, Minus, Star, StarStar, Mod, Slash, Inc, Dec,
+                LShift, RShift, URShift, BitAnd, BitOr, BitXor, Bang, Tilde,
+                LogicalAnd, LogicalOr, Question, QuestionDot, Coalesce, Colon,
+                Assign, PlusAssign, MinusAssign, StarAssign, StarStarAssign, SlashAssign, ModAssign,
+                LShiftAssign, RShiftAssign, URShiftAssign, BitAndAssign, BitOrAssign, BitXorAssign,
+                LogicalAndAssign, LogicalOrAssign, CoalesceAssign, Arrow, At, EOF
+            ])
+        }
+    }
+}
+
+// The cooked value is `None` when the part contains an escape sequence
+// that is only legal in a tagged template.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TemplatePart<'a> {
+    pub cooked: Option<Cow<'a, str>>,
+    pub raw: Cow<'a, str>,
+}
+
+impl<'a> TemplatePart<'a> {
+    pub fn into_owned(self) -> TemplatePart<'static> {
+        TemplatePart {
+            cooked: self.cooked.map(owned),
+            raw: owned(self.raw),
+        }
+    }
+}
+
+impl<'a> Untrack for TemplatePart<'a> {
+    fn untrack(&mut self) {}
 }
 
+// Text between JSX tags. The value has HTML character references decoded.
+#[derive(Debug, PartialEq, Clone)]
+pub struct JSXText {
+    pub value: String,
+    pub raw: String,
+}
+
+impl Untrack for JSXText {
+    fn untrack(&mut self) {}
+}
+
+// Literals keep the text they were written as, borrowed from the source,
+// which is ignored when they are compared.
 #[derive(Clone)]
-pub struct RegExpLiteral {
-    pub pattern: String,
-    pub flags: Vec<char>,
+pub struct RegExpLiteral<'a> {
+    pub pattern: Cow<'a, str>,
+    pub flags: Cow<'a, str>,
+    pub raw: Option<Cow<'a, str>>,
+}
+
+impl<'a> RegExpLiteral<'a> {
+    pub fn into_owned(self) -> RegExpLiteral<'static> {
+        RegExpLiteral {
+            pattern: owned(self.pattern),
+            flags: owned(self.flags),
+            raw: self.raw.map(owned),
+        }
+    }
 }
 
-impl Untrack for RegExpLiteral {
+impl<'a> Untrack for RegExpLiteral<'a> {
     fn untrack(&mut self) {}
 }
 
@@ -117,15 +263,15 @@ trait CharsEx {
     fn alphabetize(&self) -> Vec<char>;
 }
 
-impl CharsEx for Vec<char> {
+impl CharsEx for str {
     fn alphabetize(&self) -> Vec<char> {
-        let mut x: Vec<char> = self.to_vec();
+        let mut x: Vec<char> = self.chars().collect();
         x.sort();
         x
     }
 }
 
-impl Debug for RegExpLiteral {
+impl<'a> Debug for RegExpLiteral<'a> {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         fmt.debug_struct("RegExpLiteral")
             .field("pattern", &self.pattern)
@@ -134,23 +280,57 @@ impl Debug for RegExpLiteral {
     }
 }
 
-impl PartialEq for RegExpLiteral {
+impl<'a> PartialEq for RegExpLiteral<'a> {
     fn eq(&self, other: &Self) -> bool {
         (self.pattern == other.pattern) && (self.flags.alphabetize() == other.flags.alphabetize())
     }
 }
 
 #[derive(Clone)]
-pub struct StringLiteral {
-    pub source: Option<String>,
-    pub value: String,
+pub struct StringLiteral<'a> {
+    pub source: Option<Cow<'a, str>>,
+    pub value: Cow<'a, str>,
 }
 
-impl Untrack for StringLiteral {
+impl<'a> StringLiteral<'a> {
+    pub fn into_owned(self) -> StringLiteral<'static> {
+        StringLiteral {
+            source: self.source.map(owned),
+            value: owned(self.value),
+        }
+    }
+
+    // Octal escapes like `\012`, and the escapes `\8` and `\9`, are legacy
+    // syntax that strict code forbids.
+    pub fn has_legacy_escape(&self) -> bool {
+        let source = match self.source {
+            Some(ref source) => source,
+            None => { return false; }
+        };
+        let mut chars = source.chars().peekable();
+        while let Some(ch) = chars.next() {
+            if ch != '\\' {
+                continue;
+            }
+            match chars.next() {
+                Some('0') => {
+                    if chars.peek().map_or(false, |ch| ch.is_digit(10)) {
+                        return true;
+                    }
+                }
+                Some(ch) if ch.is_digit(10) => { return true; }
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+impl<'a> Untrack for StringLiteral<'a> {
     fn untrack(&mut self) {}
 }
 
-impl Debug for StringLiteral {
+impl<'a> Debug for StringLiteral<'a> {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         fmt.debug_struct("StringLiteral")
             .field("value", &self.value)
@@ -158,23 +338,38 @@ impl Debug for StringLiteral {
     }
 }
 
-impl PartialEq for StringLiteral {
+impl<'a> PartialEq for StringLiteral<'a> {
     fn eq(&self, other: &Self) -> bool {
         self.value == other.value
     }
 }
 
 #[derive(Clone)]
-pub struct NumberLiteral {
+pub struct NumberLiteral<'a> {
     pub source: Option<NumberSource>,
+    pub raw: Option<Cow<'a, str>>,
     pub value: f64,
 }
 
-impl Untrack for NumberLiteral {
+impl<'a> NumberLiteral<'a> {
+    pub fn into_owned(self) -> NumberLiteral<'static> {
+        NumberLiteral {
+            source: self.source,
+            raw: self.raw.map(owned),
+            value: self.value,
+        }
+    }
+
+    pub fn is_legacy(&self) -> bool {
+        self.source.as_ref().map_or(false, NumberSource::is_legacy)
+    }
+}
+
+impl<'a> Untrack for NumberLiteral<'a> {
     fn untrack(&mut self) {}
 }
 
-impl Debug for NumberLiteral {
+impl<'a> Debug for NumberLiteral<'a> {
     fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
         fmt.debug_struct("NumberLiteral")
             .field("value", &self.value)
@@ -182,7 +377,44 @@ impl Debug for NumberLiteral {
     }
 }
 
-impl PartialEq for NumberLiteral {
+impl<'a> PartialEq for NumberLiteral<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+// The value of a BigInt literal is kept as a string of decimal digits, since
+// it is arbitrarily large.
+#[derive(Clone)]
+pub struct BigIntLiteral<'a> {
+    pub source: Option<NumberSource>,
+    pub raw: Option<Cow<'a, str>>,
+    pub value: String,
+}
+
+impl<'a> BigIntLiteral<'a> {
+    pub fn into_owned(self) -> BigIntLiteral<'static> {
+        BigIntLiteral {
+            source: self.source,
+            raw: self.raw.map(owned),
+            value: self.value,
+        }
+    }
+}
+
+impl<'a> Untrack for BigIntLiteral<'a> {
+    fn untrack(&mut self) {}
+}
+
+impl<'a> Debug for BigIntLiteral<'a> {
+    fn fmt(&self, fmt: &mut Formatter) -> fmt::Result {
+        fmt.debug_struct("BigIntLiteral")
+            .field("value", &self.value)
+            .finish()
+    }
+}
+
+impl<'a> PartialEq for BigIntLiteral<'a> {
     fn eq(&self, other: &Self) -> bool {
         self.value == other.value
     }
@@ -210,6 +442,17 @@ fn format_int(src: &Option<String>) -> String {
 }
 
 impl NumberSource {
+    // Octal literals like `017` and decimal literals with a leading zero like
+    // `019` are legacy syntax that strict code forbids.
+    pub fn is_legacy(&self) -> bool {
+        match *self {
+            NumberSource::RadixInt(Radix::Oct(None), _) => true,
+            NumberSource::DecimalInt(ref mantissa, _)
+          | NumberSource::Float(Some(ref mantissa), _, _) => mantissa.len() > 1 && mantissa.starts_with('0'),
+            _ => false,
+        }
+    }
+
     pub fn value(&self) -> f64 {
         match *self {
             NumberSource::DecimalInt(ref mantissa, None) => mantissa.parse().unwrap(),
@@ -255,10 +498,46 @@ impl NumberSource {
         }
     }
 
-    pub fn into_token_data(self) -> TokenData {
+    pub fn into_token_data(self) -> TokenData<'static> {
         let value = self.value();
         TokenData::Number(NumberLiteral {
             source: Some(self),
+            raw: None,
+            value: value,
+        })
+    }
+
+    // The decimal digits of an integer source, as the value of a BigInt.
+    pub fn bigint_value(&self) -> String {
+        match *self {
+            NumberSource::DecimalInt(ref digits, None) => digits.clone(),
+            NumberSource::RadixInt(ref radix, ref src) => {
+                let radix = radix.value();
+                // Little-endian decimal digits of the value read so far.
+                let mut digits: Vec<u32> = vec![0];
+                for ch in src.chars() {
+                    let mut carry = ch.to_digit(radix).unwrap();
+                    for digit in digits.iter_mut() {
+                        let n = *digit * radix + carry;
+                        *digit = n % 10;
+                        carry = n / 10;
+                    }
+                    while carry > 0 {
+                        digits.push(carry % 10);
+                        carry /= 10;
+                    }
+                }
+                digits.iter().rev().map(|&d| ::std::char::from_digit(d, 10).unwrap()).collect()
+            }
+            _ => { panic!("BigInt literal with a non-integer source"); }
+        }
+    }
+
+    pub fn into_bigint_token_data(self) -> TokenData<'static> {
+        let value = self.bigint_value();
+        TokenData::BigInt(BigIntLiteral {
+            source: Some(self),
+            raw: None,
             value: value,
         })
     }
@@ -0,0 +1,226 @@
+use std::borrow::Cow;
+use std::collections::VecDeque;
+use track::{Posn, Span};
+use token::{Token, TokenData};
+use word::Reserved;
+use lexer::Lexer;
+use result::Result;
+
+// A stream of tokens for tools that don't parse, such as syntax
+// highlighters. Without a parser to say whether a `/` starts a regular
+// expression or divides, the previous significant token decides, the same
+// way that most standalone JavaScript tokenizers guess:
+//
+//   - `/` after an operand (a name, literal, `)`, `]` or `}`) divides;
+//   - `/` anywhere else starts a regular expression;
+//   - the `)` closing the head of an `if`, `for`, `while` or `with` is not
+//     the end of an operand, so `if (x) /re/.test(s)` is lexed correctly;
+//   - a `}` closing a block is assumed to close an expression.
+//
+// The stream ends after the last token, or after the first lexer error.
+pub struct Tokenizer<'a> {
+    lexer: Lexer<'a>,
+    trivia: bool,
+    operator: bool,      // does a `/` divide here?
+    braces: Vec<bool>,   // for each open `{` or `${`, is it a template substitution?
+    parens: Vec<bool>,   // for each open `(`, is it the head of a statement?
+    head: bool,          // was the previous token `if`, `for`, `while` or `with`?
+    end: Posn,           // the end of the last token or trivia produced
+    pending: VecDeque<Token<'a>>,
+    done: bool
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(source: &'a str) -> Tokenizer<'a> {
+        Tokenizer {
+            lexer: Lexer::new(source),
+            trivia: false,
+            operator: false,
+            braces: Vec::new(),
+            parens: Vec::new(),
+            head: false,
+            end: Posn::origin(),
+            pending: VecDeque::new(),
+            done: false
+        }
+    }
+
+    // Whitespace and comments are produced as `Whitespace` and `Comment`
+    // tokens, so that the tokens together cover the whole source.
+    pub fn set_trivia(&mut self, enabled: bool) {
+        self.trivia = enabled;
+        self.lexer.set_collect_comments(enabled);
+    }
+
+    pub fn set_html_comments(&mut self, enabled: bool) {
+        self.lexer.set_html_comments(enabled);
+    }
+
+    // The source text of a token, including any trivia tokens.
+    pub fn text(&self, location: Span) -> &'a str {
+        self.lexer.text(location)
+    }
+
+    fn read_token(&mut self) -> Result<Token<'a>> {
+        let operator = self.operator;
+        if self.lexer.peek_token(operator)?.value == TokenData::RBrace && self.braces.last() == Some(&true) {
+            self.braces.pop();
+            return self.lexer.read_template_continuation();
+        }
+        let token = self.lexer.read_token(operator)?;
+        if token.value == TokenData::RBrace {
+            self.braces.pop();
+        }
+        Ok(token)
+    }
+
+    fn advance(&mut self, token: &Token) {
+        let mut head = false;
+        self.operator = match token.value {
+            TokenData::LBrace => {
+                self.braces.push(false);
+                false
+            }
+            TokenData::TemplateHead(_)
+          | TokenData::TemplateMiddle(_) => {
+                self.braces.push(true);
+                false
+            }
+            TokenData::LParen => {
+                self.parens.push(self.head);
+                false
+            }
+            TokenData::RParen => !self.parens.pop().unwrap_or(false),
+            TokenData::Reserved(Reserved::If)
+          | TokenData::Reserved(Reserved::For)
+          | TokenData::Reserved(Reserved::While)
+          | TokenData::Reserved(Reserved::With) => {
+                head = true;
+                false
+            }
+            TokenData::Reserved(Reserved::This)
+          | TokenData::Reserved(Reserved::Super)
+          | TokenData::Reserved(Reserved::Null)
+          | TokenData::Reserved(Reserved::True)
+          | TokenData::Reserved(Reserved::False)
+          | TokenData::Identifier(_)
+          | TokenData::PrivateName(_)
+          | TokenData::Number(_)
+          | TokenData::BigInt(_)
+          | TokenData::String(_)
+          | TokenData::RegExp(_)
+          | TokenData::Template(_)
+          | TokenData::TemplateTail(_)
+          | TokenData::RBrack
+          | TokenData::RBrace
+          // `a++ / b` is far more likely than `++/re/.lastIndex`.
+          | TokenData::Inc
+          | TokenData::Dec => true,
+            _ => false
+        };
+        self.head = head;
+    }
+
+    // Queues the comments and whitespace that precede a token.
+    fn queue_trivia(&mut self, start: Posn) {
+        for comment in self.lexer.take_comments() {
+            self.queue_whitespace(comment.location.start);
+            let mut token = Token::new(comment.location.start, comment.location.end, TokenData::Comment(comment.kind, Cow::Borrowed(comment.text)));
+            token.newline = self.end.line < comment.location.start.line;
+            self.end = comment.location.end;
+            self.pending.push_back(token);
+        }
+        self.queue_whitespace(start);
+    }
+
+    fn queue_whitespace(&mut self, start: Posn) {
+        if start.offset > self.end.offset {
+            let text = self.lexer.text(Span { start: self.end, end: start });
+            let mut token = Token::new(self.end, start, TokenData::Whitespace(Cow::Borrowed(text)));
+            token.newline = self.end.line < start.line;
+            self.pending.push_back(token);
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>>;
+
+    fn next(&mut self) -> Option<Result<Token<'a>>> {
+        if let Some(token) = self.pending.pop_front() {
+            return Some(Ok(token));
+        }
+        if self.done {
+            return None;
+        }
+        let token = match self.read_token() {
+            Ok(token) => token,
+            Err(err) => {
+                self.done = true;
+                return Some(Err(err));
+            }
+        };
+        if self.trivia {
+            self.queue_trivia(token.location.start);
+        }
+        self.end = token.location.end;
+        if token.value == TokenData::EOF {
+            self.done = true;
+        } else {
+            self.advance(&token);
+            self.pending.push_back(token);
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use tokenizer::Tokenizer;
+    use token::{CommentKind, TokenData};
+
+    fn tokenize(source: &str, trivia: bool) -> Vec<TokenData> {
+        let mut tokenizer = Tokenizer::new(source);
+        tokenizer.set_trivia(trivia);
+        tokenizer.map(|token| token.unwrap().value).collect()
+    }
+
+    fn is_regexp(value: &TokenData) -> bool {
+        match *value {
+            TokenData::RegExp(_) => true,
+            _ => false
+        }
+    }
+
+    #[test]
+    pub fn regexp_or_division() {
+        let regexps = |source| tokenize(source, false).iter().filter(|value| is_regexp(value)).count();
+        assert_eq!(regexps("a / b / c"), 0);
+        assert_eq!(regexps("x = /a/g / 2"), 1);
+        assert_eq!(regexps("return /a/"), 1);
+        assert_eq!(regexps("f(x) / 2"), 0);
+        assert_eq!(regexps("if (x) /a/.test(y)"), 1);
+        assert_eq!(regexps("`${ {}.x / 2 }` / 2"), 0);
+        assert_eq!(regexps("`${a}${ /b/ }`"), 1);
+        assert_eq!(regexps("a++ / 2"), 0);
+    }
+
+    #[test]
+    pub fn trivia() {
+        let source = "#!node\na /* b */ // c\n  d";
+        assert_eq!(tokenize(source, true), vec![
+            TokenData::Comment(CommentKind::Hashbang, "node".into()),
+            TokenData::Whitespace("\n".into()),
+            tokenize("a", false).remove(0),
+            TokenData::Whitespace(" ".into()),
+            TokenData::Comment(CommentKind::Block, " b ".into()),
+            TokenData::Whitespace(" ".into()),
+            TokenData::Comment(CommentKind::Line, " c".into()),
+            TokenData::Whitespace("\n  ".into()),
+            tokenize("d", false).remove(0)
+        ]);
+        assert_eq!(tokenize("a  ", true).len(), 2);
+    }
+
+}
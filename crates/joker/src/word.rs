@@ -1,20 +1,26 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::convert::{AsRef, From};
 use token::TokenData;
+use track::Untrack;
 use result::Result;
 use error::Error;
 
-// Word with potential escape sequences
-pub struct Word {
+// Word with potential escape sequences, which borrows its text from the
+// source unless it had any.
+pub struct Word<'a> {
     had_escape: bool,
-    pub text: String,
+    pub text: Cow<'a, str>,
+    pub raw: &'a str,
 }
 
-impl Word {
+impl<'a> Word<'a> {
     pub fn new() -> Self {
         Word {
             had_escape: false,
-            text: String::new()
+            text: Cow::Borrowed(""),
+            raw: ""
         }
     }
 
@@ -130,6 +136,7 @@ impl Reserved {
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum Atom {
     Arguments,
+    As,
     Async,
     Await,
     Eval,
@@ -138,6 +145,7 @@ pub enum Atom {
     Implements,
     Interface,
     Let,
+    Meta,
     Of,
     Package,
     Private,
@@ -149,13 +157,39 @@ pub enum Atom {
     Yield
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub enum Name {
+// A name written with escapes keeps its raw spelling, which is ignored when
+// names are compared. Escaped names are never atoms, since an escaped word
+// can't be a contextual keyword. Names borrow their text from the source
+// when they can; `into_owned` detaches them from it.
+#[derive(Debug, Clone)]
+pub enum Name<'a> {
     Atom(Atom),
-    String(String)
+    String(Cow<'a, str>),
+    Escaped(Cow<'a, str>, Cow<'a, str>) // the name, and its raw spelling
 }
 
-impl Name {
+impl<'a, 'b> PartialEq<Name<'b>> for Name<'a> {
+    fn eq(&self, other: &Name<'b>) -> bool {
+        match (self, other) {
+            (&Name::Atom(a), &Name::Atom(b)) => a == b,
+            (&Name::Atom(_), _) | (_, &Name::Atom(_)) => false,
+            _ => self.as_ref() == other.as_ref()
+        }
+    }
+}
+
+impl<'a> Eq for Name<'a> { }
+
+impl<'a> Hash for Name<'a> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match *self {
+            Name::Atom(atom) => atom.hash(state),
+            _ => self.as_ref().hash(state)
+        }
+    }
+}
+
+impl<'a> Name<'a> {
     pub fn atom(&self) -> Option<Atom> {
         match *self {
             Name::Atom(ref atom) => Some(*atom),
@@ -165,25 +199,48 @@ impl Name {
 
     pub fn into_string(self) -> String {
         match self {
-            Name::Atom(atom) => atom.name().to_string(),
-            Name::String(s)  => s
+            Name::Atom(atom)       => atom.name().to_string(),
+            Name::String(s)        => s.into_owned(),
+            Name::Escaped(s, _)    => s.into_owned()
+        }
+    }
+
+    pub fn into_owned(self) -> Name<'static> {
+        match self {
+            Name::Atom(atom)       => Name::Atom(atom),
+            Name::String(s)        => Name::String(Cow::Owned(s.into_owned())),
+            Name::Escaped(s, raw)  => Name::Escaped(Cow::Owned(s.into_owned()), Cow::Owned(raw.into_owned()))
+        }
+    }
+
+    // The name as it was written.
+    pub fn raw(&self) -> &str {
+        match *self {
+            Name::Escaped(_, ref raw) => raw,
+            _ => self.as_ref()
         }
     }
 }
 
-impl AsRef<str> for Name {
+impl<'a> AsRef<str> for Name<'a> {
     fn as_ref(&self) -> &str {
         match self {
-            &Name::Atom(ref atom) => atom.name(),
-            &Name::String(ref s)  => s.as_ref()
+            &Name::Atom(ref atom)       => atom.name(),
+            &Name::String(ref s)        => s.as_ref(),
+            &Name::Escaped(ref s, _)    => s.as_ref()
         }
     }
 }
 
-impl From<String> for Name {
-    fn from(s: String) -> Name {
+impl<'a> Untrack for Name<'a> {
+    fn untrack(&mut self) {}
+}
+
+impl From<String> for Name<'static> {
+    fn from(s: String) -> Name<'static> {
         match &s[..] {
             "arguments"  => Name::Atom(Atom::Arguments),
+            "as"         => Name::Atom(Atom::As),
             "await"      => Name::Atom(Atom::Await),
             "eval"       => Name::Atom(Atom::Eval),
             "async"      => Name::Atom(Atom::Async),
@@ -192,6 +249,7 @@ impl From<String> for Name {
             "implements" => Name::Atom(Atom::Implements),
             "interface"  => Name::Atom(Atom::Interface),
             "let"        => Name::Atom(Atom::Let),
+            "meta"       => Name::Atom(Atom::Meta),
             "of"         => Name::Atom(Atom::Of),
             "package"    => Name::Atom(Atom::Package),
             "private"    => Name::Atom(Atom::Private),
@@ -201,7 +259,7 @@ impl From<String> for Name {
             "static"     => Name::Atom(Atom::Static),
             "target"     => Name::Atom(Atom::Target),
             "yield"      => Name::Atom(Atom::Yield),
-            _            => Name::String(s)
+            _            => Name::String(Cow::Owned(s))
         }
     }
 }
@@ -210,6 +268,7 @@ impl Atom {
     pub fn name(self) -> &'static str {
         match self {
             Atom::Arguments  => "arguments",
+            Atom::As         => "as",
             Atom::Await      => "await",
             Atom::Eval       => "eval",
             Atom::Async      => "async",
@@ -218,6 +277,7 @@ impl Atom {
             Atom::Implements => "implements",
             Atom::Interface  => "interface",
             Atom::Let        => "let",
+            Atom::Meta       => "meta",
             Atom::Of         => "of",
             Atom::Package    => "package",
             Atom::Private    => "private",
@@ -288,18 +348,20 @@ impl Map {
                 ("public",     Public),     ("static",     Static),
 
                 // Purely contextual identifier names
-                ("async",      Async),      ("from",       From),       ("of",       Of),
-                ("get",        Get),        ("set",        Set),        ("target",   Target)
+                ("as",         As),         ("async",      Async),      ("from",     From),
+                ("of",         Of),         ("get",        Get),        ("set",      Set),
+                ("target",     Target),     ("meta",       Meta)
             ])
         }
     }
 
-    pub fn tokenize(&self, s: Word) -> Result<TokenData> {
+    pub fn tokenize<'a>(&self, s: Word<'a>) -> Result<TokenData<'a>> {
         Ok(match self.reserved.get(&s.text[..]) {
             Some(&word) if !s.had_escape() => TokenData::Reserved(word),
             Some(&word) => return Err(Error::ReservedWordWithEscapes(word)),
             None => match self.contextual.get(&s.text[..]) {
                 Some(&atom) if !s.had_escape() => TokenData::Identifier(Name::Atom(atom)),
+                _ if s.had_escape() => TokenData::Identifier(Name::Escaped(s.text, Cow::Borrowed(s.raw))),
                 _ => TokenData::Identifier(Name::String(s.text))
             }
         })
@@ -17,8 +17,8 @@ extern crate unjson;
 
 use easter::expr::{Expr, Assign};
 use easter::stmt::{Stmt, StmtListItem};
-use esprit::script;
-use estree::IntoScript;
+use esprit::{script, Parser};
+use estree::{IntoScript, IntoModule};
 use glob::glob;
 use joker::track::Untrack;
 use serde_json::value::Value;
@@ -26,6 +26,7 @@ use std::fs::{File, read_dir};
 use std::io::Read;
 use std::path::Path;
 use std::env;
+use std::fmt::Debug;
 use test::{TestDesc, TestDescAndFn, TestName, TestFn, test_main};
 use test::ShouldPanic::No;
 use unjson::{ExtractField, Unjson};
@@ -73,6 +74,13 @@ fn unit_tests(target: &mut Vec<TestDescAndFn>) {
         .map(|s| glob::Pattern::new(s).unwrap())
         .collect();
 
+    let annexb: Vec<_> =
+        include_str!(".annexb")
+        .lines()
+        .filter(|s| !s.is_empty() && !s.starts_with("#"))
+        .map(|s| glob::Pattern::new(s).unwrap())
+        .collect();
+
     let files =
         read_dir(fixtures.as_path()).unwrap()
         .flat_map(|dir| glob(&format!("{}/**/*.tree.json", dir.unwrap().path().to_str().unwrap())).unwrap())
@@ -84,49 +92,69 @@ fn unit_tests(target: &mut Vec<TestDescAndFn>) {
                 tree_path.with_file_name(source_file_name)
             };
             if source_path.exists() {
-                let ignore = {
-                    let local_test_path = source_path.strip_prefix(&fixtures).unwrap().with_extension("");
-                    testignore.iter().any(|ignore| ignore.matches_path(&local_test_path))
-                };
-                Some((tree_path, source_path, ignore))
+                let local_test_path = source_path.strip_prefix(&fixtures).unwrap().with_extension("");
+                let ignore = testignore.iter().any(|ignore| ignore.matches_path(&local_test_path));
+                let annex_b = annexb.iter().any(|pattern| pattern.matches_path(&local_test_path));
+                let jsx = source_path.strip_prefix(&fixtures).unwrap().starts_with("JSX");
+                Some((tree_path, source_path, ignore, jsx, annex_b))
             } else {
                 None
             }
         });
 
-    for (tree_path, source_path, ignore) in files {
+    for (tree_path, source_path, ignore, jsx, annex_b) in files {
         add_test(target, source_path.strip_prefix(&root).unwrap().to_str().unwrap().to_string(), ignore, move || {
             let v: Value = serde_json::de::from_reader(File::open(tree_path).unwrap()).unwrap();
             let mut obj = v.into_object().unwrap();
+            let is_module = source_path.to_str().unwrap().ends_with(".module.js") ||
+                            obj.get("sourceType").and_then(|ty| ty.as_str()) == Some("module");
             let expected = match obj.extract_array("errors") {
                 Ok(errors) => {
                     Err(errors[0].as_object().unwrap()["message"].clone())
                 }
-                Err(unjson::error::Error::MissingField(_)) => {
-                    Ok(obj.into_script().map_err(|err| {
-                        format!("failed to deserialize script: {}", err)
-                    }).unwrap())
-                }
+                Err(unjson::error::Error::MissingField(_)) => Ok(obj),
                 Err(err) => panic!(err)
             };
             let mut source = String::new();
             File::open(source_path).unwrap().read_to_string(&mut source).unwrap();
-            match (script(&source[..]), expected) {
-                (Ok(mut actual_ast), expected) => {
-                    actual_ast.untrack();
-                    assert!(Ok(&actual_ast) == expected.as_ref(), "unit test got wrong result\n\
-                    expected: {:#?}\n\
-                    actual AST: {:#?}", expected, actual_ast);
-                }
-                (Err(actual_err), Ok(_)) => {
-                    panic!("unit test failed to parse:\n{:#?}", actual_err);
-                }
-                (Err(_), Err(_)) => {}
+            let mut parser = Parser::from_source(&source);
+            parser.jsx = jsx;
+            parser.annex_b = annex_b;
+            if is_module {
+                check_result(parser.module(), expected.map(|obj| {
+                    obj.into_module().map_err(|err| {
+                        format!("failed to deserialize module: {}", err)
+                    }).unwrap()
+                }));
+            } else {
+                check_result(parser.script(false), expected.map(|obj| {
+                    obj.into_script().map_err(|err| {
+                        format!("failed to deserialize script: {}", err)
+                    }).unwrap()
+                }));
             }
         });
     }
 }
 
+fn check_result<T, E>(actual: esprit::result::Result<T>, expected: Result<T, E>)
+    where T: Untrack + PartialEq + Debug,
+          E: PartialEq + Debug
+{
+    match (actual, expected) {
+        (Ok(mut actual_ast), expected) => {
+            actual_ast.untrack();
+            assert!(Ok(&actual_ast) == expected.as_ref(), "unit test got wrong result\n\
+            expected: {:#?}\n\
+            actual AST: {:#?}", expected, actual_ast);
+        }
+        (Err(actual_err), Ok(_)) => {
+            panic!("unit test failed to parse:\n{:#?}", actual_err);
+        }
+        (Err(_), Err(_)) => {}
+    }
+}
+
 fn main() {
     let args: Vec<_> = env::args().collect();
     let mut tests = Vec::new();